@@ -0,0 +1,78 @@
+use crate::build;
+use maze;
+use rand::Rng;
+
+//
+// Data only maze generator
+//
+
+pub fn generate_maze(monitor: monitor::MazeReceiver) {
+    let mut lk = match monitor.solver.lock() {
+        Ok(l) => l,
+        Err(_) => print::maze_panic!("uncontested lock failure"),
+    };
+    build::fill_maze_with_walls(&mut lk.maze);
+    let mut gen = build::seeded_rng(&lk.maze);
+    let mut cur: maze::Point = maze::Point {
+        row: 2 * (gen.gen_range(1..lk.maze.rows() - 2) / 2) + 1,
+        col: 2 * (gen.gen_range(1..lk.maze.cols() - 2) / 2) + 1,
+    };
+    build::build_path(&mut lk.maze, cur);
+    *lk.maze.get_mut(cur.row, cur.col) |= build::BUILDER_BIT;
+    let mut unvisited = cell_count(&lk.maze) - 1;
+    while unvisited > 0 {
+        let next = random_step(&lk.maze, cur, &mut gen);
+        if build::can_build_new_square(&lk.maze, next) {
+            build::join_squares(&mut lk.maze, cur, next);
+            unvisited -= 1;
+        }
+        cur = next;
+    }
+}
+
+//
+// History based generator for animation and playback.
+//
+
+pub fn generate_history(monitor: monitor::MazeMonitor) {
+    let mut lk = match monitor.lock() {
+        Ok(l) => l,
+        Err(_) => print::maze_panic!("builder could not take lock"),
+    };
+    build::fill_maze_history_with_walls(&mut lk.maze);
+    let mut gen = build::seeded_rng(&lk.maze);
+    let mut cur: maze::Point = maze::Point {
+        row: 2 * (gen.gen_range(1..lk.maze.rows() - 2) / 2) + 1,
+        col: 2 * (gen.gen_range(1..lk.maze.cols() - 2) / 2) + 1,
+    };
+    build::build_path_history(&mut lk.maze, cur);
+    *lk.maze.get_mut(cur.row, cur.col) |= build::BUILDER_BIT;
+    let mut unvisited = cell_count(&lk.maze) - 1;
+    // The walk itself leaves no trace. Only first visits break walls so only they are recorded.
+    while unvisited > 0 {
+        let next = random_step(&lk.maze, cur, &mut gen);
+        if build::can_build_new_square(&lk.maze, next) {
+            build::join_squares_history(&mut lk.maze, cur, next);
+            unvisited -= 1;
+        }
+        cur = next;
+    }
+}
+
+fn cell_count(maze: &maze::Maze) -> usize {
+    ((maze.rows() / 2) * (maze.cols() / 2)) as usize
+}
+
+// Any neighbor within the perimeter is fair game, visited or not. That is the whole algorithm.
+fn random_step(maze: &maze::Maze, cur: maze::Point, gen: &mut impl Rng) -> maze::Point {
+    loop {
+        let direction = &build::GENERATE_DIRECTIONS[gen.gen_range(0..build::NUM_DIRECTIONS)];
+        let next = maze::Point {
+            row: cur.row + direction.row,
+            col: cur.col + direction.col,
+        };
+        if build::is_square_within_perimeter_walls(maze, next) {
+            return next;
+        }
+    }
+}
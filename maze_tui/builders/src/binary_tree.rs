@@ -0,0 +1,61 @@
+use crate::build;
+use maze;
+use rand::seq::SliceRandom;
+
+// Binary trees only ever carve toward the north east corner.
+const NORTH: maze::Point = maze::Point { row: -2, col: 0 };
+const EAST: maze::Point = maze::Point { row: 0, col: 2 };
+
+//
+// Data only maze generator
+//
+
+pub fn generate_maze(monitor: monitor::MazeReceiver) {
+    let mut lk = match monitor.solver.lock() {
+        Ok(l) => l,
+        Err(_) => print::maze_panic!("uncontested lock failure"),
+    };
+    build::fill_maze_with_walls(&mut lk.maze);
+    let mut gen = build::seeded_rng(&lk.maze);
+    for row in (1..lk.maze.rows() - 1).step_by(2) {
+        for col in (1..lk.maze.cols() - 1).step_by(2) {
+            let cur = maze::Point { row, col };
+            if let Some(next) = choices(&lk.maze, cur).choose(&mut gen) {
+                build::join_squares(&mut lk.maze, cur, *next);
+            }
+        }
+    }
+}
+
+//
+// History based generator for animation and playback.
+//
+
+pub fn generate_history(monitor: monitor::MazeMonitor) {
+    let mut lk = match monitor.lock() {
+        Ok(l) => l,
+        Err(_) => print::maze_panic!("builder could not take lock"),
+    };
+    build::fill_maze_history_with_walls(&mut lk.maze);
+    let mut gen = build::seeded_rng(&lk.maze);
+    for row in (1..lk.maze.rows() - 1).step_by(2) {
+        for col in (1..lk.maze.cols() - 1).step_by(2) {
+            let cur = maze::Point { row, col };
+            if let Some(next) = choices(&lk.maze, cur).choose(&mut gen) {
+                build::join_squares_history(&mut lk.maze, cur, *next);
+            }
+        }
+    }
+}
+
+// The north east corner has no choices but its neighbors will always reach it.
+fn choices(maze: &maze::Maze, cur: maze::Point) -> Vec<maze::Point> {
+    [NORTH, EAST]
+        .iter()
+        .map(|d| maze::Point {
+            row: cur.row + d.row,
+            col: cur.col + d.col,
+        })
+        .filter(|&next| build::is_square_within_perimeter_walls(maze, next))
+        .collect()
+}
@@ -1,13 +1,14 @@
 use maze;
 use print;
 use print::maze_panic;
+use rand::rngs::StdRng;
 use ratatui::{
     buffer::Cell,
     style::{Color as RatColor, Modifier},
 };
 
 pub type SpeedUnit = u64;
-pub type BacktrackMarker = u32;
+pub type BacktrackMarker = maze::Square;
 
 #[derive(PartialEq, Eq)]
 pub enum ParityPoint {
@@ -85,10 +86,18 @@ pub const GENERATE_DIRECTIONS: [maze::Point; 4] = [
     maze::Point { row: 0, col: -2 },
 ];
 
-// Control the speed steps of animation in microseconds here.
-pub const BUILDER_SPEEDS: [SpeedUnit; 8] = [0, 5000, 2500, 1000, 500, 250, 100, 1];
+// The render tick of each -ba level in microseconds. Nothing picks level 0 so levels index the
+// table directly and level 4 matches the tick every animation starts at.
+pub const BUILDER_SPEEDS: [SpeedUnit; 8] = [
+    0, 2_000_000, 1_000_000, 500_000, 250_000, 50_000, 10_000, 1_000,
+];
+
+// MAZE BOUNDS CHECKING AND HELPERS-------------------------------------------------------
 
-/// MAZE BOUNDS CHECKING AND HELPERS-------------------------------------------------------
+// Builders draw every random choice from the maze seed so the same seed rebuilds the same maze.
+pub fn seeded_rng(maze: &maze::Maze) -> StdRng {
+    maze.seeded_rng(0)
+}
 
 pub fn reset_build(maze: &mut maze::Maze) {
     maze.as_slice_mut().fill(0b0)
@@ -98,7 +107,9 @@ pub fn choose_arbitrary_point(maze: &maze::Maze, parity: ParityPoint) -> Option<
     let init = if parity == ParityPoint::Even { 2 } else { 1 };
     for r in (init..maze.rows() - 1).step_by(2) {
         for c in (init..maze.cols() - 1).step_by(2) {
-            if (maze.get(r, c) & BUILDER_BIT) == 0 {
+            if (maze.get(r, c) & BUILDER_BIT) == 0
+                && !maze.is_masked(maze::Point { row: r, col: c })
+            {
                 return Some(maze::Point { row: r, col: c });
             }
         }
@@ -117,7 +128,9 @@ pub fn choose_point_from_row_start(
     }
     for r in (row_start..maze.rows() - 1).step_by(2) {
         for c in (init..maze.cols() - 1).step_by(2) {
-            if (maze.get(r, c) & BUILDER_BIT) == 0 {
+            if (maze.get(r, c) & BUILDER_BIT) == 0
+                && !maze.is_masked(maze::Point { row: r, col: c })
+            {
                 return Some(maze::Point { row: r, col: c });
             }
         }
@@ -132,6 +145,25 @@ pub fn can_build_new_square(maze: &maze::Maze, next: maze::Point) -> bool {
         && next.col > 0
         && next.col < maze.cols() - 1
         && (maze.get(next.row, next.col) & BUILDER_BIT) == 0
+        && !maze.is_masked(next)
+}
+
+// Builders pick their start before looking at the maze so a masked start moves to the nearest
+// open cell instead. Without a mask the start is returned untouched.
+pub fn unmasked_start(maze: &maze::Maze, start: maze::Point) -> maze::Point {
+    if !maze.is_masked(start) {
+        return start;
+    }
+    (1..maze.rows() - 1)
+        .step_by(2)
+        .flat_map(|row| {
+            (1..maze.cols() - 1)
+                .step_by(2)
+                .map(move |col| maze::Point { row, col })
+        })
+        .filter(|&p| !maze.is_masked(p))
+        .min_by_key(|p| (p.row - start.row).abs() + (p.col - start.col).abs())
+        .unwrap_or(start)
 }
 
 #[inline]
@@ -159,9 +191,9 @@ fn get_mark(square: maze::Square) -> BacktrackSymbol {
     BACKTRACKING_SYMBOLS[(square & MARKERS_MASK) as usize]
 }
 
-/// WALL ADDER HELPERS-------------------------------------------------------------------
+// WALL ADDER HELPERS-------------------------------------------------------------------
 
-/// Data Driven functions no IO or animation concerns.
+// Data Driven functions no IO or animation concerns.
 
 pub fn build_wall_outline(maze: &mut maze::Maze) {
     for r in 0..maze.rows() {
@@ -215,11 +247,11 @@ pub fn carve_path_walls(maze: &mut maze::Maze, p: maze::Point) {
     }
 }
 
-/// PATH CARVING HELPERS-------------------------------------------------------------------
+// PATH CARVING HELPERS-------------------------------------------------------------------
 
-///
-/// Data Driven with no IO or animation.
-///
+//
+// Data Driven with no IO or animation.
+//
 
 pub fn fill_maze_with_walls(maze: &mut maze::Maze) {
     for r in 0..maze.rows() {
@@ -290,7 +322,47 @@ pub fn join_squares(maze: &mut maze::Maze, cur: maze::Point, next: maze::Point)
     *maze.get_mut(next.row, next.col) |= BUILDER_BIT;
 }
 
+// Cells of any topology open every square of their body and remember they were built.
+pub fn carve_cell(maze: &mut maze::Maze, cell: maze::Point) {
+    for p in maze.topology().body(maze.rows(), maze.cols(), cell) {
+        if maze.wall_at(p.row, p.col) {
+            build_path(maze, p);
+        }
+    }
+    *maze.get_mut(cell.row, cell.col) |= BUILDER_BIT;
+}
+
+pub fn join_cells(maze: &mut maze::Maze, cur: maze::Point, link: maze::topology::Link) {
+    carve_cell(maze, cur);
+    build_path(maze, link.wall);
+    carve_cell(maze, link.next);
+}
+
+// A crossing carries one passage over a cell while another runs underneath it along dir. The walls
+// on either side of the crossing become tunnels and the cells past them are the passage exits.
+pub fn carve_under_passage(maze: &mut maze::Maze, cross: maze::Point, dir: maze::Point) {
+    for side in [1, -1] {
+        let exit = maze::Point {
+            row: cross.row + dir.row * side * 2,
+            col: cross.col + dir.col * side * 2,
+        };
+        build_path(maze, exit);
+        *maze.get_mut(exit.row, exit.col) |= BUILDER_BIT;
+        *maze.get_mut(cross.row + dir.row * side, cross.col + dir.col * side) |= maze::TUNNEL_BIT;
+    }
+}
+
+// Masked squares are never carved so modifications that cut across the grid keep a mask's shape.
+// Carving into a tunnel flattens its crossing so the passage underneath stays connected.
 pub fn build_path(maze: &mut maze::Maze, p: maze::Point) {
+    if maze.is_masked(p) {
+        return;
+    }
+    let partner = if maze::is_tunnel(maze.get(p.row, p.col)) {
+        maze.tunnel_partner(p)
+    } else {
+        None
+    };
     if p.row > 0 {
         *maze.get_mut(p.row - 1, p.col) &= !maze::SOUTH_WALL;
     }
@@ -304,7 +376,10 @@ pub fn build_path(maze: &mut maze::Maze, p: maze::Point) {
         *maze.get_mut(p.row, p.col + 1) &= !maze::WEST_WALL;
     }
     let square = maze.get(p.row, p.col);
-    *maze.get_mut(p.row, p.col) = (square & !maze::WALL_MASK) | maze::PATH_BIT;
+    *maze.get_mut(p.row, p.col) = (square & !(maze::WALL_MASK | maze::TUNNEL_BIT)) | maze::PATH_BIT;
+    if let Some(q) = partner {
+        build_path(maze, q);
+    }
 }
 
 pub fn build_wall(maze: &mut maze::Maze, p: maze::Point) {
@@ -346,9 +421,9 @@ pub fn build_wall_carefully(maze: &mut maze::Maze, p: maze::Point) {
     *maze.get_mut(p.row, p.col) &= !maze::PATH_BIT;
 }
 
-///
-/// History tracking for playback and animation.
-///
+//
+// History tracking for playback and animation.
+//
 
 pub fn fill_maze_history_with_walls(maze: &mut maze::Maze) {
     for r in 0..maze.rows() {
@@ -611,6 +686,46 @@ pub fn join_squares_history(maze: &mut maze::Maze, cur: maze::Point, next: maze:
     *maze.get_mut(next.row, next.col) |= BUILDER_BIT;
 }
 
+pub fn carve_cell_history(maze: &mut maze::Maze, cell: maze::Point) {
+    for p in maze.topology().body(maze.rows(), maze.cols(), cell) {
+        if maze.wall_at(p.row, p.col) {
+            build_path_history(maze, p);
+        }
+    }
+    *maze.get_mut(cell.row, cell.col) |= BUILDER_BIT;
+}
+
+pub fn join_cells_history(maze: &mut maze::Maze, cur: maze::Point, link: maze::topology::Link) {
+    carve_cell_history(maze, cur);
+    build_path_history(maze, link.wall);
+    carve_cell_history(maze, link.next);
+}
+
+pub fn carve_under_passage_history(maze: &mut maze::Maze, cross: maze::Point, dir: maze::Point) {
+    let mut tunnels = [maze::Delta::default(); 2];
+    for (i, side) in [1, -1].into_iter().enumerate() {
+        let exit = maze::Point {
+            row: cross.row + dir.row * side * 2,
+            col: cross.col + dir.col * side * 2,
+        };
+        build_path_history(maze, exit);
+        *maze.get_mut(exit.row, exit.col) |= BUILDER_BIT;
+        let tunnel = maze::Point {
+            row: cross.row + dir.row * side,
+            col: cross.col + dir.col * side,
+        };
+        let before = maze.get(tunnel.row, tunnel.col);
+        tunnels[i] = maze::Delta {
+            id: tunnel,
+            before,
+            after: before | maze::TUNNEL_BIT,
+            burst: 2,
+        };
+        *maze.get_mut(tunnel.row, tunnel.col) = before | maze::TUNNEL_BIT;
+    }
+    maze.build_history.push_burst(&tunnels);
+}
+
 pub fn build_wall_history(maze: &mut maze::Maze, p: maze::Point) {
     let mut wall: maze::WallLine = 0b0;
     if p.row > 0 {
@@ -712,16 +827,25 @@ pub fn build_wall_history_carefully(maze: &mut maze::Maze, p: maze::Point) -> us
 }
 
 pub fn build_path_history(maze: &mut maze::Maze, p: maze::Point) -> usize {
+    if maze.is_masked(p) {
+        return 0;
+    }
     let mut wall_changes = [maze::Delta::default(); 5];
     let mut burst = 1;
     let mut square = maze.get(p.row, p.col);
+    let partner = if maze::is_tunnel(square) {
+        maze.tunnel_partner(p)
+    } else {
+        None
+    };
+    let after = (square & !(maze::WALL_MASK | maze::TUNNEL_BIT)) | maze::PATH_BIT;
     wall_changes[0] = maze::Delta {
         id: p,
         before: square,
-        after: (square & !maze::WALL_MASK) | maze::PATH_BIT,
+        after,
         burst,
     };
-    *maze.get_mut(p.row, p.col) = (square & !maze::WALL_MASK) | maze::PATH_BIT;
+    *maze.get_mut(p.row, p.col) = after;
     if p.row > 0 {
         square = maze.get(p.row - 1, p.col);
         wall_changes[burst] = maze::Delta {
@@ -780,19 +904,22 @@ pub fn build_path_history(maze: &mut maze::Maze, p: maze::Point) -> usize {
     }
     wall_changes[0].burst = burst;
     maze.build_history.push_burst(&wall_changes[0..burst]);
-    burst
+    match partner {
+        Some(q) => burst + build_path_history(maze, q),
+        None => burst,
+    }
 }
 
-///
-/// Display Code: This section is concerned with the lower level details of how elements are
-/// displayed to the screen. For the printers that means interacting with Crossterm's print
-/// and cursor movement capabilities. For the TUI that means choosing how to decode every possible
-/// maze square and display it as a ratatui.rs buffer Cell.
-///
+//
+// Display Code: This section is concerned with the lower level details of how elements are
+// displayed to the screen. For the printers that means interacting with Crossterm's print
+// and cursor movement capabilities. For the TUI that means choosing how to decode every possible
+// maze square and display it as a ratatui.rs buffer Cell.
+//
 
-///
-/// TUI rendering helpers for decoding maze squares into Cells.
-///
+//
+// TUI rendering helpers for decoding maze squares into Cells.
+//
 
 pub fn decode_square(wall_row: &[char], square: maze::Square) -> Cell {
     if is_marked(square) {
@@ -805,6 +932,15 @@ pub fn decode_square(wall_row: &[char], square: maze::Square) -> Cell {
             modifier: Modifier::BOLD,
             skip: false,
         }
+    } else if maze::is_tunnel(square) {
+        Cell {
+            symbol: maze::tunnel_char(square).to_string(),
+            fg: RatColor::Reset,
+            bg: RatColor::Reset,
+            underline_color: RatColor::Reset,
+            modifier: Modifier::empty(),
+            skip: false,
+        }
     } else if maze::is_wall(square) {
         Cell {
             symbol: wall_row[((square & maze::WALL_MASK) >> maze::WALL_SHIFT) as usize].to_string(),
@@ -942,3 +1078,37 @@ pub fn flush_bit_vals(maze: &maze::Maze) {
         eprintln!();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn masked_maze(art: &str) -> maze::Maze {
+        let mut maze = maze::Maze::new(maze::MazeArgs {
+            odd_rows: 15,
+            odd_cols: 31,
+            ..Default::default()
+        });
+        maze.set_mask(&maze::mask::from_ascii(art).unwrap());
+        maze
+    }
+
+    #[test]
+    fn unmasked_start_keeps_a_start_already_inside() {
+        let maze = masked_maze("###\n###\n###");
+        let start = maze::Point { row: 7, col: 15 };
+        assert_eq!(unmasked_start(&maze, start), start);
+    }
+
+    #[test]
+    fn unmasked_start_lands_inside_the_mask() {
+        let maze = masked_maze("......##\n......##\n......##\n......##");
+        for row in (1..maze.rows() - 1).step_by(2) {
+            for col in (1..maze.cols() - 1).step_by(2) {
+                let start = unmasked_start(&maze, maze::Point { row, col });
+                assert!(!maze.is_masked(start));
+                assert!(start.row % 2 == 1 && start.col % 2 == 1);
+            }
+        }
+    }
+}
@@ -0,0 +1,411 @@
+use crate::build;
+use maze;
+use rand::{rngs::StdRng, Rng};
+use std::collections::VecDeque;
+
+const RULE_NAMES: [(&str, &str); 3] = [
+    ("maze", "B3/S12345"),
+    ("mazectric", "B3/S1234"),
+    ("cave", "B5678/S45678"),
+];
+const DEFAULT_RULE: &str = "B3/S12345";
+const MAX_GENERATIONS: usize = 32;
+const SEED_WALL_CHANCE: f64 = 0.5;
+pub(crate) const MOORE_NEIGHBORHOOD: [maze::Point; 8] = [
+    maze::Point { row: -1, col: -1 },
+    maze::Point { row: -1, col: 0 },
+    maze::Point { row: -1, col: 1 },
+    maze::Point { row: 0, col: -1 },
+    maze::Point { row: 0, col: 1 },
+    maze::Point { row: 1, col: -1 },
+    maze::Point { row: 1, col: 0 },
+    maze::Point { row: 1, col: 1 },
+];
+
+// Indexed by the number of wall neighbors a square has in its Moore neighborhood.
+#[derive(Clone, Copy, Debug)]
+pub struct Rule {
+    birth: [bool; 9],
+    survive: [bool; 9],
+}
+
+//
+// Caves are grown rather than carved. Walls are the live cells of a cellular automaton and every
+// square of the grid, not just those on odd coordinates, may become a wall or a path. A wall is
+// born on a path square with a birth count of wall neighbors and survives with a survival count.
+// The automaton has no idea what a maze is so it often leaves pockets of open floor sealed off
+// from one another. Once it settles we tunnel from the largest open region to every other one so
+// any two path squares can still be reached by the solvers. Rules may be named or written in the
+// usual B/S notation such as caves:B3/S1234.
+//
+
+pub fn parse_rule(arg: &str) -> Result<Rule, String> {
+    let notation = match RULE_NAMES.iter().find(|(name, _)| *name == arg) {
+        Some((_, notation)) => *notation,
+        None => arg,
+    };
+    let upper = notation.to_ascii_uppercase();
+    let (birth, survive) = match upper.split_once('/') {
+        Some((b, s)) => match (b.strip_prefix('B'), s.strip_prefix('S')) {
+            (Some(b), Some(s)) => (b, s),
+            _ => return Err(format!("invalid cave rule[{}]", arg)),
+        },
+        None => return Err(format!("invalid cave rule[{}]", arg)),
+    };
+    let counts = |digits: &str| -> Result<[bool; 9], String> {
+        let mut counts = [false; 9];
+        for d in digits.chars() {
+            match d.to_digit(10) {
+                Some(n) if n <= 8 => counts[n as usize] = true,
+                _ => return Err(format!("invalid cave rule count[{}]", d)),
+            }
+        }
+        Ok(counts)
+    };
+    Ok(Rule {
+        birth: counts(birth)?,
+        survive: counts(survive)?,
+    })
+}
+
+pub fn check_rule(arg: &str) -> Result<(), String> {
+    parse_rule(arg).map(|_| ())
+}
+
+//
+// Data only maze generator
+//
+
+pub fn generate_maze(monitor: monitor::MazeReceiver) {
+    let mut lk = match monitor.solver.lock() {
+        Ok(l) => l,
+        Err(_) => print::maze_panic!("uncontested lock failure"),
+    };
+    let rule = rule_from(&lk.arg);
+    build::fill_maze_with_walls(&mut lk.maze);
+    let mut gen = build::seeded_rng(&lk.maze);
+    let mut walls = seed_walls(&lk.maze, &mut gen);
+    for _ in 0..MAX_GENERATIONS {
+        match next_generation(&lk.maze, &walls, &rule) {
+            Some(next) => walls = next,
+            None => break,
+        }
+    }
+    while let Some(tunnel) = next_tunnel(&lk.maze, &walls) {
+        tunnel.iter().for_each(|&i| walls[i] = false);
+    }
+    for (i, &wall) in walls.iter().enumerate() {
+        let p = point_of(&lk.maze, i);
+        *lk.maze.get_mut(p.row, p.col) = square_for(&lk.maze, &walls, p, wall);
+    }
+}
+
+//
+// History based generator for animation and playback.
+//
+
+pub fn generate_history(monitor: monitor::MazeMonitor) {
+    let mut lk = match monitor.lock() {
+        Ok(l) => l,
+        Err(_) => print::maze_panic!("builder could not take lock"),
+    };
+    let rule = rule_from(&lk.arg);
+    build::fill_maze_history_with_walls(&mut lk.maze);
+    let mut gen = build::seeded_rng(&lk.maze);
+    let mut walls = seed_walls(&lk.maze, &mut gen);
+    apply_generation_history(&mut lk.maze, &walls);
+    for _ in 0..MAX_GENERATIONS {
+        match next_generation(&lk.maze, &walls, &rule) {
+            Some(next) => walls = next,
+            None => break,
+        }
+        apply_generation_history(&mut lk.maze, &walls);
+    }
+    // Each tunnel is its own burst so the joining of regions is easy to follow after the growth.
+    while let Some(tunnel) = next_tunnel(&lk.maze, &walls) {
+        tunnel.iter().for_each(|&i| walls[i] = false);
+        apply_generation_history(&mut lk.maze, &walls);
+    }
+}
+
+fn rule_from(arg: &Option<String>) -> Rule {
+    match parse_rule(arg.as_deref().unwrap_or(DEFAULT_RULE)) {
+        Ok(rule) => rule,
+        Err(msg) => print::maze_panic!("{}", msg),
+    }
+}
+
+// The perimeter is always a wall and the automaton never touches it.
+fn seed_walls(maze: &maze::Maze, gen: &mut StdRng) -> Vec<bool> {
+    (0..(maze.rows() * maze.cols()) as usize)
+        .map(|i| is_perimeter(maze, point_of(maze, i)) || gen.gen_bool(SEED_WALL_CHANCE))
+        .collect()
+}
+
+// A generation that changes nothing is a still life and there is no reason to keep going.
+fn next_generation(maze: &maze::Maze, walls: &[bool], rule: &Rule) -> Option<Vec<bool>> {
+    let next: Vec<bool> = walls
+        .iter()
+        .enumerate()
+        .map(|(i, &wall)| {
+            let p = point_of(maze, i);
+            if is_perimeter(maze, p) {
+                return true;
+            }
+            let neighbors = MOORE_NEIGHBORHOOD
+                .iter()
+                .filter(|d| walls[index_of(maze, p.row + d.row, p.col + d.col)])
+                .count();
+            if wall {
+                rule.survive[neighbors]
+            } else {
+                rule.birth[neighbors]
+            }
+        })
+        .collect();
+    if next == walls {
+        return None;
+    }
+    Some(next)
+}
+
+// Searches outward through walls from the largest open region until it finds a square of any
+// other region. Returns the wall squares along the way or None once everything is connected.
+fn next_tunnel(maze: &maze::Maze, walls: &[bool]) -> Option<Vec<usize>> {
+    let mut region = vec![usize::MAX; walls.len()];
+    let mut sizes = Vec::new();
+    for i in 0..walls.len() {
+        if walls[i] || region[i] != usize::MAX {
+            continue;
+        }
+        sizes.push(flood_region(maze, walls, &mut region, i, sizes.len()));
+    }
+    let main = match sizes
+        .iter()
+        .enumerate()
+        .max_by_key(|&(id, &size)| (size, !id))
+    {
+        Some((id, _)) => id,
+        // The automaton walled over everything so dig across the middle to give solvers room.
+        None => {
+            let row = maze.rows() / 2;
+            return Some(
+                (1..maze.cols() - 1)
+                    .map(|c| index_of(maze, row, c))
+                    .collect(),
+            );
+        }
+    };
+    if sizes.len() == 1 {
+        return None;
+    }
+    let mut parents = vec![usize::MAX; walls.len()];
+    let mut bfs: VecDeque<usize> = (0..walls.len()).filter(|&i| region[i] == main).collect();
+    bfs.iter().for_each(|&i| parents[i] = i);
+    while let Some(cur) = bfs.pop_front() {
+        let p = point_of(maze, cur);
+        for d in &maze::CARDINAL_DIRECTIONS {
+            let next = maze::Point {
+                row: p.row + d.row,
+                col: p.col + d.col,
+            };
+            if is_perimeter(maze, next) {
+                continue;
+            }
+            let n = index_of(maze, next.row, next.col);
+            if parents[n] != usize::MAX {
+                continue;
+            }
+            parents[n] = cur;
+            if !walls[n] {
+                let mut tunnel = Vec::new();
+                let mut step = cur;
+                while walls[step] {
+                    tunnel.push(step);
+                    step = parents[step];
+                }
+                return Some(tunnel);
+            }
+            bfs.push_back(n);
+        }
+    }
+    print::maze_panic!("cave regions could not be joined")
+}
+
+pub(crate) fn flood_region(
+    maze: &maze::Maze,
+    walls: &[bool],
+    region: &mut [usize],
+    start: usize,
+    id: usize,
+) -> usize {
+    let mut size = 0;
+    let mut dfs = vec![start];
+    region[start] = id;
+    while let Some(cur) = dfs.pop() {
+        size += 1;
+        let p = point_of(maze, cur);
+        for d in &maze::CARDINAL_DIRECTIONS {
+            let n = index_of(maze, p.row + d.row, p.col + d.col);
+            if !walls[n] && region[n] == usize::MAX {
+                region[n] = id;
+                dfs.push(n);
+            }
+        }
+    }
+    size
+}
+
+// Every square that differs from the automaton's wall grid changes in a single burst.
+fn apply_generation_history(maze: &mut maze::Maze, walls: &[bool]) {
+    let mut delta_vec = Vec::new();
+    for (i, &wall) in walls.iter().enumerate() {
+        let p = point_of(maze, i);
+        let before = maze.get(p.row, p.col);
+        let after = square_for(maze, walls, p, wall);
+        if before != after {
+            delta_vec.push(maze::Delta {
+                id: p,
+                before,
+                after,
+                burst: 1,
+            });
+        }
+    }
+    if delta_vec.is_empty() {
+        return;
+    }
+    let burst = delta_vec.len();
+    delta_vec[0].burst = burst;
+    delta_vec[burst - 1].burst = burst;
+    for d in delta_vec.iter() {
+        *maze.get_mut(d.id.row, d.id.col) = d.after;
+    }
+    maze.build_history.push_burst(delta_vec.as_slice());
+}
+
+// Wall lines only reach toward neighboring walls just like build_wall_carefully would leave them.
+pub(crate) fn square_for(
+    maze: &maze::Maze,
+    walls: &[bool],
+    p: maze::Point,
+    wall: bool,
+) -> maze::Square {
+    if !wall {
+        return maze::PATH_BIT;
+    }
+    let mut square: maze::WallLine = 0b0;
+    if p.row > 0 && walls[index_of(maze, p.row - 1, p.col)] {
+        square |= maze::NORTH_WALL;
+    }
+    if p.row + 1 < maze.rows() && walls[index_of(maze, p.row + 1, p.col)] {
+        square |= maze::SOUTH_WALL;
+    }
+    if p.col > 0 && walls[index_of(maze, p.row, p.col - 1)] {
+        square |= maze::WEST_WALL;
+    }
+    if p.col + 1 < maze.cols() && walls[index_of(maze, p.row, p.col + 1)] {
+        square |= maze::EAST_WALL;
+    }
+    square
+}
+
+#[inline]
+pub(crate) fn is_perimeter(maze: &maze::Maze, p: maze::Point) -> bool {
+    p.row <= 0 || p.col <= 0 || p.row >= maze.rows() - 1 || p.col >= maze.cols() - 1
+}
+
+#[inline]
+pub(crate) fn index_of(maze: &maze::Maze, row: i32, col: i32) -> usize {
+    (row * maze.cols() + col) as usize
+}
+
+#[inline]
+pub(crate) fn point_of(maze: &maze::Maze, i: usize) -> maze::Point {
+    maze::Point {
+        row: i as i32 / maze.cols(),
+        col: i as i32 % maze.cols(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(digits: &[usize]) -> [bool; 9] {
+        let mut counts = [false; 9];
+        digits.iter().for_each(|&d| counts[d] = true);
+        counts
+    }
+
+    #[test]
+    fn rule_strings_set_birth_and_survival_counts() {
+        let rule = parse_rule("B3/S1234").unwrap();
+        assert_eq!(rule.birth, counts(&[3]));
+        assert_eq!(rule.survive, counts(&[1, 2, 3, 4]));
+        let rule = parse_rule("b5678/s45678").unwrap();
+        assert_eq!(rule.birth, counts(&[5, 6, 7, 8]));
+        assert_eq!(rule.survive, counts(&[4, 5, 6, 7, 8]));
+        let rule = parse_rule("B0/S").unwrap();
+        assert_eq!(rule.birth, counts(&[0]));
+        assert_eq!(rule.survive, counts(&[]));
+    }
+
+    #[test]
+    fn named_rules_match_their_notation() {
+        for (name, notation) in RULE_NAMES {
+            let named = parse_rule(name).unwrap();
+            let written = parse_rule(notation).unwrap();
+            assert_eq!(named.birth, written.birth, "{}", name);
+            assert_eq!(named.survive, written.survive, "{}", name);
+        }
+    }
+
+    #[test]
+    fn bad_rules_are_rejected() {
+        for arg in [
+            "",
+            "B3",
+            "S1234",
+            "3/1234",
+            "S1234/B3",
+            "B9/S1234",
+            "B3/S12a4",
+            "B3/S1234/",
+            "B3 /S1234",
+            "B-3/S1234",
+            "labyrinth",
+        ] {
+            assert!(parse_rule(arg).is_err(), "{:?}", arg);
+        }
+    }
+
+    #[test]
+    fn start_and_finish_are_connected() {
+        for (rule, seed) in [("maze", 1), ("mazectric", 2), ("cave", 3), ("B5678/S8", 4)] {
+            let monitor = monitor::Monitor::new(maze::Maze::new(maze::MazeArgs {
+                odd_rows: 21,
+                odd_cols: 41,
+                seed,
+                ..Default::default()
+            }));
+            monitor.lock().unwrap().arg = Some(String::from(rule));
+            generate_history(monitor.clone());
+            let lk = monitor.lock().unwrap();
+            let walls: Vec<bool> = (0..(lk.maze.rows() * lk.maze.cols()) as usize)
+                .map(|i| {
+                    let p = point_of(&lk.maze, i);
+                    lk.maze.wall_at(p.row, p.col)
+                })
+                .collect();
+            // Solvers may place the start and finish on any path square so test the two far ends.
+            let start = walls.iter().position(|&w| !w).unwrap();
+            let finish = walls.iter().rposition(|&w| !w).unwrap();
+            assert_ne!(start, finish, "{}", rule);
+            let mut region = vec![usize::MAX; walls.len()];
+            let size = flood_region(&lk.maze, &walls, &mut region, start, 0);
+            assert_eq!(region[finish], 0, "{}", rule);
+            assert_eq!(size, walls.iter().filter(|&&w| !w).count(), "{}", rule);
+        }
+    }
+}
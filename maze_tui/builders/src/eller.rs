@@ -4,7 +4,7 @@ use print;
 
 use rand::{
     distributions::{Bernoulli, Distribution},
-    thread_rng, Rng,
+    Rng,
 };
 use std::collections::HashMap;
 
@@ -26,9 +26,9 @@ struct IdMergeRequest {
     loser: SetId,
 }
 
-///
-/// Data only maze generator
-///
+//
+// Data only maze generator
+//
 
 pub fn generate_maze(monitor: monitor::MazeReceiver) {
     let mut lk = match monitor.solver.lock() {
@@ -36,7 +36,7 @@ pub fn generate_maze(monitor: monitor::MazeReceiver) {
         Err(_) => print::maze_panic!("uncontested lock failure"),
     };
     build::fill_maze_with_walls(&mut lk.maze);
-    let mut rng = thread_rng();
+    let mut rng = build::seeded_rng(&lk.maze);
     let coin = Bernoulli::new(0.66);
     let mut window = SlidingSetWindow::new(&lk.maze);
     let mut sets_in_this_row: HashMap<SetId, Vec<maze::Point>> = HashMap::new();
@@ -127,9 +127,9 @@ fn complete_final_row(maze: &mut maze::Maze, window: &mut SlidingSetWindow) {
     }
 }
 
-///
-/// History based generator for animation and playback.
-///
+//
+// History based generator for animation and playback.
+//
 
 pub fn generate_history(monitor: monitor::MazeMonitor) {
     let mut lk = match monitor.lock() {
@@ -137,7 +137,7 @@ pub fn generate_history(monitor: monitor::MazeMonitor) {
         Err(_) => print::maze_panic!("uncontested lock failure"),
     };
     build::fill_maze_history_with_walls(&mut lk.maze);
-    let mut rng = thread_rng();
+    let mut rng = build::seeded_rng(&lk.maze);
     let coin = Bernoulli::new(0.66);
     let mut window = SlidingSetWindow::new(&lk.maze);
     let mut sets_in_this_row: HashMap<SetId, Vec<maze::Point>> = HashMap::new();
@@ -219,9 +219,9 @@ fn complete_final_row_history(maze: &mut maze::Maze, window: &mut SlidingSetWind
     }
 }
 
-///
-/// Helper data structure implementation for managing sets available to all.
-///
+//
+// Helper data structure implementation for managing sets available to all.
+//
 
 impl SlidingSetWindow {
     fn new(maze: &maze::Maze) -> Self {
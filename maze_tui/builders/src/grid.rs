@@ -1,7 +1,7 @@
 use crate::build;
 use maze;
 
-use rand::{seq::SliceRandom, thread_rng, Rng};
+use rand::{seq::SliceRandom, Rng};
 
 const RUN_LIMIT: i32 = 4;
 
@@ -10,9 +10,9 @@ struct RunStart {
     dir: maze::Point,
 }
 
-///
-/// Data only maze generator
-///
+//
+// Data only maze generator
+//
 
 pub fn generate_maze(monitor: monitor::MazeReceiver) {
     let mut lk = match monitor.solver.lock() {
@@ -20,7 +20,7 @@ pub fn generate_maze(monitor: monitor::MazeReceiver) {
         Err(_) => print::maze_panic!("uncontested lock failure"),
     };
     build::fill_maze_with_walls(&mut lk.maze);
-    let mut rng = thread_rng();
+    let mut rng = build::seeded_rng(&lk.maze);
     let mut dfs: Vec<maze::Point> = Vec::from([maze::Point {
         row: 2 * (rng.gen_range(1..lk.maze.rows() - 1) / 2) + 1,
         col: 2 * (rng.gen_range(1..lk.maze.cols() - 1) / 2) + 1,
@@ -63,9 +63,9 @@ fn complete_run(maze: &mut maze::Maze, dfs: &mut Vec<maze::Point>, mut run: RunS
     }
 }
 
-///
-/// History based generator for animation and playback.
-///
+//
+// History based generator for animation and playback.
+//
 
 pub fn generate_history(monitor: monitor::MazeMonitor) {
     let mut lk = match monitor.lock() {
@@ -73,7 +73,7 @@ pub fn generate_history(monitor: monitor::MazeMonitor) {
         Err(_) => print::maze_panic!("uncontested lock failure"),
     };
     build::fill_maze_history_with_walls(&mut lk.maze);
-    let mut rng = thread_rng();
+    let mut rng = build::seeded_rng(&lk.maze);
     let mut dfs: Vec<maze::Point> = Vec::from([maze::Point {
         row: 2 * (rng.gen_range(1..lk.maze.rows() - 1) / 2) + 1,
         col: 2 * (rng.gen_range(1..lk.maze.cols() - 1) / 2) + 1,
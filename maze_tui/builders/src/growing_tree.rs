@@ -0,0 +1,258 @@
+use crate::build;
+use maze;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+
+const POLICY_NAMES: [&str; 4] = ["newest", "oldest", "random", "middle"];
+const NEWEST: usize = 0;
+const OLDEST: usize = 1;
+const RANDOM: usize = 2;
+const MIDDLE: usize = 3;
+const DEFAULT_POLICY: Policy = Policy {
+    weights: [0.5, 0.0, 0.5, 0.0],
+};
+
+// Chance of each pick in POLICY_NAMES order. The weights always sum to one.
+#[derive(Clone, Copy, Debug)]
+pub struct Policy {
+    weights: [f64; 4],
+}
+
+//
+// The growing tree keeps a list of cells that may still have unbuilt neighbors. Always growing
+// from the newest cell makes it a recursive backtracker while picking at random makes it behave
+// like Prim's algorithm. A policy such as newest=0.5 mixes the two. Weights may be written as
+// fractions or percentages and any weight left over when they sum to less than one goes to random.
+// Each name appears at most once with a weight above zero.
+//
+
+pub fn parse_policy(arg: &str) -> Result<Policy, String> {
+    let mut weights = [0.0; 4];
+    for term in arg.split(',') {
+        let (name, weight) = match term.split_once('=') {
+            Some((name, weight)) => match weight.parse::<f64>() {
+                Ok(w) if w.is_finite() && w > 0.0 => (name, w),
+                _ => return Err(format!("invalid growing tree weight[{}]", weight)),
+            },
+            None => (term, 1.0),
+        };
+        match POLICY_NAMES.iter().position(|&p| p == name) {
+            Some(i) if weights[i] > 0.0 => {
+                return Err(format!("growing tree policy[{}] is given twice", name))
+            }
+            Some(i) => weights[i] = weight,
+            None => return Err(format!("invalid growing tree policy[{}]", name)),
+        }
+    }
+    let total: f64 = weights.iter().sum();
+    if total < 1.0 {
+        weights[RANDOM] += 1.0 - total;
+    } else {
+        weights.iter_mut().for_each(|w| *w /= total);
+    }
+    Ok(Policy { weights })
+}
+
+pub fn check_policy(arg: &str) -> Result<(), String> {
+    parse_policy(arg).map(|_| ())
+}
+
+//
+// Data only maze generator
+//
+
+pub fn generate_maze(monitor: monitor::MazeReceiver) {
+    let mut lk = match monitor.solver.lock() {
+        Ok(l) => l,
+        Err(_) => print::maze_panic!("uncontested lock failure"),
+    };
+    let policy = policy_from(&lk.arg);
+    build::fill_maze_with_walls(&mut lk.maze);
+    let mut gen = build::seeded_rng(&lk.maze);
+    let start: maze::Point = maze::Point {
+        row: 2 * (gen.gen_range(1..lk.maze.rows() - 2) / 2) + 1,
+        col: 2 * (gen.gen_range(1..lk.maze.cols() - 2) / 2) + 1,
+    };
+    let start = build::unmasked_start(&lk.maze, start);
+    build::build_path(&mut lk.maze, start);
+    *lk.maze.get_mut(start.row, start.col) |= build::BUILDER_BIT;
+    let mut cells = vec![start];
+    let mut random_direction_indices: [usize; build::NUM_DIRECTIONS] = [0, 1, 2, 3];
+    'growing: while !cells.is_empty() {
+        let i = pick(&policy, cells.len(), &mut gen);
+        let cur = cells[i];
+        random_direction_indices.shuffle(&mut gen);
+        for &d in random_direction_indices.iter() {
+            let direction = &build::GENERATE_DIRECTIONS[d];
+            let branch = maze::Point {
+                row: cur.row + direction.row,
+                col: cur.col + direction.col,
+            };
+            if build::can_build_new_square(&lk.maze, branch) {
+                build::join_squares(&mut lk.maze, cur, branch);
+                cells.push(branch);
+                continue 'growing;
+            }
+        }
+        cells.remove(i);
+    }
+}
+
+//
+// History based generator for animation and playback.
+//
+
+pub fn generate_history(monitor: monitor::MazeMonitor) {
+    let mut lk = match monitor.lock() {
+        Ok(l) => l,
+        Err(_) => print::maze_panic!("builder could not take lock"),
+    };
+    let policy = policy_from(&lk.arg);
+    build::fill_maze_history_with_walls(&mut lk.maze);
+    let mut gen = build::seeded_rng(&lk.maze);
+    let start: maze::Point = maze::Point {
+        row: 2 * (gen.gen_range(1..lk.maze.rows() - 2) / 2) + 1,
+        col: 2 * (gen.gen_range(1..lk.maze.cols() - 2) / 2) + 1,
+    };
+    let start = build::unmasked_start(&lk.maze, start);
+    build::carve_wall_history(&mut lk.maze, start, 0);
+    let mut cells = vec![start];
+    let mut random_direction_indices: [usize; build::NUM_DIRECTIONS] = [0, 1, 2, 3];
+    'growing: while !cells.is_empty() {
+        let i = pick(&policy, cells.len(), &mut gen);
+        let cur = cells[i];
+        random_direction_indices.shuffle(&mut gen);
+        for &d in random_direction_indices.iter() {
+            let direction = &build::GENERATE_DIRECTIONS[d];
+            let branch = maze::Point {
+                row: cur.row + direction.row,
+                col: cur.col + direction.col,
+            };
+            if build::can_build_new_square(&lk.maze, branch) {
+                // The backtracking markers stay on a cell while it is in the list so the frontier
+                // the policy chooses from is visible as it grows and shrinks.
+                build::carve_path_history(&mut lk.maze, cur, branch);
+                cells.push(branch);
+                continue 'growing;
+            }
+        }
+        clear_marks_history(&mut lk.maze, cur);
+        cells.remove(i);
+    }
+}
+
+fn policy_from(arg: &Option<String>) -> Policy {
+    match arg {
+        Some(arg) => match parse_policy(arg) {
+            Ok(policy) => policy,
+            Err(msg) => print::maze_panic!("{}", msg),
+        },
+        None => DEFAULT_POLICY,
+    }
+}
+
+fn pick(policy: &Policy, len: usize, gen: &mut StdRng) -> usize {
+    let mut roll = gen.gen::<f64>();
+    let choice = policy
+        .weights
+        .iter()
+        .position(|&w| {
+            let hit = roll < w;
+            roll -= w;
+            hit
+        })
+        .unwrap_or(RANDOM);
+    match choice {
+        NEWEST => len - 1,
+        OLDEST => 0,
+        MIDDLE => len / 2,
+        _ => gen.gen_range(0..len),
+    }
+}
+
+// A finished cell and the wall it was carved through both lose their markers.
+fn clear_marks_history(maze: &mut maze::Maze, cur: maze::Point) {
+    let square = maze.get(cur.row, cur.col);
+    let dir = square & build::MARKERS_MASK;
+    if dir == 0 {
+        return;
+    }
+    let half = &build::BACKTRACKING_HALF_POINTS[dir as usize];
+    let half_step = maze::Point {
+        row: cur.row + half.row,
+        col: cur.col + half.col,
+    };
+    let half_step_square = maze.get(half_step.row, half_step.col);
+    maze.build_history.push_burst(&[
+        maze::Delta {
+            id: cur,
+            before: square,
+            after: square & !build::MARKERS_MASK,
+            burst: 2,
+        },
+        maze::Delta {
+            id: half_step,
+            before: half_step_square,
+            after: half_step_square & !build::MARKERS_MASK,
+            burst: 2,
+        },
+    ]);
+    *maze.get_mut(cur.row, cur.col) &= !build::MARKERS_MASK;
+    *maze.get_mut(half_step.row, half_step.col) &= !build::MARKERS_MASK;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weights_are_normalized_and_the_rest_goes_to_random() {
+        assert_eq!(
+            parse_policy("newest").unwrap().weights,
+            [1.0, 0.0, 0.0, 0.0]
+        );
+        assert_eq!(
+            parse_policy("newest=50,oldest=50").unwrap().weights,
+            [0.5, 0.5, 0.0, 0.0]
+        );
+        assert_eq!(
+            parse_policy("middle=0.25").unwrap().weights,
+            [0.0, 0.0, 0.75, 0.25]
+        );
+    }
+
+    #[test]
+    fn unknown_names_are_rejected() {
+        for arg in ["newer", "newest=0.5,sideways=0.5", "NEWEST", "newest:0.5"] {
+            assert!(parse_policy(arg).is_err(), "{}", arg);
+        }
+    }
+
+    #[test]
+    fn zero_and_negative_weights_are_rejected() {
+        for arg in [
+            "newest=0",
+            "newest=0.5,random=0",
+            "newest=-0.5",
+            "newest=1,oldest=-1",
+            "newest=",
+            "newest=NaN",
+            "newest=inf",
+        ] {
+            assert!(parse_policy(arg).is_err(), "{}", arg);
+        }
+    }
+
+    #[test]
+    fn empty_parts_are_rejected() {
+        for arg in ["", ",", "newest,", ",newest", "newest,,random", "=0.5"] {
+            assert!(parse_policy(arg).is_err(), "{:?}", arg);
+        }
+    }
+
+    #[test]
+    fn duplicate_names_are_rejected() {
+        for arg in ["newest,newest", "newest=0.25,random,newest=0.25"] {
+            assert!(parse_policy(arg).is_err(), "{}", arg);
+        }
+    }
+}
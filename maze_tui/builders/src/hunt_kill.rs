@@ -1,6 +1,6 @@
 use crate::build;
 use maze;
-use rand::{seq::SliceRandom, thread_rng, Rng};
+use rand::{seq::SliceRandom, Rng};
 
 type DirectionMarker = build::BacktrackMarker;
 
@@ -9,9 +9,9 @@ const GOING_EAST: DirectionMarker = build::FROM_EAST;
 const GOING_SOUTH: DirectionMarker = build::FROM_SOUTH;
 const GOING_WEST: DirectionMarker = build::FROM_WEST;
 
-///
-/// Data only maze generator
-///
+//
+// Data only maze generator
+//
 
 pub fn generate_maze(monitor: monitor::MazeReceiver) {
     let mut lk = match monitor.solver.lock() {
@@ -19,7 +19,7 @@ pub fn generate_maze(monitor: monitor::MazeReceiver) {
         Err(_) => print::maze_panic!("uncontested lock failure"),
     };
     build::fill_maze_with_walls(&mut lk.maze);
-    let mut gen = thread_rng();
+    let mut gen = build::seeded_rng(&lk.maze);
     let start: maze::Point = maze::Point {
         row: 2 * (gen.gen_range(1..lk.maze.rows() - 2) / 2) + 1,
         col: 2 * (gen.gen_range(1..lk.maze.cols() - 2) / 2) + 1,
@@ -71,9 +71,9 @@ pub fn generate_maze(monitor: monitor::MazeReceiver) {
     }
 }
 
-///
-/// History based generator for animation and playback.
-///
+//
+// History based generator for animation and playback.
+//
 
 pub fn generate_history(monitor: monitor::MazeMonitor) {
     let mut lk = match monitor.lock() {
@@ -81,7 +81,7 @@ pub fn generate_history(monitor: monitor::MazeMonitor) {
         Err(_) => print::maze_panic!("uncontested lock failure"),
     };
     build::fill_maze_history_with_walls(&mut lk.maze);
-    let mut gen = thread_rng();
+    let mut gen = build::seeded_rng(&lk.maze);
     let start: maze::Point = maze::Point {
         row: 2 * (gen.gen_range(1..lk.maze.rows() - 2) / 2) + 1,
         col: 2 * (gen.gen_range(1..lk.maze.cols() - 2) / 2) + 1,
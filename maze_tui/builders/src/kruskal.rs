@@ -3,12 +3,12 @@ use crate::disjoint;
 use maze;
 use print;
 
-use rand::prelude::*;
+use rand::{rngs::StdRng, seq::SliceRandom};
 use std::collections::HashMap;
 
-///
-/// Data only maze generator
-///
+//
+// Data only maze generator
+//
 
 pub fn generate_maze(monitor: monitor::MazeReceiver) {
     let mut lk = match monitor.solver.lock() {
@@ -16,7 +16,12 @@ pub fn generate_maze(monitor: monitor::MazeReceiver) {
         Err(_) => print::maze_panic!("uncontested lock failure"),
     };
     build::fill_maze_with_walls(&mut lk.maze);
-    let walls = load_shuffled_walls(&lk.maze);
+    let mut gen = build::seeded_rng(&lk.maze);
+    if lk.maze.topology() != maze::topology::Topology::Square {
+        join_topology(&mut lk.maze, &mut gen);
+        return;
+    }
+    let walls = load_shuffled_walls(&lk.maze, &mut gen);
     let ids = tag_cells(&lk.maze);
     let mut sets = disjoint::DisjointSet::new(ids.len());
 
@@ -31,7 +36,10 @@ pub fn generate_maze(monitor: monitor::MazeReceiver) {
                 col: w.col,
             };
             if let (Some(a_id), Some(b_id)) = (ids.get(&above), ids.get(&below)) {
-                if sets.made_union(*a_id, *b_id) {
+                if !lk.maze.is_masked(above)
+                    && !lk.maze.is_masked(below)
+                    && sets.made_union(*a_id, *b_id)
+                {
                     build::join_squares(&mut lk.maze, above, below);
                 }
             } else {
@@ -47,7 +55,10 @@ pub fn generate_maze(monitor: monitor::MazeReceiver) {
                 col: w.col + 1,
             };
             if let (Some(l_id), Some(r_id)) = (ids.get(&left), ids.get(&right)) {
-                if sets.made_union(*l_id, *r_id) {
+                if !lk.maze.is_masked(left)
+                    && !lk.maze.is_masked(right)
+                    && sets.made_union(*l_id, *r_id)
+                {
                     build::join_squares(&mut lk.maze, right, left);
                 }
             } else {
@@ -57,9 +68,24 @@ pub fn generate_maze(monitor: monitor::MazeReceiver) {
     }
 }
 
-///
-/// History based generator for animation and playback.
-///
+fn join_topology(maze: &mut maze::Maze, gen: &mut StdRng) {
+    let cells = maze.topology().cells(maze.rows(), maze.cols());
+    let ids: HashMap<maze::Point, usize> = cells.iter().enumerate().map(|(i, &c)| (c, i)).collect();
+    let mut sets = disjoint::DisjointSet::new(ids.len());
+    for (cell, link) in load_shuffled_links(maze, &cells, gen) {
+        if let (Some(&a_id), Some(&b_id)) = (ids.get(&cell), ids.get(&link.next)) {
+            if sets.made_union(a_id, b_id) {
+                build::join_cells(maze, cell, link);
+            }
+        } else {
+            print::maze_panic!("Kruskal couldn't find a cell id. Build broke.");
+        }
+    }
+}
+
+//
+// History based generator for animation and playback.
+//
 
 pub fn generate_history(monitor: monitor::MazeMonitor) {
     let mut lk = match monitor.lock() {
@@ -67,7 +93,12 @@ pub fn generate_history(monitor: monitor::MazeMonitor) {
         Err(_) => print::maze_panic!("uncontested lock failure"),
     };
     build::fill_maze_history_with_walls(&mut lk.maze);
-    let walls = load_shuffled_walls(&lk.maze);
+    let mut gen = build::seeded_rng(&lk.maze);
+    if lk.maze.topology() != maze::topology::Topology::Square {
+        join_topology_history(&mut lk.maze, &mut gen);
+        return;
+    }
+    let walls = load_shuffled_walls(&lk.maze, &mut gen);
     let ids = tag_cells(&lk.maze);
     let mut sets = disjoint::DisjointSet::new(ids.len());
 
@@ -82,7 +113,10 @@ pub fn generate_history(monitor: monitor::MazeMonitor) {
                 col: w.col,
             };
             if let (Some(a_id), Some(b_id)) = (ids.get(&above), ids.get(&below)) {
-                if sets.made_union(*a_id, *b_id) {
+                if !lk.maze.is_masked(above)
+                    && !lk.maze.is_masked(below)
+                    && sets.made_union(*a_id, *b_id)
+                {
                     build::join_squares_history(&mut lk.maze, above, below);
                 }
             } else {
@@ -98,7 +132,10 @@ pub fn generate_history(monitor: monitor::MazeMonitor) {
                 col: w.col + 1,
             };
             if let (Some(l_id), Some(r_id)) = (ids.get(&left), ids.get(&right)) {
-                if sets.made_union(*l_id, *r_id) {
+                if !lk.maze.is_masked(left)
+                    && !lk.maze.is_masked(right)
+                    && sets.made_union(*l_id, *r_id)
+                {
                     build::join_squares_history(&mut lk.maze, right, left);
                 }
             } else {
@@ -108,11 +145,26 @@ pub fn generate_history(monitor: monitor::MazeMonitor) {
     }
 }
 
-///
-/// Data only helpers available to all.
-///
+fn join_topology_history(maze: &mut maze::Maze, gen: &mut StdRng) {
+    let cells = maze.topology().cells(maze.rows(), maze.cols());
+    let ids: HashMap<maze::Point, usize> = cells.iter().enumerate().map(|(i, &c)| (c, i)).collect();
+    let mut sets = disjoint::DisjointSet::new(ids.len());
+    for (cell, link) in load_shuffled_links(maze, &cells, gen) {
+        if let (Some(&a_id), Some(&b_id)) = (ids.get(&cell), ids.get(&link.next)) {
+            if sets.made_union(a_id, b_id) {
+                build::join_cells_history(maze, cell, link);
+            }
+        } else {
+            print::maze_panic!("Kruskal couldn't find a cell id. Build broke.");
+        }
+    }
+}
+
+//
+// Data only helpers available to all.
+//
 
-fn load_shuffled_walls(maze: &maze::Maze) -> Vec<maze::Point> {
+fn load_shuffled_walls(maze: &maze::Maze, gen: &mut StdRng) -> Vec<maze::Point> {
     let mut walls = Vec::new();
     for r in (1..maze.rows() - 1).step_by(2) {
         for c in (2..maze.cols() - 1).step_by(2) {
@@ -124,7 +176,7 @@ fn load_shuffled_walls(maze: &maze::Maze) -> Vec<maze::Point> {
             walls.push(maze::Point { row: r, col: c });
         }
     }
-    walls.shuffle(&mut thread_rng());
+    walls.shuffle(gen);
     walls
 }
 
@@ -139,3 +191,24 @@ fn tag_cells(maze: &maze::Maze) -> HashMap<maze::Point, usize> {
     }
     set_ids
 }
+
+// Every wall between two cells appears once, listed from the cell that comes first.
+fn load_shuffled_links(
+    maze: &maze::Maze,
+    cells: &[maze::Point],
+    gen: &mut StdRng,
+) -> Vec<(maze::Point, maze::topology::Link)> {
+    let topology = maze.topology();
+    let mut links: Vec<(maze::Point, maze::topology::Link)> = cells
+        .iter()
+        .flat_map(|&cell| {
+            topology
+                .links(maze.rows(), maze.cols(), cell)
+                .into_iter()
+                .filter(move |l| (l.next.row, l.next.col) > (cell.row, cell.col))
+                .map(move |l| (cell, l))
+        })
+        .collect();
+    links.shuffle(gen);
+    links
+}
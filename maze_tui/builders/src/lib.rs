@@ -1,13 +1,20 @@
+pub mod aldous_broder;
 pub mod arena;
+pub mod binary_tree;
 pub mod build;
+pub mod caves;
 pub(crate) mod disjoint;
 pub mod eller;
 pub mod grid;
+pub mod growing_tree;
 pub mod hunt_kill;
 pub mod kruskal;
 pub mod modify;
 pub mod prim;
 pub mod recursive_backtracker;
 pub mod recursive_subdivision;
+pub mod sidewinder;
+pub mod text;
+pub mod weave;
 pub mod wilson_adder;
 pub mod wilson_carver;
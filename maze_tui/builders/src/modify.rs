@@ -1,9 +1,69 @@
 use crate::build;
 use maze;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+use std::collections::VecDeque;
 
-///
-/// Data only maze generator
-///
+pub const MODIFICATION_SEED_STREAM: maze::Seed = 0x5EED_B4A1;
+const DEFAULT_BRAID: f64 = 1.0;
+const DEFAULT_ROOMS: usize = 4;
+const MAX_ROOMS: usize = 64;
+const ROOM_PLACEMENT_TRIES: usize = 64;
+const MIN_ROOM_CELLS: i32 = 2;
+const MAX_ROOM_CELLS: i32 = 6;
+
+// Rooms cover whole cells so both corners, inclusive, fall on odd coordinates.
+#[derive(Clone, Copy, Debug)]
+struct Room {
+    top_left: maze::Point,
+    bottom_right: maze::Point,
+}
+
+//
+// A braid maze has loops instead of dead ends. Each dead end is visited in random order and, with
+// the probability given as in braid:0.5, has a wall knocked out toward another cell. A neighbor that
+// is also a dead end is preferred because one wall then removes two dead ends. Dead ends only exist
+// between cells on odd coordinates so caves and arenas pass through mostly untouched.
+//
+
+pub fn parse_braid(arg: &str) -> Result<f64, String> {
+    match arg.parse::<f64>() {
+        Ok(p) if (0.0..=1.0).contains(&p) => Ok(p),
+        _ => Err(format!(
+            "braid probability must be within [0.0, 1.0] not [{}]",
+            arg
+        )),
+    }
+}
+
+pub fn check_braid(arg: &str) -> Result<(), String> {
+    parse_braid(arg).map(|_| ())
+}
+
+//
+// Rooms turn any maze into something closer to a dungeon. Up to the requested number of
+// rectangles, as in rooms:8 and never more than MAX_ROOMS, are placed where they do not touch one another and everything inside
+// them is opened up. The walls around a room are left alone so every corridor that used to cross
+// the rectangle now enters the room instead and nothing that was reachable becomes unreachable.
+// If a room lands somewhere with no corridor around it at all we tunnel to the nearest path.
+//
+
+pub fn parse_rooms(arg: &str) -> Result<usize, String> {
+    match arg.parse::<usize>() {
+        Ok(n) if (1..=MAX_ROOMS).contains(&n) => Ok(n),
+        _ => Err(format!(
+            "room count must be within [1, {}] not [{}]",
+            MAX_ROOMS, arg
+        )),
+    }
+}
+
+pub fn check_rooms(arg: &str) -> Result<(), String> {
+    parse_rooms(arg).map(|_| ())
+}
+
+//
+// Data only maze generator
+//
 
 pub fn add_cross(monitor: monitor::MazeReceiver) {
     let mut lk = match monitor.solver.lock() {
@@ -133,9 +193,43 @@ fn add_negative_slope(maze: &mut maze::Maze, p: maze::Point) {
     }
 }
 
-///
-/// History based generator for animation and playback.
-///
+pub fn braid(monitor: monitor::MazeReceiver) {
+    let mut lk = match monitor.solver.lock() {
+        Ok(l) => l,
+        Err(_) => print::maze_panic!("uncontested lock failure"),
+    };
+    let probability = braid_from(&lk.arg);
+    let mut gen = lk.maze.seeded_rng(MODIFICATION_SEED_STREAM);
+    for cell in shuffled_dead_ends(&lk.maze, &mut gen) {
+        if !is_dead_end(&lk.maze, cell) || !gen.gen_bool(probability) {
+            continue;
+        }
+        if let Some(wall) = choose_braid_wall(&lk.maze, cell, &mut gen) {
+            build::build_path(&mut lk.maze, wall);
+        }
+    }
+}
+
+pub fn add_rooms(monitor: monitor::MazeReceiver) {
+    let mut lk = match monitor.solver.lock() {
+        Ok(l) => l,
+        Err(_) => print::maze_panic!("uncontested lock failure"),
+    };
+    let count = rooms_from(&lk.arg);
+    let mut gen = lk.maze.seeded_rng(MODIFICATION_SEED_STREAM);
+    for room in place_rooms(&lk.maze, count, &mut gen) {
+        for p in room.squares() {
+            build::build_path(&mut lk.maze, p);
+        }
+        for p in room_tunnel(&lk.maze, &room) {
+            build::build_path(&mut lk.maze, p);
+        }
+    }
+}
+
+//
+// History based generator for animation and playback.
+//
 
 pub fn add_cross_history(monitor: monitor::MazeMonitor) {
     let mut lk = match monitor.lock() {
@@ -264,3 +358,327 @@ fn add_negative_slope_history(maze: &mut maze::Maze, p: maze::Point) {
         }
     }
 }
+
+pub fn braid_history(monitor: monitor::MazeMonitor) {
+    let mut lk = match monitor.lock() {
+        Ok(l) => l,
+        Err(_) => print::maze_panic!("uncontested lock failure"),
+    };
+    let probability = braid_from(&lk.arg);
+    let mut gen = lk.maze.seeded_rng(MODIFICATION_SEED_STREAM);
+    for cell in shuffled_dead_ends(&lk.maze, &mut gen) {
+        if !is_dead_end(&lk.maze, cell) || !gen.gen_bool(probability) {
+            continue;
+        }
+        if let Some(wall) = choose_braid_wall(&lk.maze, cell, &mut gen) {
+            build::build_path_history(&mut lk.maze, wall);
+        }
+    }
+}
+
+pub fn add_rooms_history(monitor: monitor::MazeMonitor) {
+    let mut lk = match monitor.lock() {
+        Ok(l) => l,
+        Err(_) => print::maze_panic!("uncontested lock failure"),
+    };
+    let count = rooms_from(&lk.arg);
+    let mut gen = lk.maze.seeded_rng(MODIFICATION_SEED_STREAM);
+    for room in place_rooms(&lk.maze, count, &mut gen) {
+        carve_room_history(&mut lk.maze, &room);
+        for p in room_tunnel(&lk.maze, &room) {
+            build::build_path_history(&mut lk.maze, p);
+        }
+    }
+}
+
+fn braid_from(arg: &Option<String>) -> f64 {
+    match arg {
+        Some(arg) => match parse_braid(arg) {
+            Ok(p) => p,
+            Err(msg) => print::maze_panic!("{}", msg),
+        },
+        None => DEFAULT_BRAID,
+    }
+}
+
+fn shuffled_dead_ends(maze: &maze::Maze, gen: &mut StdRng) -> Vec<maze::Point> {
+    let mut dead_ends: Vec<maze::Point> = (1..maze.rows() - 1)
+        .step_by(2)
+        .flat_map(|row| {
+            (1..maze.cols() - 1)
+                .step_by(2)
+                .map(move |col| maze::Point { row, col })
+        })
+        .filter(|&p| is_dead_end(maze, p))
+        .collect();
+    dead_ends.shuffle(gen);
+    dead_ends
+}
+
+fn is_dead_end(maze: &maze::Maze, p: maze::Point) -> bool {
+    maze::is_path(maze.get(p.row, p.col))
+        && maze::CARDINAL_DIRECTIONS
+            .iter()
+            .filter(|d| maze::is_path(maze.get(p.row + d.row, p.col + d.col)))
+            .count()
+            == 1
+}
+
+// Returns the wall square between the dead end and the cell it should join, if any.
+fn choose_braid_wall(
+    maze: &maze::Maze,
+    cell: maze::Point,
+    gen: &mut StdRng,
+) -> Option<maze::Point> {
+    let candidates: Vec<(maze::Point, maze::Point)> = maze::CARDINAL_DIRECTIONS
+        .iter()
+        .filter_map(|d| {
+            let wall = maze::Point {
+                row: cell.row + d.row,
+                col: cell.col + d.col,
+            };
+            let next = maze::Point {
+                row: cell.row + 2 * d.row,
+                col: cell.col + 2 * d.col,
+            };
+            (build::is_square_within_perimeter_walls(maze, next)
+                && !maze::is_path(maze.get(wall.row, wall.col))
+                && maze::is_path(maze.get(next.row, next.col)))
+            .then_some((wall, next))
+        })
+        .collect();
+    let dead_end_neighbors: Vec<&(maze::Point, maze::Point)> = candidates
+        .iter()
+        .filter(|(_, next)| is_dead_end(maze, *next))
+        .collect();
+    match dead_end_neighbors.choose(gen) {
+        Some((wall, _)) => Some(*wall),
+        None => candidates.choose(gen).map(|(wall, _)| *wall),
+    }
+}
+
+fn rooms_from(arg: &Option<String>) -> usize {
+    match arg {
+        Some(arg) => match parse_rooms(arg) {
+            Ok(n) => n,
+            Err(msg) => print::maze_panic!("{}", msg),
+        },
+        None => DEFAULT_ROOMS,
+    }
+}
+
+impl Room {
+    // Rooms keep at least one cell of maze between them so they never merge into one.
+    fn overlaps(&self, other: &Room) -> bool {
+        !(self.top_left.row > other.bottom_right.row + 2
+            || other.top_left.row > self.bottom_right.row + 2
+            || self.top_left.col > other.bottom_right.col + 2
+            || other.top_left.col > self.bottom_right.col + 2)
+    }
+
+    fn contains(&self, p: maze::Point) -> bool {
+        p.row >= self.top_left.row
+            && p.row <= self.bottom_right.row
+            && p.col >= self.top_left.col
+            && p.col <= self.bottom_right.col
+    }
+
+    fn squares(&self) -> impl Iterator<Item = maze::Point> + '_ {
+        (self.top_left.row..=self.bottom_right.row).flat_map(move |row| {
+            (self.top_left.col..=self.bottom_right.col).map(move |col| maze::Point { row, col })
+        })
+    }
+
+    // The squares surrounding the room, which are never carved by the room itself.
+    fn border(&self) -> impl Iterator<Item = maze::Point> + '_ {
+        (self.top_left.row - 1..=self.bottom_right.row + 1)
+            .flat_map(move |row| {
+                (self.top_left.col - 1..=self.bottom_right.col + 1)
+                    .map(move |col| maze::Point { row, col })
+            })
+            .filter(|&p| !self.contains(p))
+    }
+}
+
+// Fewer rooms than requested are placed if the maze is too crowded to fit them all.
+fn place_rooms(maze: &maze::Maze, count: usize, gen: &mut StdRng) -> Vec<Room> {
+    let cell_rows = (maze.rows() - 1) / 2;
+    let cell_cols = (maze.cols() - 1) / 2;
+    let mut rooms: Vec<Room> = Vec::with_capacity(count);
+    if cell_rows < MIN_ROOM_CELLS || cell_cols < MIN_ROOM_CELLS {
+        return rooms;
+    }
+    let max_rows = MAX_ROOM_CELLS.min(cell_rows / 2).max(MIN_ROOM_CELLS);
+    let max_cols = MAX_ROOM_CELLS.min(cell_cols / 2).max(MIN_ROOM_CELLS);
+    for _ in 0..count * ROOM_PLACEMENT_TRIES {
+        if rooms.len() == count {
+            break;
+        }
+        let height = gen.gen_range(MIN_ROOM_CELLS..=max_rows);
+        let width = gen.gen_range(MIN_ROOM_CELLS..=max_cols);
+        let top_left = maze::Point {
+            row: 2 * gen.gen_range(0..=cell_rows - height) + 1,
+            col: 2 * gen.gen_range(0..=cell_cols - width) + 1,
+        };
+        let room = Room {
+            top_left,
+            bottom_right: maze::Point {
+                row: top_left.row + 2 * (height - 1),
+                col: top_left.col + 2 * (width - 1),
+            },
+        };
+        if rooms.iter().any(|r| r.overlaps(&room)) || room.squares().any(|p| maze.is_masked(p)) {
+            continue;
+        }
+        rooms.push(room);
+    }
+    rooms
+}
+
+// The whole room opens at once. Carving touches the border walls as well so the squares are
+// carved with the data only helper and the difference is recorded as a single burst.
+fn carve_room_history(maze: &mut maze::Maze, room: &Room) {
+    let before: Vec<(maze::Point, maze::Square)> = room
+        .squares()
+        .chain(room.border())
+        .map(|p| (p, maze.get(p.row, p.col)))
+        .collect();
+    for p in room.squares() {
+        build::build_path(maze, p);
+    }
+    let mut delta_vec: Vec<maze::Delta> = before
+        .iter()
+        .filter(|(p, square)| maze.get(p.row, p.col) != *square)
+        .map(|&(p, square)| maze::Delta {
+            id: p,
+            before: square,
+            after: maze.get(p.row, p.col),
+            burst: 1,
+        })
+        .collect();
+    if delta_vec.is_empty() {
+        return;
+    }
+    let burst = delta_vec.len();
+    delta_vec[0].burst = burst;
+    delta_vec[burst - 1].burst = burst;
+    maze.build_history.push_burst(delta_vec.as_slice());
+}
+
+// Any path on the border already joins the room to the maze. Otherwise search outward through
+// walls for the nearest path and return the walls in the way.
+fn room_tunnel(maze: &maze::Maze, room: &Room) -> Vec<maze::Point> {
+    if room.border().any(|p| maze::is_path(maze.get(p.row, p.col))) {
+        return Vec::new();
+    }
+    let index = |p: maze::Point| (p.row * maze.cols() + p.col) as usize;
+    let mut parents: Vec<Option<maze::Point>> = vec![None; (maze.rows() * maze.cols()) as usize];
+    let mut bfs: VecDeque<maze::Point> = room.squares().collect();
+    bfs.iter().for_each(|&p| parents[index(p)] = Some(p));
+    while let Some(cur) = bfs.pop_front() {
+        for d in &maze::CARDINAL_DIRECTIONS {
+            let next = maze::Point {
+                row: cur.row + d.row,
+                col: cur.col + d.col,
+            };
+            if !build::is_square_within_perimeter_walls(maze, next)
+                || maze.is_masked(next)
+                || parents[index(next)].is_some()
+            {
+                continue;
+            }
+            parents[index(next)] = Some(cur);
+            if maze::is_path(maze.get(next.row, next.col)) {
+                let mut tunnel = Vec::new();
+                let mut step = cur;
+                while !room.contains(step) {
+                    tunnel.push(step);
+                    step = parents[index(step)].unwrap_or(step);
+                }
+                return tunnel;
+            }
+            bfs.push_back(next);
+        }
+    }
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn perfect_maze(seed: maze::Seed) -> monitor::MazeMonitor {
+        let monitor = monitor::Monitor::new(maze::Maze::new(maze::MazeArgs {
+            odd_rows: 21,
+            odd_cols: 41,
+            seed,
+            ..Default::default()
+        }));
+        crate::recursive_backtracker::generate_history(monitor.clone());
+        monitor
+    }
+
+    #[test]
+    fn braid_probability_must_be_within_zero_and_one() {
+        assert_eq!(parse_braid("0").unwrap(), 0.0);
+        assert_eq!(parse_braid("0.5").unwrap(), 0.5);
+        assert_eq!(parse_braid("1").unwrap(), 1.0);
+        for arg in ["-0.1", "1.01", "50", "50%", "NaN", "inf", "", "half"] {
+            assert!(parse_braid(arg).is_err(), "{:?}", arg);
+        }
+    }
+
+    #[test]
+    fn room_count_must_be_within_one_and_the_max() {
+        assert_eq!(parse_rooms("1").unwrap(), 1);
+        assert_eq!(parse_rooms(&MAX_ROOMS.to_string()).unwrap(), MAX_ROOMS);
+        let too_many = (MAX_ROOMS + 1).to_string();
+        let overflow = usize::MAX.to_string();
+        for arg in ["0", "-1", "2.5", "", "eight", &too_many, &overflow] {
+            assert!(parse_rooms(arg).is_err(), "{:?}", arg);
+        }
+    }
+
+    #[test]
+    fn rooms_stay_within_their_size_limits_and_the_maze() {
+        let monitor = perfect_maze(5);
+        let lk = monitor.lock().unwrap();
+        let mut gen = lk.maze.seeded_rng(MODIFICATION_SEED_STREAM);
+        let rooms = place_rooms(&lk.maze, MAX_ROOMS, &mut gen);
+        assert!(!rooms.is_empty());
+        for room in &rooms {
+            let height = (room.bottom_right.row - room.top_left.row) / 2 + 1;
+            let width = (room.bottom_right.col - room.top_left.col) / 2 + 1;
+            assert!(
+                (MIN_ROOM_CELLS..=MAX_ROOM_CELLS).contains(&height),
+                "{:?}",
+                room
+            );
+            assert!(
+                (MIN_ROOM_CELLS..=MAX_ROOM_CELLS).contains(&width),
+                "{:?}",
+                room
+            );
+            assert!(room.border().all(|p| p.row >= 0
+                && p.row < lk.maze.rows()
+                && p.col >= 0
+                && p.col < lk.maze.cols()));
+        }
+    }
+
+    #[test]
+    fn full_braid_leaves_no_dead_ends() {
+        for seed in 0..4 {
+            let monitor = perfect_maze(seed);
+            monitor.lock().unwrap().arg = Some(String::from("1.0"));
+            braid_history(monitor.clone());
+            let lk = monitor.lock().unwrap();
+            let mut gen = lk.maze.seeded_rng(MODIFICATION_SEED_STREAM);
+            assert!(
+                shuffled_dead_ends(&lk.maze, &mut gen).is_empty(),
+                "seed {}",
+                seed
+            );
+        }
+    }
+}
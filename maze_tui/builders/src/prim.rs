@@ -3,7 +3,7 @@ use maze;
 
 use rand::{
     distributions::{Distribution, Uniform},
-    thread_rng, Rng,
+    Rng,
 };
 use std::collections::{BinaryHeap, HashMap};
 
@@ -31,9 +31,9 @@ impl Ord for PriorityPoint {
     }
 }
 
-///
-/// Data only maze generator
-///
+//
+// Data only maze generator
+//
 
 pub fn generate_maze(monitor: monitor::MazeReceiver) {
     let mut lk = match monitor.solver.lock() {
@@ -41,7 +41,7 @@ pub fn generate_maze(monitor: monitor::MazeReceiver) {
         Err(_) => print::maze_panic!("uncontested lock failure"),
     };
     build::fill_maze_with_walls(&mut lk.maze);
-    let mut rng = thread_rng();
+    let mut rng = build::seeded_rng(&lk.maze);
     let weight_range = Uniform::from(1..=100);
     let start = PriorityPoint {
         priority: weight_range.sample(&mut rng),
@@ -50,6 +50,10 @@ pub fn generate_maze(monitor: monitor::MazeReceiver) {
             col: 2 * rng.gen_range(1..((lk.maze.cols() - 2) / 2)) + 1,
         },
     };
+    let start = PriorityPoint {
+        p: build::unmasked_start(&lk.maze, start.p),
+        ..start
+    };
     let mut lookup_weights: HashMap<maze::Point, u8> = HashMap::from([(start.p, start.priority)]);
     let mut pq = BinaryHeap::from([start]);
     while let Some(&cur) = pq.peek() {
@@ -86,9 +90,9 @@ pub fn generate_maze(monitor: monitor::MazeReceiver) {
     }
 }
 
-///
-/// History based generator for animation and playback.
-///
+//
+// History based generator for animation and playback.
+//
 
 pub fn generate_history(monitor: monitor::MazeMonitor) {
     let mut lk = match monitor.lock() {
@@ -96,7 +100,7 @@ pub fn generate_history(monitor: monitor::MazeMonitor) {
         Err(_) => print::maze_panic!("uncontested lock failure"),
     };
     build::fill_maze_history_with_walls(&mut lk.maze);
-    let mut rng = thread_rng();
+    let mut rng = build::seeded_rng(&lk.maze);
     let weight_range = Uniform::from(1..=100);
     let start = PriorityPoint {
         priority: weight_range.sample(&mut rng),
@@ -105,6 +109,10 @@ pub fn generate_history(monitor: monitor::MazeMonitor) {
             col: 2 * rng.gen_range(1..((lk.maze.cols() - 2) / 2)) + 1,
         },
     };
+    let start = PriorityPoint {
+        p: build::unmasked_start(&lk.maze, start.p),
+        ..start
+    };
     let mut lookup_weights: HashMap<maze::Point, u8> = HashMap::from([(start.p, start.priority)]);
     let mut pq = BinaryHeap::from([start]);
     while let Some(&cur) = pq.peek() {
@@ -1,10 +1,10 @@
 use crate::build;
 use maze;
-use rand::{seq::SliceRandom, thread_rng, Rng};
+use rand::{seq::SliceRandom, Rng};
 
-///
-/// Data only maze generator
-///
+//
+// Data only maze generator
+//
 
 pub fn generate_maze(monitor: monitor::MazeReceiver) {
     let mut lk = match monitor.solver.lock() {
@@ -12,11 +12,16 @@ pub fn generate_maze(monitor: monitor::MazeReceiver) {
         Err(_) => print::maze_panic!("uncontested lock failure"),
     };
     build::fill_maze_with_walls(&mut lk.maze);
-    let mut gen = thread_rng();
+    if lk.maze.topology() != maze::topology::Topology::Square {
+        descend_topology(&mut lk.maze);
+        return;
+    }
+    let mut gen = build::seeded_rng(&lk.maze);
     let start: maze::Point = maze::Point {
         row: 2 * (gen.gen_range(1..lk.maze.rows() - 2) / 2) + 1,
         col: 2 * (gen.gen_range(1..lk.maze.cols() - 2) / 2) + 1,
     };
+    let start = build::unmasked_start(&lk.maze, start);
     let mut random_direction_indices: [usize; build::NUM_DIRECTIONS] = [0, 1, 2, 3];
     let mut cur: maze::Point = start;
     'descending: loop {
@@ -52,9 +57,39 @@ pub fn generate_maze(monitor: monitor::MazeReceiver) {
     }
 }
 
-///
-/// History based generator for animation and playback.
-///
+// Hex and triangle cells have more or fewer neighbors than the four backtracking markers can
+// describe so the path back is kept on a stack instead of in the squares.
+fn descend_topology(maze: &mut maze::Maze) {
+    let mut gen = build::seeded_rng(maze);
+    let topology = maze.topology();
+    let cells = topology.cells(maze.rows(), maze.cols());
+    let start = match cells.choose(&mut gen) {
+        Some(&cell) => cell,
+        None => return,
+    };
+    build::carve_cell(maze, start);
+    let mut stack = vec![start];
+    while let Some(&cur) = stack.last() {
+        let mut links = topology.links(maze.rows(), maze.cols(), cur);
+        links.shuffle(&mut gen);
+        match links
+            .into_iter()
+            .find(|l| !build::has_builder_bit(maze, l.next))
+        {
+            Some(l) => {
+                build::join_cells(maze, cur, l);
+                stack.push(l.next);
+            }
+            None => {
+                stack.pop();
+            }
+        }
+    }
+}
+
+//
+// History based generator for animation and playback.
+//
 
 pub fn generate_history(monitor: monitor::MazeMonitor) {
     let mut lk = match monitor.lock() {
@@ -62,11 +97,16 @@ pub fn generate_history(monitor: monitor::MazeMonitor) {
         Err(_) => print::maze_panic!("builder could not take lock"),
     };
     build::fill_maze_history_with_walls(&mut lk.maze);
-    let mut gen = thread_rng();
+    if lk.maze.topology() != maze::topology::Topology::Square {
+        descend_topology_history(&mut lk.maze);
+        return;
+    }
+    let mut gen = build::seeded_rng(&lk.maze);
     let start: maze::Point = maze::Point {
         row: 2 * (gen.gen_range(1..lk.maze.rows() - 2) / 2) + 1,
         col: 2 * (gen.gen_range(1..lk.maze.cols() - 2) / 2) + 1,
     };
+    let start = build::unmasked_start(&lk.maze, start);
     let mut random_direction_indices: [usize; build::NUM_DIRECTIONS] = [0, 1, 2, 3];
     let mut cur: maze::Point = start;
     'descending: loop {
@@ -115,3 +155,72 @@ pub fn generate_history(monitor: monitor::MazeMonitor) {
         }
     }
 }
+
+fn descend_topology_history(maze: &mut maze::Maze) {
+    let mut gen = build::seeded_rng(maze);
+    let topology = maze.topology();
+    let cells = topology.cells(maze.rows(), maze.cols());
+    let start = match cells.choose(&mut gen) {
+        Some(&cell) => cell,
+        None => return,
+    };
+    build::carve_cell_history(maze, start);
+    let mut stack = vec![start];
+    while let Some(&cur) = stack.last() {
+        let mut links = topology.links(maze.rows(), maze.cols(), cur);
+        links.shuffle(&mut gen);
+        match links
+            .into_iter()
+            .find(|l| !build::has_builder_bit(maze, l.next))
+        {
+            Some(l) => {
+                build::join_cells_history(maze, cur, l);
+                stack.push(l.next);
+            }
+            None => {
+                stack.pop();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use maze::topology::Topology;
+
+    #[test]
+    fn topology_mazes_reach_every_cell_as_a_tree() {
+        for topology in [Topology::Hex, Topology::Triangle, Topology::Polar] {
+            let mut maze = maze::Maze::new(maze::MazeArgs {
+                odd_rows: 15,
+                odd_cols: 31,
+                seed: 7,
+                topology,
+                ..Default::default()
+            });
+            build::fill_maze_with_walls(&mut maze);
+            descend_topology(&mut maze);
+            let (rows, cols) = (maze.rows(), maze.cols());
+            let cells = topology.cells(rows, cols);
+            let mut seen = vec![cells[0]];
+            let mut dfs = vec![cells[0]];
+            let mut passages = 0;
+            while let Some(cur) = dfs.pop() {
+                for l in topology.links(rows, cols, cur) {
+                    if maze.wall_at(l.wall.row, l.wall.col) {
+                        continue;
+                    }
+                    passages += 1;
+                    if !seen.contains(&l.next) {
+                        seen.push(l.next);
+                        dfs.push(l.next);
+                    }
+                }
+            }
+            assert_eq!(seen.len(), cells.len(), "{}", topology.name());
+            // Every passage is counted from both ends and a tree has one fewer than its cells.
+            assert_eq!(passages, 2 * (cells.len() - 1), "{}", topology.name());
+        }
+    }
+}
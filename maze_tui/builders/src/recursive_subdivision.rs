@@ -1,7 +1,7 @@
 use crate::build;
 use maze;
 
-use rand::{rngs::ThreadRng, thread_rng, Rng};
+use rand::{rngs::StdRng, Rng};
 
 type Height = i32;
 type Width = i32;
@@ -15,9 +15,9 @@ struct Chamber {
 
 const MIN_CHAMBER: i32 = 3;
 
-///
-/// Data only maze generator
-///
+//
+// Data only maze generator
+//
 
 pub fn generate_maze(monitor: monitor::MazeReceiver) {
     let mut lk = match monitor.solver.lock() {
@@ -25,7 +25,7 @@ pub fn generate_maze(monitor: monitor::MazeReceiver) {
         Err(_) => print::maze_panic!("uncontested lock failure"),
     };
     build::build_wall_outline(&mut lk.maze);
-    let mut rng = thread_rng();
+    let mut rng = build::seeded_rng(&lk.maze);
     let mut chamber_stack: Vec<Chamber> = Vec::from([Chamber {
         offset: maze::Point { row: 0, col: 0 },
         h: lk.maze.rows(),
@@ -92,9 +92,9 @@ pub fn generate_maze(monitor: monitor::MazeReceiver) {
     }
 }
 
-///
-/// History based generator for animation and playback.
-///
+//
+// History based generator for animation and playback.
+//
 
 pub fn generate_history(monitor: monitor::MazeMonitor) {
     let mut lk = match monitor.lock() {
@@ -102,7 +102,7 @@ pub fn generate_history(monitor: monitor::MazeMonitor) {
         Err(_) => print::maze_panic!("uncontested lock failure"),
     };
     build::build_wall_outline_history(&mut lk.maze);
-    let mut rng = thread_rng();
+    let mut rng = build::seeded_rng(&lk.maze);
     let mut chamber_stack: Vec<Chamber> = Vec::from([Chamber {
         offset: maze::Point { row: 0, col: 0 },
         h: lk.maze.rows(),
@@ -169,14 +169,14 @@ pub fn generate_history(monitor: monitor::MazeMonitor) {
     }
 }
 
-///
-/// Data only helpers.
-///
+//
+// Data only helpers.
+//
 
-fn rand_even_div(rng: &mut ThreadRng, axis_limit: i32) -> i32 {
+fn rand_even_div(rng: &mut StdRng, axis_limit: i32) -> i32 {
     2 * rng.gen_range(1..=((axis_limit - 2) / 2))
 }
 
-fn rand_odd_pass(rng: &mut ThreadRng, axis_limit: i32) -> i32 {
+fn rand_odd_pass(rng: &mut StdRng, axis_limit: i32) -> i32 {
     2 * rng.gen_range(1..=((axis_limit - 2) / 2)) + 1
 }
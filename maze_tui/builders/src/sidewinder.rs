@@ -0,0 +1,128 @@
+use crate::build;
+use maze;
+use rand::Rng;
+
+//
+// Data only maze generator
+//
+
+pub fn generate_maze(monitor: monitor::MazeReceiver) {
+    let mut lk = match monitor.solver.lock() {
+        Ok(l) => l,
+        Err(_) => print::maze_panic!("uncontested lock failure"),
+    };
+    build::fill_maze_with_walls(&mut lk.maze);
+    let mut gen = build::seeded_rng(&lk.maze);
+    let last_col = last_cell_col(&lk.maze);
+    for col in (1..last_col).step_by(2) {
+        build::join_squares(
+            &mut lk.maze,
+            maze::Point { row: 1, col },
+            maze::Point {
+                row: 1,
+                col: col + 2,
+            },
+        );
+    }
+    for row in (3..lk.maze.rows() - 1).step_by(2) {
+        let mut run_start = 1;
+        for col in (1..=last_col).step_by(2) {
+            let cur = maze::Point { row, col };
+            if col == last_col || gen.gen_bool(0.5) {
+                let exit = maze::Point {
+                    row,
+                    col: 2 * (gen.gen_range(run_start..=col) / 2) + 1,
+                };
+                build::join_squares(
+                    &mut lk.maze,
+                    exit,
+                    maze::Point {
+                        row: row - 2,
+                        col: exit.col,
+                    },
+                );
+                run_start = col + 2;
+                continue;
+            }
+            build::join_squares(&mut lk.maze, cur, maze::Point { row, col: col + 2 });
+        }
+    }
+}
+
+//
+// History based generator for animation and playback.
+//
+
+pub fn generate_history(monitor: monitor::MazeMonitor) {
+    let mut lk = match monitor.lock() {
+        Ok(l) => l,
+        Err(_) => print::maze_panic!("builder could not take lock"),
+    };
+    build::fill_maze_history_with_walls(&mut lk.maze);
+    let mut gen = build::seeded_rng(&lk.maze);
+    let last_col = last_cell_col(&lk.maze);
+    for col in (1..last_col).step_by(2) {
+        build::join_squares_history(
+            &mut lk.maze,
+            maze::Point { row: 1, col },
+            maze::Point {
+                row: 1,
+                col: col + 2,
+            },
+        );
+    }
+    for row in (3..lk.maze.rows() - 1).step_by(2) {
+        let mut run_start = 1;
+        for col in (1..=last_col).step_by(2) {
+            let cur = maze::Point { row, col };
+            if col == last_col || gen.gen_bool(0.5) {
+                let exit = maze::Point {
+                    row,
+                    col: 2 * (gen.gen_range(run_start..=col) / 2) + 1,
+                };
+                clear_run_history(&mut lk.maze, row, run_start, col);
+                build::join_squares_history(
+                    &mut lk.maze,
+                    exit,
+                    maze::Point {
+                        row: row - 2,
+                        col: exit.col,
+                    },
+                );
+                run_start = col + 2;
+                continue;
+            }
+            // Carving leaves the backtracking markers behind so the open run is easy to spot.
+            build::carve_path_history(&mut lk.maze, cur, maze::Point { row, col: col + 2 });
+        }
+    }
+}
+
+fn last_cell_col(maze: &maze::Maze) -> i32 {
+    (1..maze.cols() - 1).step_by(2).next_back().unwrap_or(1)
+}
+
+// A closed run is finished so its markers are all removed at once.
+fn clear_run_history(maze: &mut maze::Maze, row: i32, run_start: i32, run_end: i32) {
+    let mut delta_vec = Vec::new();
+    for col in run_start..=run_end {
+        let square = maze.get(row, col);
+        if !build::is_marked(square) {
+            continue;
+        }
+        delta_vec.push(maze::Delta {
+            id: maze::Point { row, col },
+            before: square,
+            after: square & !build::MARKERS_MASK,
+            burst: 1,
+        });
+        *maze.get_mut(row, col) &= !build::MARKERS_MASK;
+    }
+    if delta_vec.is_empty() {
+        return;
+    }
+    let burst = delta_vec.len();
+    delta_vec[0].burst = burst;
+    delta_vec[burst - 1].burst = burst;
+    maze.build_history.push_burst(delta_vec.as_slice());
+}
@@ -0,0 +1,448 @@
+use crate::caves;
+use maze;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+const GLYPH_SIZE: i32 = 5;
+// Every glyph is followed by one blank pixel column and every line by one blank pixel row.
+const GLYPH_ADVANCE: i32 = GLYPH_SIZE + 1;
+const MARGIN_CELLS: i32 = 1;
+const OUTLINE_TUNNEL_COST: usize = 64;
+const FONT: [(char, [&str; 5]); 43] = [
+    ('A', [" ### ", "#   #", "#####", "#   #", "#   #"]),
+    ('B', ["#### ", "#   #", "#### ", "#   #", "#### "]),
+    ('C', [" ####", "#    ", "#    ", "#    ", " ####"]),
+    ('D', ["#### ", "#   #", "#   #", "#   #", "#### "]),
+    ('E', ["#####", "#    ", "#### ", "#    ", "#####"]),
+    ('F', ["#####", "#    ", "#### ", "#    ", "#    "]),
+    ('G', [" ####", "#    ", "#  ##", "#   #", " ####"]),
+    ('H', ["#   #", "#   #", "#####", "#   #", "#   #"]),
+    ('I', ["#####", "  #  ", "  #  ", "  #  ", "#####"]),
+    ('J', ["#####", "   # ", "   # ", "#  # ", " ##  "]),
+    ('K', ["#   #", "#  # ", "###  ", "#  # ", "#   #"]),
+    ('L', ["#    ", "#    ", "#    ", "#    ", "#####"]),
+    ('M', ["#   #", "## ##", "# # #", "#   #", "#   #"]),
+    ('N', ["#   #", "##  #", "# # #", "#  ##", "#   #"]),
+    ('O', [" ### ", "#   #", "#   #", "#   #", " ### "]),
+    ('P', ["#### ", "#   #", "#### ", "#    ", "#    "]),
+    ('Q', [" ### ", "#   #", "# # #", "#  # ", " ## #"]),
+    ('R', ["#### ", "#   #", "#### ", "#  # ", "#   #"]),
+    ('S', [" ####", "#    ", " ### ", "    #", "#### "]),
+    ('T', ["#####", "  #  ", "  #  ", "  #  ", "  #  "]),
+    ('U', ["#   #", "#   #", "#   #", "#   #", " ### "]),
+    ('V', ["#   #", "#   #", "#   #", " # # ", "  #  "]),
+    ('W', ["#   #", "#   #", "# # #", "## ##", "#   #"]),
+    ('X', ["#   #", " # # ", "  #  ", " # # ", "#   #"]),
+    ('Y', ["#   #", " # # ", "  #  ", "  #  ", "  #  "]),
+    ('Z', ["#####", "   # ", "  #  ", " #   ", "#####"]),
+    ('0', [" ### ", "#  ##", "# # #", "##  #", " ### "]),
+    ('1', ["  #  ", " ##  ", "  #  ", "  #  ", " ### "]),
+    ('2', [" ### ", "#   #", "  ## ", " #   ", "#####"]),
+    ('3', ["#### ", "    #", " ### ", "    #", "#### "]),
+    ('4', ["#   #", "#   #", "#####", "    #", "    #"]),
+    ('5', ["#####", "#    ", "#### ", "    #", "#### "]),
+    ('6', [" ### ", "#    ", "#### ", "#   #", " ### "]),
+    ('7', ["#####", "    #", "   # ", "  #  ", "  #  "]),
+    ('8', [" ### ", "#   #", " ### ", "#   #", " ### "]),
+    ('9', [" ### ", "#   #", " ####", "    #", " ### "]),
+    (' ', ["     ", "     ", "     ", "     ", "     "]),
+    ('!', ["  #  ", "  #  ", "  #  ", "     ", "  #  "]),
+    ('?', [" ### ", "#   #", "  ## ", "     ", "  #  "]),
+    ('.', ["     ", "     ", "     ", "     ", "  #  "]),
+    (',', ["     ", "     ", "     ", "  #  ", " #   "]),
+    ('-', ["     ", "     ", "#####", "     ", "     "]),
+    ('\'', ["  #  ", "  #  ", "     ", "     ", "     "]),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Style {
+    Walls,
+    Plaza,
+}
+
+// The message laid out over the cells of a maze, one glyph pixel per cell.
+#[derive(Clone, Debug)]
+pub struct Layout {
+    style: Style,
+    rows: i32,
+    cols: i32,
+    letters: Vec<bool>,
+}
+
+// The wall grid with the message pressed in and the squares tunnels must treat with care.
+struct Stamp {
+    walls: Vec<bool>,
+    letters: Vec<bool>,
+    pockets: Vec<bool>,
+    outline: Vec<bool>,
+}
+
+//
+// A message is written across the middle of the maze in a built-in block font, as in
+// -text "HELLO" or -text "HELLO:plaza". By default the letters are solid walls with a ring of
+// open floor around them so they read clearly between the corridors. A plaza does the opposite
+// and carves the letters out as open floor outlined by walls. Builders that can follow a mask
+// reserve the letters before they start and grow around them. Every other builder runs as usual
+// and the message is pressed into the finished maze. Either way the squares around the letters
+// are tunneled back together afterward so the solvers can still reach every part of the maze.
+// Holes inside letters such as A or O cannot be reached without crossing a letter so they stay
+// open but are reserved so that no start or finish is placed inside of them.
+//
+
+pub fn parse_message(arg: &str) -> Result<(String, Style), String> {
+    let (text, style) = match arg.rsplit_once(':') {
+        Some((text, "walls")) => (text, Style::Walls),
+        Some((text, "plaza")) => (text, Style::Plaza),
+        _ => (arg, Style::Walls),
+    };
+    let text = text.trim().to_ascii_uppercase();
+    if text.is_empty() {
+        return Err(String::from("message is empty"));
+    }
+    if let Some(c) = text.chars().find(|&c| glyph(c).is_none()) {
+        return Err(format!("no glyph for character[{}]", c));
+    }
+    Ok((text, style))
+}
+
+pub fn check_message(arg: &str) -> Result<(), String> {
+    parse_message(arg).map(|_| ())
+}
+
+// Words wrap onto new lines when a line would be too wide for the maze.
+pub fn layout(arg: &str, maze: &maze::Maze) -> Result<Layout, String> {
+    let (text, style) = parse_message(arg)?;
+    let rows = maze.rows() / 2;
+    let cols = maze.cols() / 2;
+    let too_small = || {
+        format!(
+            "message[{}] does not fit a {}x{} maze",
+            text,
+            maze.rows(),
+            maze.cols()
+        )
+    };
+    let max_chars = (cols - 2 * MARGIN_CELLS + 1) / GLYPH_ADVANCE;
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        if word.len() as i32 > max_chars {
+            return Err(too_small());
+        }
+        match lines.last_mut() {
+            Some(line) if (line.len() + 1 + word.len()) as i32 <= max_chars => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(String::from(word)),
+        }
+    }
+    let height = lines.len() as i32 * GLYPH_ADVANCE - 1;
+    if height > rows - 2 * MARGIN_CELLS {
+        return Err(too_small());
+    }
+    let mut letters = vec![false; (rows * cols) as usize];
+    let top = (rows - height) / 2;
+    for (l, line) in lines.iter().enumerate() {
+        let width = line.len() as i32 * GLYPH_ADVANCE - 1;
+        let left = (cols - width) / 2;
+        for (i, c) in line.chars().enumerate() {
+            let pixels = glyph(c).unwrap_or(&FONT[0].1);
+            for (r, pixel_row) in pixels.iter().enumerate() {
+                for (col, pixel) in pixel_row.chars().enumerate() {
+                    if pixel == '#' {
+                        let row = top + l as i32 * GLYPH_ADVANCE + r as i32;
+                        let col = left + i as i32 * GLYPH_ADVANCE + col as i32;
+                        letters[(row * cols + col) as usize] = true;
+                    }
+                }
+            }
+        }
+    }
+    Ok(Layout {
+        style,
+        rows,
+        cols,
+        letters,
+    })
+}
+
+impl Layout {
+    // The cells a mask should close so builders grow around the message.
+    pub fn letters(&self) -> &[bool] {
+        &self.letters
+    }
+
+    fn is_letter_cell(&self, row: i32, col: i32) -> bool {
+        self.letters
+            [(row.clamp(0, self.rows - 1) * self.cols + col.clamp(0, self.cols - 1)) as usize]
+    }
+}
+
+//
+// Data only maze generator
+//
+
+pub fn add_text(monitor: monitor::MazeReceiver) {
+    let mut lk = match monitor.solver.lock() {
+        Ok(l) => l,
+        Err(_) => print::maze_panic!("uncontested lock failure"),
+    };
+    let layout = layout_from(&lk.arg, &lk.maze);
+    let mut stamp = stamp_walls(&lk.maze, &layout);
+    while let Some(tunnel) = next_tunnel(&lk.maze, &stamp) {
+        tunnel.iter().for_each(|&i| stamp.walls[i] = false);
+    }
+    for (i, &wall) in stamp.walls.iter().enumerate() {
+        let p = caves::point_of(&lk.maze, i);
+        if let Some(after) = square_after(&lk.maze, &stamp.walls, p, wall) {
+            *lk.maze.get_mut(p.row, p.col) = after;
+        }
+    }
+    let reserved = reserved_cells(&lk.maze, &layout, &stamp.pockets);
+    lk.maze.reserve(&reserved);
+}
+
+//
+// History based generator for animation and playback.
+//
+
+pub fn add_text_history(monitor: monitor::MazeMonitor) {
+    let mut lk = match monitor.lock() {
+        Ok(l) => l,
+        Err(_) => print::maze_panic!("builder could not take lock"),
+    };
+    let layout = layout_from(&lk.arg, &lk.maze);
+    let mut stamp = stamp_walls(&lk.maze, &layout);
+    apply_walls_history(&mut lk.maze, &stamp.walls);
+    while let Some(tunnel) = next_tunnel(&lk.maze, &stamp) {
+        tunnel.iter().for_each(|&i| stamp.walls[i] = false);
+        apply_walls_history(&mut lk.maze, &stamp.walls);
+    }
+    let reserved = reserved_cells(&lk.maze, &layout, &stamp.pockets);
+    lk.maze.reserve(&reserved);
+}
+
+fn layout_from(arg: &Option<String>, maze: &maze::Maze) -> Layout {
+    match arg {
+        Some(arg) => match layout(arg, maze) {
+            Ok(layout) => layout,
+            Err(msg) => print::maze_panic!("{}", msg),
+        },
+        None => print::maze_panic!("text needs a message to write"),
+    }
+}
+
+#[inline]
+fn glyph(c: char) -> Option<&'static [&'static str; 5]> {
+    FONT.iter().find(|(g, _)| *g == c).map(|(_, pixels)| pixels)
+}
+
+// A square is a letter when it touches any letter cell, the same squares a mask of the letters
+// would close, so strokes are three squares thick. The outline is the ring of squares around them.
+fn stamp_walls(maze: &maze::Maze, layout: &Layout) -> Stamp {
+    let len = (maze.rows() * maze.cols()) as usize;
+    let letters: Vec<bool> = (0..len)
+        .map(|i| {
+            let p = caves::point_of(maze, i);
+            !caves::is_perimeter(maze, p)
+                && [(p.row - 1) / 2, p.row / 2].iter().any(|&r| {
+                    [(p.col - 1) / 2, p.col / 2]
+                        .iter()
+                        .any(|&c| layout.is_letter_cell(r, c))
+                })
+        })
+        .collect();
+    let pockets = pocket_squares(maze, &letters);
+    let mut walls: Vec<bool> = (0..len)
+        .map(|i| {
+            let p = caves::point_of(maze, i);
+            maze::is_wall(maze.get(p.row, p.col))
+        })
+        .collect();
+    let outline: Vec<bool> = (0..len)
+        .map(|i| {
+            let p = caves::point_of(maze, i);
+            !caves::is_perimeter(maze, p)
+                && !letters[i]
+                && caves::MOORE_NEIGHBORHOOD
+                    .iter()
+                    .any(|d| letters[caves::index_of(maze, p.row + d.row, p.col + d.col)])
+        })
+        .collect();
+    for i in 0..len {
+        if letters[i] {
+            walls[i] = layout.style == Style::Walls;
+        } else if pockets[i] || outline[i] {
+            walls[i] = layout.style == Style::Plaza;
+        }
+    }
+    Stamp {
+        walls,
+        letters,
+        pockets,
+        outline,
+    }
+}
+
+// Everything that is not a letter and cannot be reached from the edges of the maze without
+// crossing a letter is a pocket.
+fn pocket_squares(maze: &maze::Maze, letters: &[bool]) -> Vec<bool> {
+    let mut outside = vec![false; letters.len()];
+    let mut dfs: Vec<usize> = (0..letters.len())
+        .filter(|&i| {
+            let p = caves::point_of(maze, i);
+            !caves::is_perimeter(maze, p)
+                && !letters[i]
+                && (p.row == 1
+                    || p.col == 1
+                    || p.row == maze.rows() - 2
+                    || p.col == maze.cols() - 2)
+        })
+        .collect();
+    dfs.iter().for_each(|&i| outside[i] = true);
+    while let Some(cur) = dfs.pop() {
+        let p = caves::point_of(maze, cur);
+        for d in &maze::CARDINAL_DIRECTIONS {
+            let next = maze::Point {
+                row: p.row + d.row,
+                col: p.col + d.col,
+            };
+            let n = caves::index_of(maze, next.row, next.col);
+            if !caves::is_perimeter(maze, next) && !letters[n] && !outside[n] {
+                outside[n] = true;
+                dfs.push(n);
+            }
+        }
+    }
+    (0..letters.len())
+        .map(|i| !caves::is_perimeter(maze, caves::point_of(maze, i)) && !letters[i] && !outside[i])
+        .collect()
+}
+
+// Letters and the middle of every pocket are closed so solvers do not start or finish there.
+fn reserved_cells(maze: &maze::Maze, layout: &Layout, pockets: &[bool]) -> Vec<bool> {
+    (0..layout.rows * layout.cols)
+        .map(|i| {
+            let (row, col) = (i / layout.cols, i % layout.cols);
+            layout.letters[i as usize] || pockets[caves::index_of(maze, 2 * row + 1, 2 * col + 1)]
+        })
+        .collect()
+}
+
+// Searches outward from the largest region outside of the pockets until it reaches another region.
+// Tunnels never cut through a letter or a pocket and only cut an outline when there is no cheaper
+// way around so a plaza keeps its shape. Returns None once nothing else can be reached.
+fn next_tunnel(maze: &maze::Maze, stamp: &Stamp) -> Option<Vec<usize>> {
+    let walls = &stamp.walls;
+    let mut region = vec![usize::MAX; walls.len()];
+    let mut sizes = Vec::new();
+    for i in 0..walls.len() {
+        if walls[i] || region[i] != usize::MAX {
+            continue;
+        }
+        sizes.push(caves::flood_region(
+            maze,
+            walls,
+            &mut region,
+            i,
+            sizes.len(),
+        ));
+    }
+    let main = (0..walls.len())
+        .filter(|&i| region[i] != usize::MAX && !stamp.pockets[i])
+        .max_by_key(|&i| (sizes[region[i]], !region[i]))
+        .map(|i| region[i])?;
+    let mut cost = vec![usize::MAX; walls.len()];
+    let mut parents = vec![usize::MAX; walls.len()];
+    let mut pq: BinaryHeap<Reverse<(usize, usize)>> = BinaryHeap::new();
+    for i in (0..walls.len()).filter(|&i| region[i] == main) {
+        cost[i] = 0;
+        parents[i] = i;
+        pq.push(Reverse((0, i)));
+    }
+    while let Some(Reverse((c, cur))) = pq.pop() {
+        if c > cost[cur] {
+            continue;
+        }
+        if !walls[cur] && region[cur] != main {
+            let mut tunnel = Vec::new();
+            let mut step = parents[cur];
+            while walls[step] {
+                tunnel.push(step);
+                step = parents[step];
+            }
+            return Some(tunnel);
+        }
+        let p = caves::point_of(maze, cur);
+        for d in &maze::CARDINAL_DIRECTIONS {
+            let next = maze::Point {
+                row: p.row + d.row,
+                col: p.col + d.col,
+            };
+            if caves::is_perimeter(maze, next) {
+                continue;
+            }
+            let n = caves::index_of(maze, next.row, next.col);
+            if walls[n] && (stamp.letters[n] || stamp.pockets[n]) {
+                continue;
+            }
+            let step_cost = match (walls[n], stamp.outline[n]) {
+                (false, _) => 0,
+                (true, false) => 1,
+                (true, true) => OUTLINE_TUNNEL_COST,
+            };
+            if c + step_cost < cost[n] {
+                cost[n] = c + step_cost;
+                parents[n] = cur;
+                pq.push(Reverse((cost[n], n)));
+            }
+        }
+    }
+    None
+}
+
+// Every square that differs from the wall grid changes in a single burst.
+fn apply_walls_history(maze: &mut maze::Maze, walls: &[bool]) {
+    let mut delta_vec = Vec::new();
+    for (i, &wall) in walls.iter().enumerate() {
+        let p = caves::point_of(maze, i);
+        if let Some(after) = square_after(maze, walls, p, wall) {
+            delta_vec.push(maze::Delta {
+                id: p,
+                before: maze.get(p.row, p.col),
+                after,
+                burst: 1,
+            });
+        }
+    }
+    if delta_vec.is_empty() {
+        return;
+    }
+    let burst = delta_vec.len();
+    delta_vec[0].burst = burst;
+    delta_vec[burst - 1].burst = burst;
+    for d in delta_vec.iter() {
+        *maze.get_mut(d.id.row, d.id.col) = d.after;
+    }
+    maze.build_history.push_burst(delta_vec.as_slice());
+}
+
+// Paths the builder already carved keep their bits. Walls only need their lines updated. Regions
+// are joined square by square so any tunnels from a weave are flattened into plain walls.
+fn square_after(
+    maze: &maze::Maze,
+    walls: &[bool],
+    p: maze::Point,
+    wall: bool,
+) -> Option<maze::Square> {
+    let before = maze.get(p.row, p.col);
+    let after = match (wall, maze::is_path(before)) {
+        (false, true) => return None,
+        (false, false) => (before & !(maze::WALL_MASK | maze::TUNNEL_BIT)) | maze::PATH_BIT,
+        (true, _) => {
+            (before & !(maze::PATH_BIT | maze::WALL_MASK | maze::TUNNEL_BIT))
+                | caves::square_for(maze, walls, p, true)
+        }
+    };
+    (after != before).then_some(after)
+}
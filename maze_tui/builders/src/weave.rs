@@ -0,0 +1,222 @@
+use crate::build;
+use crate::disjoint;
+use maze;
+use print;
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+use std::collections::HashMap;
+
+const DEFAULT_DENSITY: f64 = 0.4;
+// Crossings never sit within this many cells of each other in a row or column. A tunnel is then
+// always entered from a plain cell and two passages never try to share the same railing.
+const CROSSING_SPACING: i32 = 2;
+
+// The crossing cell carries the over passage while the under passage runs along dir beneath it.
+#[derive(Clone, Copy)]
+struct Crossing {
+    cell: maze::Point,
+    under: maze::Point,
+    over: maze::Point,
+}
+
+//
+// A weave maze lets passages cross over and under each other. Before Kruskal's algorithm joins any
+// cells, crossings are scattered across the grid with the probability given as in weave:0.5. Each
+// crossing joins its over passage and its under passage in the disjoint sets just like any other
+// wall so Kruskal's algorithm still produces a perfect maze around them.
+//
+
+pub fn parse_density(arg: &str) -> Result<f64, String> {
+    match arg.parse::<f64>() {
+        Ok(p) if (0.0..=1.0).contains(&p) => Ok(p),
+        _ => Err(format!(
+            "weave density must be within [0.0, 1.0] not [{}]",
+            arg
+        )),
+    }
+}
+
+pub fn check_density(arg: &str) -> Result<(), String> {
+    parse_density(arg).map(|_| ())
+}
+
+//
+// Data only maze generator
+//
+
+pub fn generate_maze(monitor: monitor::MazeReceiver) {
+    let mut lk = match monitor.solver.lock() {
+        Ok(l) => l,
+        Err(_) => print::maze_panic!("uncontested lock failure"),
+    };
+    let density = density_from(&lk.arg);
+    build::fill_maze_with_walls(&mut lk.maze);
+    let mut gen = build::seeded_rng(&lk.maze);
+    let ids = tag_cells(&lk.maze);
+    let mut sets = disjoint::DisjointSet::new(ids.len());
+
+    for c in place_crossings(&lk.maze, &ids, &mut sets, density, &mut gen) {
+        build::join_squares(&mut lk.maze, offset(c.cell, c.over, -2), c.cell);
+        build::join_squares(&mut lk.maze, c.cell, offset(c.cell, c.over, 2));
+        build::carve_under_passage(&mut lk.maze, c.cell, c.under);
+    }
+    for w in &load_shuffled_walls(&lk.maze, &mut gen) {
+        if let Some((a, b)) = wall_cells(&lk.maze, &ids, *w) {
+            if sets.made_union(ids[&a], ids[&b]) {
+                build::join_squares(&mut lk.maze, a, b);
+            }
+        }
+    }
+}
+
+//
+// History based generator for animation and playback.
+//
+
+pub fn generate_history(monitor: monitor::MazeMonitor) {
+    let mut lk = match monitor.lock() {
+        Ok(l) => l,
+        Err(_) => print::maze_panic!("uncontested lock failure"),
+    };
+    let density = density_from(&lk.arg);
+    build::fill_maze_history_with_walls(&mut lk.maze);
+    let mut gen = build::seeded_rng(&lk.maze);
+    let ids = tag_cells(&lk.maze);
+    let mut sets = disjoint::DisjointSet::new(ids.len());
+
+    for c in place_crossings(&lk.maze, &ids, &mut sets, density, &mut gen) {
+        build::join_squares_history(&mut lk.maze, offset(c.cell, c.over, -2), c.cell);
+        build::join_squares_history(&mut lk.maze, c.cell, offset(c.cell, c.over, 2));
+        build::carve_under_passage_history(&mut lk.maze, c.cell, c.under);
+    }
+    for w in &load_shuffled_walls(&lk.maze, &mut gen) {
+        if let Some((a, b)) = wall_cells(&lk.maze, &ids, *w) {
+            if sets.made_union(ids[&a], ids[&b]) {
+                build::join_squares_history(&mut lk.maze, a, b);
+            }
+        }
+    }
+}
+
+//
+// Data only helpers available to all.
+//
+
+fn density_from(arg: &Option<String>) -> f64 {
+    match arg {
+        Some(arg) => match parse_density(arg) {
+            Ok(p) => p,
+            Err(msg) => print::maze_panic!("{}", msg),
+        },
+        None => DEFAULT_DENSITY,
+    }
+}
+
+// A crossing needs a cell on all four sides of it and all five cells must still be disconnected
+// so joining the over and under passages cannot form a loop.
+fn place_crossings(
+    maze: &maze::Maze,
+    ids: &HashMap<maze::Point, usize>,
+    sets: &mut disjoint::DisjointSet,
+    density: f64,
+    gen: &mut StdRng,
+) -> Vec<Crossing> {
+    let mut candidates = Vec::new();
+    for r in (3..maze.rows() - 3).step_by(2) {
+        for c in (3..maze.cols() - 3).step_by(2) {
+            candidates.push(maze::Point { row: r, col: c });
+        }
+    }
+    candidates.shuffle(gen);
+    let mut crossings: Vec<Crossing> = Vec::new();
+    for cell in candidates {
+        if !gen.gen_bool(density) || crossings.iter().any(|c| too_close(c.cell, cell)) {
+            continue;
+        }
+        let (over, under) = if gen.gen_bool(0.5) {
+            (maze::CARDINAL_DIRECTIONS[0], maze::CARDINAL_DIRECTIONS[1])
+        } else {
+            (maze::CARDINAL_DIRECTIONS[1], maze::CARDINAL_DIRECTIONS[0])
+        };
+        let joined = [
+            cell,
+            offset(cell, over, -2),
+            offset(cell, over, 2),
+            offset(cell, under, -2),
+            offset(cell, under, 2),
+        ]
+        .map(|p| sets.find(ids[&p]));
+        if (1..joined.len()).any(|i| joined[..i].contains(&joined[i])) {
+            continue;
+        }
+        sets.made_union(joined[0], joined[1]);
+        sets.made_union(joined[0], joined[2]);
+        sets.made_union(joined[3], joined[4]);
+        crossings.push(Crossing { cell, under, over });
+    }
+    crossings
+}
+
+#[inline]
+fn too_close(a: maze::Point, b: maze::Point) -> bool {
+    let spacing = CROSSING_SPACING * 2;
+    (a.row == b.row && (a.col - b.col).abs() <= spacing)
+        || (a.col == b.col && (a.row - b.row).abs() <= spacing)
+}
+
+#[inline]
+fn offset(p: maze::Point, dir: maze::Point, n: i32) -> maze::Point {
+    maze::Point {
+        row: p.row + dir.row * n,
+        col: p.col + dir.col * n,
+    }
+}
+
+// Tunnels are already part of a crossing so they are never knocked down as an ordinary wall.
+fn wall_cells(
+    maze: &maze::Maze,
+    ids: &HashMap<maze::Point, usize>,
+    w: maze::Point,
+) -> Option<(maze::Point, maze::Point)> {
+    if maze.tunnel_at(w.row, w.col) {
+        return None;
+    }
+    let dir = if w.row % 2 == 0 {
+        maze::CARDINAL_DIRECTIONS[2]
+    } else {
+        maze::CARDINAL_DIRECTIONS[1]
+    };
+    let (a, b) = (offset(w, dir, -1), offset(w, dir, 1));
+    if !ids.contains_key(&a) || !ids.contains_key(&b) {
+        print::maze_panic!("Weave couldn't find a cell id. Build broke.");
+    }
+    Some((a, b))
+}
+
+fn load_shuffled_walls(maze: &maze::Maze, gen: &mut StdRng) -> Vec<maze::Point> {
+    let mut walls = Vec::new();
+    for r in (1..maze.rows() - 1).step_by(2) {
+        for c in (2..maze.cols() - 1).step_by(2) {
+            walls.push(maze::Point { row: r, col: c });
+        }
+    }
+    for r in (2..maze.rows() - 1).step_by(2) {
+        for c in (1..maze.cols() - 1).step_by(2) {
+            walls.push(maze::Point { row: r, col: c });
+        }
+    }
+    walls.shuffle(gen);
+    walls
+}
+
+fn tag_cells(maze: &maze::Maze) -> HashMap<maze::Point, usize> {
+    let mut set_ids = HashMap::new();
+    let mut id = 0;
+    for r in (1..maze.rows() - 1).step_by(2) {
+        for c in (1..maze.cols() - 1).step_by(2) {
+            set_ids.insert(maze::Point { row: r, col: c }, id);
+            id += 1;
+        }
+    }
+    set_ids
+}
@@ -2,7 +2,7 @@ use crate::build;
 use maze;
 use print;
 
-use rand::{seq::SliceRandom, thread_rng, Rng};
+use rand::{seq::SliceRandom, Rng};
 
 const WALK_BIT: maze::Square = 0b0100_0000_0000_0000;
 
@@ -21,9 +21,9 @@ struct RandomWalk {
     next: maze::Point,
 }
 
-///
-/// Data only maze generator
-///
+//
+// Data only maze generator
+//
 
 pub fn generate_maze(monitor: monitor::MazeReceiver) {
     let mut lk = match monitor.solver.lock() {
@@ -31,7 +31,7 @@ pub fn generate_maze(monitor: monitor::MazeReceiver) {
         Err(_) => print::maze_panic!("uncontested lock failure"),
     };
     build::build_wall_outline(&mut lk.maze);
-    let mut rng = thread_rng();
+    let mut rng = build::seeded_rng(&lk.maze);
     let mut cur = RandomWalk {
         prev_row_start: 2,
         prev: maze::Point { row: 0, col: 0 },
@@ -149,9 +149,9 @@ fn build_with_marks(maze: &mut maze::Maze, cur: maze::Point, next: maze::Point)
     build::build_wall_line(maze, next);
 }
 
-///
-/// History based generator for animation and playback.
-///
+//
+// History based generator for animation and playback.
+//
 
 pub fn generate_history(monitor: monitor::MazeMonitor) {
     let mut lk = match monitor.lock() {
@@ -159,7 +159,7 @@ pub fn generate_history(monitor: monitor::MazeMonitor) {
         Err(_) => print::maze_panic!("uncontested lock failure"),
     };
     build::build_wall_outline_history(&mut lk.maze);
-    let mut rng = thread_rng();
+    let mut rng = build::seeded_rng(&lk.maze);
     let mut cur = RandomWalk {
         prev_row_start: 2,
         prev: maze::Point { row: 0, col: 0 },
@@ -425,9 +425,9 @@ fn bridge_gap_history(maze: &mut maze::Maze, cur: maze::Point, next: maze::Point
     build_walk_line_history(maze, wall);
 }
 
-///
-/// Logic utilities available to all.
-///
+//
+// Logic utilities available to all.
+//
 
 fn is_valid_step(maze: &maze::Maze, next: maze::Point, prev: maze::Point) -> bool {
     next.row >= 0
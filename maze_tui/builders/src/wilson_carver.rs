@@ -1,7 +1,7 @@
 use crate::build;
 use maze;
 
-use rand::{seq::SliceRandom, thread_rng, Rng};
+use rand::{seq::SliceRandom, Rng};
 
 const WALK_BIT: maze::Square = 0b0100_0000_0000_0000;
 
@@ -20,9 +20,9 @@ struct RandomWalk {
     next: maze::Point,
 }
 
-///
-/// Data only maze generator
-///
+//
+// Data only maze generator
+//
 
 pub fn generate_maze(monitor: monitor::MazeReceiver) {
     let mut lk = match monitor.solver.lock() {
@@ -30,17 +30,18 @@ pub fn generate_maze(monitor: monitor::MazeReceiver) {
         Err(_) => print::maze_panic!("uncontested lock failure"),
     };
     build::fill_maze_with_walls(&mut lk.maze);
-    let mut rng = thread_rng();
+    let mut rng = build::seeded_rng(&lk.maze);
     let start = maze::Point {
         row: 2 * (rng.gen_range(2..lk.maze.rows() - 1) / 2) + 1,
         col: 2 * (rng.gen_range(2..lk.maze.cols() - 1) / 2) + 1,
     };
+    let start = build::unmasked_start(&lk.maze, start);
     build::build_path(&mut lk.maze, start);
     *lk.maze.get_mut(start.row, start.col) |= build::BUILDER_BIT;
     let mut cur = RandomWalk {
         prev_row_start: 1,
         prev: maze::Point { row: 0, col: 0 },
-        walk: maze::Point { row: 1, col: 1 },
+        walk: build::unmasked_start(&lk.maze, maze::Point { row: 1, col: 1 }),
         next: maze::Point { row: 0, col: 0 },
     };
     *lk.maze.get_mut(cur.walk.row, cur.walk.col) &= !build::MARKERS_MASK;
@@ -152,9 +153,9 @@ fn build_with_marks(maze: &mut maze::Maze, cur: maze::Point, next: maze::Point)
     build::carve_path_walls(maze, next);
 }
 
-///
-/// History based generator for animation and playback.
-///
+//
+// History based generator for animation and playback.
+//
 
 pub fn generate_history(monitor: monitor::MazeMonitor) {
     let mut lk = match monitor.lock() {
@@ -162,17 +163,18 @@ pub fn generate_history(monitor: monitor::MazeMonitor) {
         Err(_) => print::maze_panic!("uncontested lock failure"),
     };
     build::fill_maze_history_with_walls(&mut lk.maze);
-    let mut rng = thread_rng();
+    let mut rng = build::seeded_rng(&lk.maze);
     let start = maze::Point {
         row: 2 * (rng.gen_range(2..lk.maze.rows() - 1) / 2) + 1,
         col: 2 * (rng.gen_range(2..lk.maze.cols() - 1) / 2) + 1,
     };
+    let start = build::unmasked_start(&lk.maze, start);
     build::build_path_history(&mut lk.maze, start);
     *lk.maze.get_mut(start.row, start.col) |= build::BUILDER_BIT;
     let mut cur = RandomWalk {
         prev_row_start: 1,
         prev: maze::Point { row: 0, col: 0 },
-        walk: maze::Point { row: 1, col: 1 },
+        walk: build::unmasked_start(&lk.maze, maze::Point { row: 1, col: 1 }),
         next: maze::Point { row: 0, col: 0 },
     };
     *lk.maze.get_mut(cur.walk.row, cur.walk.col) &= !build::MARKERS_MASK;
@@ -383,16 +385,18 @@ fn break_wall_history(maze: &mut maze::Maze, cur: maze::Point, next: maze::Point
     build_walk_square(maze, wall);
 }
 
-///
-/// Data only helpers for all.
-///
+//
+// Data only helpers for all.
+//
 
+// Masks leave dead end corridors a walk could never leave without stepping back the way it came.
 fn is_valid_step(maze: &maze::Maze, next: maze::Point, prev: maze::Point) -> bool {
     next.row > 0
         && next.row < maze.rows() - 1
         && next.col > 0
         && next.col < maze.cols() - 1
-        && next != prev
+        && (next != prev || maze.mask.is_some())
+        && !maze.is_masked(next)
 }
 
 fn backtrack_point(maze: &maze::Maze, walk: &maze::Point) -> &'static maze::Point {
@@ -0,0 +1,365 @@
+use builders::build;
+use solvers::solve;
+use std::{
+    collections::HashMap,
+    error, fmt,
+    fs::File,
+    io::{self, BufWriter},
+    path::Path,
+};
+
+pub type Rgb = [u8; 3];
+
+pub struct RasterArgs {
+    pub cell_size: u32,
+    // Only every nth step of a Tape becomes a GIF frame. Bursts always count as one step.
+    pub frame_skip: usize,
+    // GIF frame delays are measured in hundredths of a second.
+    pub frame_delay: u16,
+}
+
+#[derive(Debug)]
+pub enum RasterError {
+    Io(io::Error),
+    Png(png::EncodingError),
+    Gif(gif::EncodingError),
+    TooLarge { width: u64, height: u64 },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Building,
+    Solving,
+}
+
+// Pixels are kept as palette indices so GIF frames can be cut straight out of the canvas.
+struct Canvas {
+    cell: usize,
+    cols: usize,
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+struct Palette {
+    colors: Vec<Rgb>,
+    lookup: HashMap<Rgb, u8>,
+    exact: bool,
+}
+
+pub const DEFAULT_CELL_SIZE: u32 = 8;
+pub const DEFAULT_FRAME_SKIP: usize = 8;
+pub const DEFAULT_FRAME_DELAY: u16 = 2;
+const MAX_DIMENSION: u64 = u16::MAX as u64;
+const WALL_RGB: Rgb = [0, 0, 0];
+const PATH_RGB: Rgb = [255, 255, 255];
+const START_FINISH_RGB: Rgb = [0, 255, 255];
+// The xterm values for the few ANSI colors the backtracking builders mark squares with.
+const ANSI_RGB: [Rgb; 8] = [
+    [0, 0, 0],
+    [205, 0, 0],
+    [0, 205, 0],
+    [205, 205, 0],
+    [0, 0, 238],
+    [205, 0, 205],
+    [0, 205, 205],
+    [229, 229, 229],
+];
+const CUBE_LEVELS: usize = 6;
+
+impl Default for RasterArgs {
+    fn default() -> Self {
+        Self {
+            cell_size: DEFAULT_CELL_SIZE,
+            frame_skip: DEFAULT_FRAME_SKIP,
+            frame_delay: DEFAULT_FRAME_DELAY,
+        }
+    }
+}
+
+impl fmt::Display for RasterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RasterError::Io(e) => write!(f, "image file error: {}", e),
+            RasterError::Png(e) => write!(f, "png encoding error: {}", e),
+            RasterError::Gif(e) => write!(f, "gif encoding error: {}", e),
+            RasterError::TooLarge { width, height } => write!(
+                f,
+                "image of {}x{} pixels is too large, try a smaller cell size",
+                width, height
+            ),
+        }
+    }
+}
+
+impl error::Error for RasterError {}
+
+impl From<io::Error> for RasterError {
+    fn from(e: io::Error) -> Self {
+        RasterError::Io(e)
+    }
+}
+
+impl From<png::EncodingError> for RasterError {
+    fn from(e: png::EncodingError) -> Self {
+        RasterError::Png(e)
+    }
+}
+
+impl From<gif::EncodingError> for RasterError {
+    fn from(e: gif::EncodingError) -> Self {
+        RasterError::Gif(e)
+    }
+}
+
+//
+// Public rendering entry points. A PNG shows one finished maze and a GIF replays a whole history.
+//
+
+pub fn save_png(path: &Path, maze: &maze::Blueprint, cell_size: u32) -> Result<(), RasterError> {
+    let mut palette = Palette::from_squares(maze.buf.iter().map(|&s| (Phase::Solving, s)));
+    let mut canvas = Canvas::new(maze, cell_size)?;
+    for (i, &square) in maze.buf.iter().enumerate() {
+        canvas.draw_square(i, square, Phase::Solving, &mut palette);
+    }
+    let mut encoder = png::Encoder::new(
+        BufWriter::new(File::create(path)?),
+        canvas.width as u32,
+        canvas.height as u32,
+    );
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    let rgb: Vec<u8> = canvas
+        .pixels
+        .iter()
+        .flat_map(|&p| palette.colors[p as usize])
+        .collect();
+    writer.write_image_data(&rgb)?;
+    writer.finish()?;
+    Ok(())
+}
+
+// The maze must be rewound to the start of its animation as it is after recording or loading.
+pub fn save_gif(path: &Path, maze: &maze::Maze, args: &RasterArgs) -> Result<(), RasterError> {
+    let squares = maze
+        .as_slice()
+        .iter()
+        .map(|&s| (Phase::Building, s))
+        .chain(tape_squares(&maze.build_history, Phase::Building))
+        .chain(tape_squares(&maze.solve_history, Phase::Solving));
+    let mut palette = Palette::from_squares(squares);
+    let mut canvas = Canvas::new(&maze.maze, args.cell_size)?;
+    let mut cur = maze.maze.buf.clone();
+    for (i, &square) in cur.iter().enumerate() {
+        canvas.draw_square(i, square, Phase::Building, &mut palette);
+    }
+    let mut encoder = gif::Encoder::new(
+        BufWriter::new(File::create(path)?),
+        canvas.width as u16,
+        canvas.height as u16,
+        &palette.flat(),
+    )?;
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+    let full = (0, 0, maze.cols() as usize - 1, maze.rows() as usize - 1);
+    encoder.write_frame(&canvas.frame(full, args.frame_delay))?;
+    for (tape, phase) in [
+        (&maze.build_history, Phase::Building),
+        (&maze.solve_history, Phase::Solving),
+    ] {
+        // Switching phases changes how every marked square decodes so redraw the whole maze.
+        if phase == Phase::Solving {
+            for (i, &square) in cur.iter().enumerate() {
+                canvas.draw_square(i, square, phase, &mut palette);
+            }
+        }
+        let mut dirty: Option<(usize, usize, usize, usize)> = None;
+        let mut steps = 0;
+        let mut i = 0;
+        while i < tape.len() {
+            let burst = tape[i].burst.max(1);
+            for delta in tape.slice(i, (i + burst).min(tape.len())) {
+                let index = (delta.id.row * maze.cols() + delta.id.col) as usize;
+                cur[index] = delta.after;
+                canvas.draw_square(index, delta.after, phase, &mut palette);
+                let (c, r) = (delta.id.col as usize, delta.id.row as usize);
+                dirty = Some(match dirty {
+                    None => (c, r, c, r),
+                    Some((l, t, rt, b)) => (l.min(c), t.min(r), rt.max(c), b.max(r)),
+                });
+            }
+            i += burst;
+            steps += 1;
+            if steps % args.frame_skip.max(1) == 0 || i >= tape.len() {
+                if let Some(rect) = dirty.take() {
+                    encoder.write_frame(&canvas.frame(rect, args.frame_delay))?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn tape_squares(
+    tape: &maze::Tape,
+    phase: Phase,
+) -> impl Iterator<Item = (Phase, maze::Square)> + '_ {
+    (0..tape.len()).map(move |i| (phase, tape[i].after))
+}
+
+// Decoding follows the TUI frames. Builders mark squares with backtracking colors while solvers
+// and painters leave RGB paint in the low 24 bits of the path squares they touch.
+fn square_rgb(square: maze::Square, phase: Phase) -> Rgb {
+    if phase == Phase::Building && build::is_marked(square) {
+        let mark = build::BACKTRACKING_SYMBOLS
+            [(square & build::MARKERS_MASK) as usize % build::BACKTRACKING_SYMBOLS.len()];
+        return ANSI_RGB[mark.ansi as usize % ANSI_RGB.len()];
+    }
+    if maze::is_wall(square) {
+        return PATH_RGB;
+    }
+    if phase == Phase::Solving {
+        if solve::is_start(square) || solve::is_finish(square) {
+            return START_FINISH_RGB;
+        }
+        if solve::is_color(square) {
+            let paint = square & solve::THREAD_MASK;
+            return [(paint >> 16) as u8, (paint >> 8) as u8, paint as u8];
+        }
+    }
+    PATH_RGB
+}
+
+impl Canvas {
+    fn new(maze: &maze::Blueprint, cell_size: u32) -> Result<Self, RasterError> {
+        let cell = cell_size.max(1) as u64;
+        let (width, height) = (maze.cols as u64 * cell, maze.rows as u64 * cell);
+        if width > MAX_DIMENSION || height > MAX_DIMENSION {
+            return Err(RasterError::TooLarge { width, height });
+        }
+        Ok(Self {
+            cell: cell as usize,
+            cols: maze.cols as usize,
+            width: width as usize,
+            height: height as usize,
+            pixels: vec![0; (width * height) as usize],
+        })
+    }
+
+    // A wall is a thick center block with arms reaching the cell edges it connects through. The
+    // neighbor draws the other half of every arm so each cell can be redrawn on its own.
+    fn draw_square(&mut self, i: usize, square: maze::Square, phase: Phase, palette: &mut Palette) {
+        let (x0, y0) = ((i % self.cols) * self.cell, (i / self.cols) * self.cell);
+        let background = palette.index(square_rgb(square, phase));
+        self.fill(x0, y0, self.cell, self.cell, background);
+        if maze::is_path(square) {
+            return;
+        }
+        let wall = palette.index(WALL_RGB);
+        let thick = (self.cell / 3).max(1);
+        let lo = (self.cell - thick) / 2;
+        let hi = lo + thick;
+        self.fill(x0 + lo, y0 + lo, thick, thick, wall);
+        if square & maze::NORTH_WALL != 0 {
+            self.fill(x0 + lo, y0, thick, hi, wall);
+        }
+        if square & maze::SOUTH_WALL != 0 {
+            self.fill(x0 + lo, y0 + lo, thick, self.cell - lo, wall);
+        }
+        if square & maze::WEST_WALL != 0 {
+            self.fill(x0, y0 + lo, hi, thick, wall);
+        }
+        if square & maze::EAST_WALL != 0 {
+            self.fill(x0 + lo, y0 + lo, self.cell - lo, thick, wall);
+        }
+    }
+
+    fn fill(&mut self, x: usize, y: usize, w: usize, h: usize, color: u8) {
+        for row in y..y + h {
+            self.pixels[row * self.width + x..row * self.width + x + w].fill(color);
+        }
+    }
+
+    // Frames only cover the squares that changed and keep everything else from earlier frames.
+    fn frame(&self, cells: (usize, usize, usize, usize), delay: u16) -> gif::Frame<'static> {
+        let (left, top, right, bottom) = cells;
+        let (x, y) = (left * self.cell, top * self.cell);
+        let w = (right - left + 1) * self.cell;
+        let h = (bottom - top + 1) * self.cell;
+        let mut pixels = Vec::with_capacity(w * h);
+        for row in y..y + h {
+            pixels.extend_from_slice(&self.pixels[row * self.width + x..row * self.width + x + w]);
+        }
+        let mut frame = gif::Frame::from_indexed_pixels(w as u16, h as u16, pixels, None);
+        frame.left = x as u16;
+        frame.top = y as u16;
+        frame.delay = delay;
+        frame.dispose = gif::DisposalMethod::Keep;
+        frame
+    }
+}
+
+// Most mazes use only a few dozen colors but a painter gradient can exceed what a GIF palette
+// holds. In that case every color snaps to a fixed cube of levels instead.
+impl Palette {
+    fn from_squares(squares: impl Iterator<Item = (Phase, maze::Square)>) -> Self {
+        let mut palette = Self {
+            colors: Vec::new(),
+            lookup: HashMap::new(),
+            exact: true,
+        };
+        for rgb in [WALL_RGB, PATH_RGB, START_FINISH_RGB] {
+            palette.insert(rgb);
+        }
+        for (phase, square) in squares {
+            palette.insert(square_rgb(square, phase));
+            if palette.colors.len() > 256 {
+                return Self::cube();
+            }
+        }
+        palette
+    }
+
+    fn cube() -> Self {
+        let mut palette = Self {
+            colors: Vec::new(),
+            lookup: HashMap::new(),
+            exact: false,
+        };
+        for r in 0..CUBE_LEVELS {
+            for g in 0..CUBE_LEVELS {
+                for b in 0..CUBE_LEVELS {
+                    palette.insert([level(r), level(g), level(b)]);
+                }
+            }
+        }
+        palette.insert(START_FINISH_RGB);
+        palette
+    }
+
+    fn insert(&mut self, rgb: Rgb) {
+        if !self.lookup.contains_key(&rgb) {
+            self.lookup.insert(rgb, self.colors.len() as u8);
+            self.colors.push(rgb);
+        }
+    }
+
+    fn index(&mut self, rgb: Rgb) -> u8 {
+        if let Some(&i) = self.lookup.get(&rgb) {
+            return i;
+        }
+        if self.exact {
+            print::maze_panic!("color {:?} missing from a palette built for this maze", rgb);
+        }
+        let snap = |c: u8| level((c as usize * (CUBE_LEVELS - 1) + 127) / 255);
+        self.lookup[&[snap(rgb[0]), snap(rgb[1]), snap(rgb[2])]]
+    }
+
+    fn flat(&self) -> Vec<u8> {
+        self.colors.iter().flatten().copied().collect()
+    }
+}
+
+fn level(i: usize) -> u8 {
+    (i * 255 / (CUBE_LEVELS - 1)) as u8
+}
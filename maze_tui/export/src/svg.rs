@@ -0,0 +1,231 @@
+use solvers::solve;
+use std::{fmt::Write, fs, io, path::Path};
+
+pub const DEFAULT_CELL_SIZE: u32 = 10;
+const WALL_COLOR: &str = "#000000";
+const BACKGROUND_COLOR: &str = "#ffffff";
+const START_FINISH_COLOR: &str = "#00ffff";
+
+//
+// Every square becomes a cell of the same size. Walls are not drawn as blocks like the terminal
+// does. Instead each wall square reaches out to the neighbors its wall bits say it connects to so
+// the result looks like a maze drawn with a pen at any resolution.
+//
+
+pub fn save(path: &Path, maze: &maze::Blueprint, cell_size: u32, paint: bool) -> io::Result<()> {
+    fs::write(path, to_svg(maze, cell_size, paint))
+}
+
+pub fn to_svg(maze: &maze::Blueprint, cell_size: u32, paint: bool) -> String {
+    if maze.topology == maze::topology::Topology::Polar {
+        return to_polar_svg(maze, cell_size, paint);
+    }
+    let cell = cell_size.max(1) as i64;
+    let width = maze.cols as i64 * cell;
+    let height = maze.rows as i64 * cell;
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+        w = width,
+        h = height
+    )
+    .expect("writing to a String cannot fail");
+    writeln!(
+        svg,
+        "<rect width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+        width, height, BACKGROUND_COLOR
+    )
+    .expect("writing to a String cannot fail");
+    if paint {
+        write_paint(&mut svg, maze, cell);
+    }
+    write_walls(&mut svg, maze, cell);
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// A polar maze is drawn as the circle it stands for rather than the rows it is stored in. Each
+// ring is as thick as a cell is wide in the square drawing and every colored square of a ring
+// becomes the slice of the ring it covers.
+fn to_polar_svg(maze: &maze::Blueprint, cell_size: u32, paint: bool) -> String {
+    let cell = cell_size.max(1) as f64;
+    let rings = maze::topology::polar_rings(maze.rows, maze.cols);
+    let thickness = cell * 2.0;
+    let radius = thickness * rings.counts.len() as f64;
+    let center = radius + cell;
+    let size = (center * 2.0).ceil() as i64;
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{s}\" height=\"{s}\" viewBox=\"0 0 {s} {s}\">",
+        s = size
+    )
+    .expect("writing to a String cannot fail");
+    writeln!(
+        svg,
+        "<rect width=\"{s}\" height=\"{s}\" fill=\"{}\"/>",
+        BACKGROUND_COLOR,
+        s = size
+    )
+    .expect("writing to a String cannot fail");
+    let at = |r: f64, turn: f64| {
+        let angle = turn * std::f64::consts::TAU;
+        (center + r * angle.sin(), center - r * angle.cos())
+    };
+    if paint {
+        for ring in 0..rings.counts.len() {
+            let row = ring as i32 * 2 + 1;
+            let (inner, outer) = (ring as f64 * thickness, (ring + 1) as f64 * thickness);
+            for col in 0..rings.width {
+                let square = maze.get(row, col);
+                let fill = if maze::is_wall(square) {
+                    continue;
+                } else if solve::is_start(square) || solve::is_finish(square) {
+                    START_FINISH_COLOR.to_string()
+                } else if solve::is_color(square) {
+                    format!("#{:06x}", square & solve::THREAD_MASK)
+                } else {
+                    continue;
+                };
+                let (a, b) = (rings.turn(col), rings.turn(col + 1));
+                let (p0, p1, p2, p3) = (at(inner, a), at(outer, a), at(outer, b), at(inner, b));
+                writeln!(
+                    svg,
+                    "<path d=\"M{:.2} {:.2}L{:.2} {:.2}A{o:.2} {o:.2} 0 0 1 {:.2} {:.2}L{:.2} {:.2}A{i:.2} {i:.2} 0 0 0 {:.2} {:.2}Z\" fill=\"{}\"/>",
+                    p0.0, p0.1, p1.0, p1.1, p2.0, p2.1, p3.0, p3.1, p0.0, p0.1, fill,
+                    o = outer,
+                    i = inner
+                )
+                .expect("writing to a String cannot fail");
+            }
+        }
+    }
+    // A cell keeps the arc on its inner edge and the line on its counter clockwise edge unless
+    // the wall square standing for either one was carved.
+    let mut d = String::new();
+    for ring in 0..rings.counts.len() {
+        let (row, n, span) = (ring as i32 * 2 + 1, rings.counts[ring], rings.span(ring));
+        let (inner, outer) = (ring as f64 * thickness, (ring + 1) as f64 * thickness);
+        for i in 0..n {
+            let (a, b) = (rings.turn(i * span), rings.turn((i + 1) * span));
+            if ring > 0 && !maze.path_at(row - 1, i * span + 1) {
+                let (p0, p1) = (at(inner, a), at(inner, b));
+                write!(
+                    d,
+                    "M{:.2} {:.2}A{r:.2} {r:.2} 0 0 1 {:.2} {:.2}",
+                    p0.0,
+                    p0.1,
+                    p1.0,
+                    p1.1,
+                    r = inner
+                )
+                .expect("writing to a String cannot fail");
+            }
+            if n > 1 && !maze.path_at(row, i * span) {
+                let (p0, p1) = (at(inner, a), at(outer, a));
+                write!(d, "M{:.2} {:.2}L{:.2} {:.2}", p0.0, p0.1, p1.0, p1.1)
+                    .expect("writing to a String cannot fail");
+            }
+        }
+    }
+    writeln!(
+        svg,
+        "<path d=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{:.2}\" stroke-linecap=\"round\"/>",
+        d,
+        WALL_COLOR,
+        (cell / 4.0).max(1.0)
+    )
+    .expect("writing to a String cannot fail");
+    writeln!(
+        svg,
+        "<circle cx=\"{c:.2}\" cy=\"{c:.2}\" r=\"{:.2}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{:.2}\"/>",
+        radius,
+        WALL_COLOR,
+        (cell / 4.0).max(1.0),
+        c = center
+    )
+    .expect("writing to a String cannot fail");
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// Solver threads and painters both leave their color in the low 24 bits of a path square.
+fn write_paint(svg: &mut String, maze: &maze::Blueprint, cell: i64) {
+    svg.push_str("<g shape-rendering=\"crispEdges\">\n");
+    for (i, &square) in maze.buf.iter().enumerate() {
+        if maze::is_wall(square) {
+            continue;
+        }
+        let fill = if solve::is_start(square) || solve::is_finish(square) {
+            START_FINISH_COLOR.to_string()
+        } else if solve::is_color(square) {
+            format!("#{:06x}", square & solve::THREAD_MASK)
+        } else {
+            continue;
+        };
+        let (row, col) = (i as i64 / maze.cols as i64, i as i64 % maze.cols as i64);
+        writeln!(
+            svg,
+            "<rect x=\"{}\" y=\"{}\" width=\"{c}\" height=\"{c}\" fill=\"{}\"/>",
+            col * cell,
+            row * cell,
+            fill,
+            c = cell
+        )
+        .expect("writing to a String cannot fail");
+    }
+    svg.push_str("</g>\n");
+}
+
+// Only the east and south connections are drawn from each wall so every segment appears once. A
+// floating wall with no connections is a zero length segment that the line cap turns into a dot.
+// Walls between hex or triangle cells that meet at an angle also cross their square diagonally.
+fn write_walls(svg: &mut String, maze: &maze::Blueprint, cell: i64) {
+    let (cap, stroke) = match maze.wall_style_index {
+        s if s == maze::MazeStyle::Round as usize => ("round", cell / 4),
+        s if s == maze::MazeStyle::Bold as usize => ("square", cell / 2),
+        s if s == maze::MazeStyle::Contrast as usize => ("square", cell),
+        _ => ("square", cell / 4),
+    };
+    let mut d = String::new();
+    let half = cell / 2;
+    for (i, &square) in maze.buf.iter().enumerate() {
+        if maze::is_path(square) {
+            continue;
+        }
+        let x = (i as i64 % maze.cols as i64) * cell + half;
+        let y = (i as i64 / maze.cols as i64) * cell + half;
+        let walls = square & maze::WALL_MASK;
+        match maze.slant_at(i as i32 / maze.cols, i as i32 % maze.cols) {
+            Some(maze::topology::UP_SLANT) => {
+                write!(d, "M{} {}l{} {}", x - half, y + half, cell, -cell)
+                    .expect("writing to a String cannot fail");
+            }
+            Some(_) => {
+                write!(d, "M{} {}l{} {}", x - half, y - half, cell, cell)
+                    .expect("writing to a String cannot fail");
+            }
+            None if walls == maze::FLOATING_WALL => {
+                write!(d, "M{} {}h0", x, y).expect("writing to a String cannot fail");
+                continue;
+            }
+            None => {}
+        }
+        if walls & maze::EAST_WALL != 0 {
+            write!(d, "M{} {}h{}", x, y, cell).expect("writing to a String cannot fail");
+        }
+        if walls & maze::SOUTH_WALL != 0 {
+            write!(d, "M{} {}v{}", x, y, cell).expect("writing to a String cannot fail");
+        }
+    }
+    writeln!(
+        svg,
+        "<path d=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\" stroke-linecap=\"{}\"/>",
+        d,
+        WALL_COLOR,
+        stroke.max(1),
+        cap
+    )
+    .expect("writing to a String cannot fail");
+}
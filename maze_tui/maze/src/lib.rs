@@ -26,12 +26,23 @@
 // maze start bit--------||| |||| |||| |||| |||| |||| |||| ||||
 // maze goals bit-------|||| |||| |||| |||| |||| |||| |||| ||||
 //                    0b0000 0000 0000 0000 0000 0000 0000 0000
+//
+// Squares are wider than these 32 bits. The lowest bit above them marks the tunnel walls of a
+// weave maze and is never touched by a solver so it survives every solve untouched. The two bits
+// above the tunnel belong to solvers that count their visits to a square, such as Tremaux.
+use rand::{rngs::StdRng, SeedableRng};
+use std::cmp;
 use std::ops::{Index, IndexMut};
 
+pub mod mask;
+pub mod save;
+pub mod topology;
+
 // Public Types
 
-pub type Square = u32;
-pub type WallLine = u32;
+pub type Square = u64;
+pub type WallLine = u64;
+pub type Seed = u64;
 
 #[derive(Default, Debug, PartialEq, Eq, Hash, Copy, Clone)]
 pub struct Point {
@@ -63,6 +74,8 @@ pub struct MazeArgs {
     pub odd_cols: i32,
     pub offset: Offset,
     pub style: MazeStyle,
+    pub seed: Seed,
+    pub topology: topology::Topology,
 }
 
 // This is at the core of our maze. The fundamental information and structure we need.
@@ -73,6 +86,7 @@ pub struct Blueprint {
     pub cols: i32,
     pub offset: Offset,
     pub wall_style_index: usize,
+    pub topology: topology::Topology,
 }
 
 // We will also be tracking how our maze changes for the TUI animation playback.
@@ -97,11 +111,20 @@ pub struct Maze {
     pub maze: Blueprint,
     pub build_history: Tape,
     pub solve_history: Tape,
+    // Every random choice made while building or solving derives from this seed.
+    pub seed: Seed,
+    // Builders that honor a mask only carve the cells it leaves open. One entry per cell.
+    pub mask: Option<mask::Mask>,
+    // Squares placed by hand in the editor. Solvers start and finish there before choosing their own.
+    pub placed_start: Option<Point>,
+    pub placed_finish: Option<Point>,
 }
 // Read Only Data Available to Any Maze Users
 
 // Any modification made to these bits by a builder MUST be cleared before build process completes.
 pub const CLEAR_AVAILABLE_BITS: Square = 0x10FFFFFF;
+// Builders mark and solvers paint squares in the low bits so any of them set means a color shows.
+const COLOR_MASK: Square = 0xFFFFFF;
 
 pub const DEFAULT_ROWS: i32 = 31;
 pub const DEFAULT_COLS: i32 = 111;
@@ -113,6 +136,11 @@ pub const NORTH_WALL: WallLine = 0x1000000;
 pub const EAST_WALL: WallLine = 0x2000000;
 pub const SOUTH_WALL: WallLine = 0x4000000;
 pub const WEST_WALL: WallLine = 0x8000000;
+// A tunnel is a wall square flanking a crossing that a passage runs underneath. Every bit of the
+// low word already belongs to a builder or solver so tunnels live above it where nothing else looks.
+pub const TUNNEL_BIT: Square = 0x100000000;
+pub const VERTICAL_TUNNEL: char = '╎';
+pub const HORIZONTAL_TUNNEL: char = '╌';
 // Walls are constructed in terms of other walls they need to connect to. For example, read
 // 0b0011 as, "this is a wall square that must connect to other walls to the East and North."
 const WALL_ROW: usize = 16;
@@ -174,9 +202,69 @@ impl Maze {
                 cols,
                 offset: args.offset,
                 wall_style_index: args.style as usize,
+                topology: args.topology,
             },
             build_history: Tape::default(),
             solve_history: Tape::default(),
+            seed: args.seed,
+            mask: None,
+            placed_start: None,
+            placed_finish: None,
+        }
+    }
+
+    // A maze loaded from a file has no history yet but may still be solved and painted.
+    pub fn from_blueprint(maze: Blueprint, seed: Seed) -> Self {
+        Self {
+            maze,
+            build_history: Tape::default(),
+            solve_history: Tape::default(),
+            seed,
+            mask: None,
+            placed_start: None,
+            placed_finish: None,
+        }
+    }
+
+    pub fn set_mask(&mut self, template: &mask::Mask) {
+        self.mask = Some(template.stretch(self.rows() / 2, self.cols() / 2));
+    }
+
+    // Closes the given cells on top of any mask already in place so builders and solvers avoid them.
+    pub fn reserve(&mut self, cells: &[bool]) {
+        let rows = self.rows() / 2;
+        let cols = self.cols() / 2;
+        let template = match &self.mask {
+            Some(m) => m.clone(),
+            None => match mask::Mask::new(rows as usize, cols as usize, vec![true; cells.len()]) {
+                Ok(m) => m,
+                Err(_) => return,
+            },
+        };
+        self.mask = Some(template.close(cells));
+    }
+
+    // Wall squares touch the cells on either side of them and are masked if any of those cells are
+    // so carving a wall can never open a path out of the shape.
+    #[inline]
+    pub fn is_masked(&self, p: Point) -> bool {
+        match &self.mask {
+            None => false,
+            Some(m) => {
+                let rows = [(p.row - 1) / 2, p.row / 2].map(|r| r.clamp(0, m.rows() - 1));
+                let cols = [(p.col - 1) / 2, p.col / 2].map(|c| c.clamp(0, m.cols() - 1));
+                rows.iter().any(|&r| cols.iter().any(|&c| !m.is_open(r, c)))
+            }
+        }
+    }
+
+    // Applies every recorded delta so a maze rewound for playback shows its finished state.
+    pub fn play_to_end(&mut self) {
+        for tape in [&self.build_history, &self.solve_history] {
+            for delta in tape.steps.iter() {
+                self.maze.buf[(delta.id.row * self.maze.cols + delta.id.col) as usize] =
+                    delta.after;
+            }
         }
     }
 
@@ -195,6 +283,22 @@ impl Maze {
         self.maze.cols
     }
 
+    #[inline]
+    pub fn seed(&self) -> Seed {
+        self.seed
+    }
+
+    // Builders draw from the seed as is. Anything run after them xors in a stream of its own so
+    // it never repeats the builder's choices while the same seed still replays all of them.
+    pub fn seeded_rng(&self, stream: Seed) -> StdRng {
+        StdRng::seed_from_u64(self.seed ^ stream)
+    }
+
+    #[inline]
+    pub fn topology(&self) -> topology::Topology {
+        self.maze.topology
+    }
+
     #[inline]
     pub fn wall_char(&self, square: Square) -> char {
         WALL_STYLES[(self.maze.wall_style_index * WALL_ROW)
@@ -246,6 +350,61 @@ impl Maze {
     pub fn path_at(&self, row: i32, col: i32) -> bool {
         self.maze.path_at(row, col)
     }
+
+    #[inline]
+    pub fn tunnel_at(&self, row: i32, col: i32) -> bool {
+        self.maze.tunnel_at(row, col)
+    }
+
+    // Moving from one square to the next is a single step unless the step enters a tunnel from
+    // outside of its crossing. Then the passage continues underneath to the cell past the crossing.
+    // Every solver moves with this so weave mazes are traversed the same way as any other maze.
+    // It is also the neighbor query for a polar maze, where a step off the end of a ring wraps.
+    pub fn step(&self, cur: Point, dir: Point) -> Point {
+        let at = |n: i32| Point {
+            row: cur.row + dir.row * n,
+            col: cur.col + dir.col * n,
+        };
+        if self.maze.topology == topology::Topology::Polar {
+            return self.maze.topology.wrap(self.rows(), self.cols(), at(1));
+        }
+        let inside =
+            |p: Point| p.row >= 0 && p.row < self.rows() && p.col >= 0 && p.col < self.cols();
+        let (behind, exit) = (at(-1), at(4));
+        if inside(behind)
+            && inside(exit)
+            && self.tunnel_at(at(1).row, at(1).col)
+            && !self.tunnel_at(behind.row, behind.col)
+            && self.path_at(at(2).row, at(2).col)
+            && self.tunnel_at(at(3).row, at(3).col)
+            && self.path_at(exit.row, exit.col)
+        {
+            return exit;
+        }
+        at(1)
+    }
+
+    // A tunnel always has a partner on the far side of the crossing it runs under.
+    pub fn tunnel_partner(&self, p: Point) -> Option<Point> {
+        let candidates = if p.row % 2 == 1 {
+            [Point { row: 0, col: 2 }, Point { row: 0, col: -2 }]
+        } else {
+            [Point { row: 2, col: 0 }, Point { row: -2, col: 0 }]
+        };
+        candidates
+            .iter()
+            .map(|d| Point {
+                row: p.row + d.row,
+                col: p.col + d.col,
+            })
+            .find(|n| {
+                n.row > 0
+                    && n.row < self.rows() - 1
+                    && n.col > 0
+                    && n.col < self.cols() - 1
+                    && self.tunnel_at(n.row, n.col)
+            })
+    }
 }
 
 impl Blueprint {
@@ -280,10 +439,26 @@ impl Blueprint {
         (self.buf[(row * self.cols + col) as usize] & PATH_BIT) != 0
     }
 
+    #[inline]
+    pub fn tunnel_at(&self, row: i32, col: i32) -> bool {
+        is_tunnel(self.buf[(row * self.cols + col) as usize])
+    }
+
     #[inline]
     pub fn is_mini(&self) -> bool {
         self.wall_style_index == (MazeStyle::Mini as usize)
     }
+
+    // Only plain walls are slanted. Tunnels and builder marks keep their own glyphs.
+    #[inline]
+    pub fn slant_at(&self, row: i32, col: i32) -> Option<char> {
+        let square = self.get(row, col);
+        if is_path(square) || is_tunnel(square) || square & COLOR_MASK != 0 {
+            return None;
+        }
+        self.topology
+            .slant(self.rows, self.cols, Point { row, col })
+    }
 }
 
 impl Default for MazeArgs {
@@ -293,15 +468,17 @@ impl Default for MazeArgs {
             odd_cols: DEFAULT_COLS,
             style: MazeStyle::Sharp,
             offset: Offset::default(),
+            seed: 0,
+            topology: topology::Topology::Square,
         }
     }
 }
 
-///
-/// The Tape data structure implementation is concerned with sensible ways to step through the
-/// history of deltas as a maze build and solve operation completes. We only need an index and
-/// we track deltas as simple before and after u32's and what square changed.
-///
+//
+// The Tape data structure implementation is concerned with sensible ways to step through the
+// history of deltas as a maze build and solve operation completes. We only need an index and
+// we track deltas as simple before and after squares and what square changed.
+//
 
 impl Index<usize> for Tape {
     type Output = Delta;
@@ -329,11 +506,12 @@ impl Tape {
         self.steps.is_empty()
     }
 
+    // The index rests at the start of a burst so the end is the first delta of the last burst.
     pub fn end(&mut self) {
         if self.steps.is_empty() {
             panic!("no tape to end because no deltas provided");
         }
-        self.i = self.steps.len() - 1;
+        self.i = self.last_burst_index();
     }
 
     pub fn start(&mut self) {
@@ -421,6 +599,55 @@ impl Tape {
         self.steps.push(s);
     }
 
+    pub fn pos(&self) -> usize {
+        self.i
+    }
+
+    // Moves straight to the burst holding target and writes every burst passed on the way into
+    // the maze, after values going forward and before values going back. Bursts before the
+    // index end up applied and the burst at the index does not, except past the last delta where
+    // every burst is applied and the index rests on the last burst as it does when playback ends.
+    pub fn seek(&mut self, maze: &mut Blueprint, target: usize) {
+        if self.steps.is_empty() {
+            return;
+        }
+        if target >= self.steps.len() {
+            for d in &self.steps[self.i..] {
+                *maze.get_mut(d.id.row, d.id.col) = d.after;
+            }
+            self.i = self.last_burst_index();
+            return;
+        }
+        if target < self.i {
+            let end = cmp::min(self.i + self.steps[self.i].burst.max(1), self.steps.len());
+            while self.i > target && self.set_prev() {}
+            for d in self.steps[self.i..end].iter().rev() {
+                *maze.get_mut(d.id.row, d.id.col) = d.before;
+            }
+            return;
+        }
+        let start = self.i;
+        while self.i + self.steps[self.i].burst.max(1) <= target && self.set_next() {}
+        for d in &self.steps[start..self.i] {
+            *maze.get_mut(d.id.row, d.id.col) = d.after;
+        }
+    }
+
+    // Takes back the most recent burst so a history still being written can be undone.
+    pub fn pop_burst(&mut self) -> Vec<Delta> {
+        let last = self.last_burst_index();
+        let burst = self.steps.split_off(last);
+        self.i = self.i.min(self.last_burst_index());
+        burst
+    }
+
+    fn last_burst_index(&self) -> usize {
+        match self.steps.last() {
+            Some(d) => self.steps.len().saturating_sub(d.burst.max(1)),
+            None => 0,
+        }
+    }
+
     pub fn at_end(&self) -> bool {
         self.i == self.peek_next_index()
     }
@@ -442,9 +669,9 @@ impl Tape {
     }
 }
 
-///
-/// Free functions for when the maze object is not present but we still want static info.
-///
+//
+// Free functions for when the maze object is not present but we still want static info.
+//
 
 #[inline]
 pub fn wall_row(row_index: usize) -> &'static [char] {
@@ -465,3 +692,108 @@ pub fn is_wall(square: Square) -> bool {
 pub fn is_path(square: Square) -> bool {
     (square & PATH_BIT) != 0
 }
+
+#[inline]
+pub fn is_tunnel(square: Square) -> bool {
+    (square & (PATH_BIT | TUNNEL_BIT)) == TUNNEL_BIT
+}
+
+// Tunnels are drawn as dashed walls running the same way as the railing they form.
+#[inline]
+pub fn tunnel_char(square: Square) -> char {
+    if square & (NORTH_WALL | SOUTH_WALL) != 0 {
+        VERTICAL_TUNNEL
+    } else {
+        HORIZONTAL_TUNNEL
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // One delta per square so every square shows exactly which bursts have been applied.
+    fn burst_tape(bursts: &[usize]) -> (Blueprint, Tape) {
+        let maze = Maze::new(MazeArgs {
+            odd_rows: 5,
+            odd_cols: 5,
+            offset: Offset::default(),
+            style: MazeStyle::Sharp,
+            seed: 0,
+            topology: topology::Topology::Square,
+        });
+        let mut tape = Tape::default();
+        let mut square = 0;
+        for &len in bursts {
+            let burst: Vec<Delta> = (square..square + len as i32)
+                .map(|i| Delta {
+                    id: Point {
+                        row: i / maze.cols(),
+                        col: i % maze.cols(),
+                    },
+                    before: 0,
+                    after: PATH_BIT,
+                    burst: len,
+                })
+                .collect();
+            tape.push_burst(&burst);
+            square += len as i32;
+        }
+        (maze.maze, tape)
+    }
+
+    fn applied(maze: &Blueprint, tape: &Tape) -> usize {
+        let applied = (0..tape.len())
+            .take_while(|&i| is_path(maze.get(tape[i].id.row, tape[i].id.col)))
+            .count();
+        assert!(
+            (applied..tape.len()).all(|i| !is_path(maze.get(tape[i].id.row, tape[i].id.col))),
+            "bursts applied out of order"
+        );
+        applied
+    }
+
+    #[test]
+    fn seek_forward_stops_at_the_burst_holding_target() {
+        let (mut maze, mut tape) = burst_tape(&[1, 3, 2, 4]);
+        tape.seek(&mut maze, 2);
+        assert_eq!(tape.pos(), 1);
+        assert_eq!(applied(&maze, &tape), 1);
+        tape.seek(&mut maze, 4);
+        assert_eq!(tape.pos(), 4);
+        assert_eq!(applied(&maze, &tape), 4);
+        tape.seek(&mut maze, 9);
+        assert_eq!(tape.pos(), 6);
+        assert_eq!(applied(&maze, &tape), 6);
+    }
+
+    #[test]
+    fn seek_backward_takes_back_every_burst_crossed() {
+        let (mut maze, mut tape) = burst_tape(&[1, 3, 2, 4]);
+        tape.seek(&mut maze, 7);
+        assert_eq!(applied(&maze, &tape), 6);
+        tape.seek(&mut maze, 5);
+        assert_eq!(tape.pos(), 4);
+        assert_eq!(applied(&maze, &tape), 4);
+        tape.seek(&mut maze, 3);
+        assert_eq!(tape.pos(), 1);
+        assert_eq!(applied(&maze, &tape), 1);
+        tape.seek(&mut maze, 0);
+        assert_eq!(tape.pos(), 0);
+        assert_eq!(applied(&maze, &tape), 0);
+    }
+
+    #[test]
+    fn seek_past_the_end_applies_everything_and_rests_on_the_last_burst() {
+        let (mut maze, mut tape) = burst_tape(&[1, 3, 2, 4]);
+        tape.seek(&mut maze, usize::MAX);
+        assert_eq!(tape.pos(), 6);
+        assert_eq!(applied(&maze, &tape), tape.len());
+        tape.seek(&mut maze, 5);
+        assert_eq!(tape.pos(), 4);
+        assert_eq!(applied(&maze, &tape), 4);
+        tape.seek(&mut maze, usize::MAX);
+        tape.seek(&mut maze, 0);
+        assert_eq!(applied(&maze, &tape), 0);
+    }
+}
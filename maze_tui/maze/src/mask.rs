@@ -0,0 +1,452 @@
+use crate::Point;
+use std::collections::VecDeque;
+use std::{error, fmt, fs, io, path::Path};
+
+// A mask is a small picture of where a maze may go. It is drawn at whatever size is convenient
+// and stretched over the cells of the maze when the maze is created, so a 20x10 letter becomes a
+// maze of any size in the same shape. Builders that honor a mask never carve a masked cell and
+// solvers never place a start or finish on one.
+//
+// Masks may be read from three kinds of file.
+//
+// ASCII art----|any character other than a space or a period is inside the shape. Every line is
+//              |the same width and tabs or other control characters are rejected.
+// PBM----------|P1 or P4 bitmaps where black pixels are inside the shape.
+// PGM----------|P2 or P5 graymaps where pixels darker than half of the max value are inside.
+//
+// Letters and logos are often made of separate pieces. A maze cannot be solved from one piece to
+// another so the stretched mask opens the shortest bridge of cells between the pieces until
+// everything inside the shape is one region.
+
+const ASCII_OUTSIDE: [char; 2] = [' ', '.'];
+
+#[derive(Debug, Clone, Default)]
+pub struct Mask {
+    rows: i32,
+    cols: i32,
+    open: Vec<bool>,
+}
+
+#[derive(Debug)]
+pub enum MaskError {
+    Io(io::Error),
+    Image(String),
+    Empty,
+}
+
+impl fmt::Display for MaskError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MaskError::Io(e) => write!(f, "mask file error: {}", e),
+            MaskError::Image(reason) => write!(f, "mask image error: {}", reason),
+            MaskError::Empty => write!(f, "mask has no cells inside the shape"),
+        }
+    }
+}
+
+impl error::Error for MaskError {}
+
+impl From<io::Error> for MaskError {
+    fn from(e: io::Error) -> Self {
+        MaskError::Io(e)
+    }
+}
+
+pub fn load(path: &Path) -> Result<Mask, MaskError> {
+    let bytes = fs::read(path)?;
+    if matches!(bytes.get(0..2), Some(b"P1" | b"P2" | b"P4" | b"P5")) {
+        return from_netpbm(&bytes);
+    }
+    match std::str::from_utf8(&bytes) {
+        Ok(text) => from_ascii(text),
+        Err(_) => Err(MaskError::Image(String::from("unknown mask format"))),
+    }
+}
+
+pub fn from_ascii(text: &str) -> Result<Mask, MaskError> {
+    let lines: Vec<&str> = text.trim_end_matches(['\n', '\r']).lines().collect();
+    let rows = lines.len();
+    let cols = lines.first().map_or(0, |l| l.chars().count());
+    let mut open = Vec::with_capacity(rows * cols);
+    for (r, line) in lines.iter().enumerate() {
+        if line.chars().count() != cols {
+            return Err(MaskError::Image(format!(
+                "line {} is {} characters wide but line 1 is {}, pad lines with periods",
+                r + 1,
+                line.chars().count(),
+                cols
+            )));
+        }
+        for ch in line.chars() {
+            if ch.is_control() {
+                return Err(MaskError::Image(format!(
+                    "line {} has unsupported character {:?}",
+                    r + 1,
+                    ch
+                )));
+            }
+            open.push(!ASCII_OUTSIDE.contains(&ch) && !ch.is_whitespace());
+        }
+    }
+    Mask::new(rows, cols, open)
+}
+
+pub fn from_netpbm(bytes: &[u8]) -> Result<Mask, MaskError> {
+    let mut reader = NetpbmReader { bytes, i: 2 };
+    let kind = &bytes[0..2];
+    let cols = reader.number()?;
+    let rows = reader.number()?;
+    let max = match kind {
+        b"P2" | b"P5" => reader.number()?,
+        _ => 1,
+    };
+    if max == 0 || max > u16::MAX as usize {
+        return Err(MaskError::Image(format!("invalid max value {}", max)));
+    }
+    let pixels = rows
+        .checked_mul(cols)
+        .ok_or(MaskError::Image(String::from("image is too large")))?;
+    let open: Vec<bool> = match kind {
+        b"P1" => (0..pixels)
+            .map(|_| reader.bit())
+            .collect::<Result<_, _>>()?,
+        b"P2" => (0..pixels)
+            .map(|_| reader.number().map(|gray| gray < max.div_ceil(2)))
+            .collect::<Result<_, _>>()?,
+        b"P4" => {
+            let raster = reader.raster((cols.div_ceil(8)) * rows)?;
+            (0..pixels)
+                .map(|i| {
+                    let byte = raster[(i / cols) * cols.div_ceil(8) + (i % cols) / 8];
+                    (byte >> (7 - (i % cols) % 8)) & 1 == 1
+                })
+                .collect()
+        }
+        _ => {
+            let width = if max < 256 { 1 } else { 2 };
+            let raster = reader.raster(pixels * width)?;
+            raster
+                .chunks(width)
+                .map(|px| {
+                    let gray = px.iter().fold(0usize, |acc, &b| (acc << 8) | b as usize);
+                    gray < max.div_ceil(2)
+                })
+                .collect()
+        }
+    };
+    Mask::new(rows, cols, open)
+}
+
+impl Mask {
+    pub fn new(rows: usize, cols: usize, open: Vec<bool>) -> Result<Self, MaskError> {
+        if rows == 0 || cols == 0 || open.len() != rows * cols {
+            return Err(MaskError::Image(format!(
+                "{}x{} mask has {} pixels",
+                rows,
+                cols,
+                open.len()
+            )));
+        }
+        if !open.iter().any(|&o| o) {
+            return Err(MaskError::Empty);
+        }
+        Ok(Self {
+            rows: rows as i32,
+            cols: cols as i32,
+            open,
+        })
+    }
+
+    #[inline]
+    pub fn rows(&self) -> i32 {
+        self.rows
+    }
+
+    #[inline]
+    pub fn cols(&self) -> i32 {
+        self.cols
+    }
+
+    #[inline]
+    pub fn is_open(&self, row: i32, col: i32) -> bool {
+        self.open[(row * self.cols + col) as usize]
+    }
+
+    // Samples this mask once per cell of the target grid and joins the pieces that result.
+    pub fn stretch(&self, rows: i32, cols: i32) -> Mask {
+        let mut open: Vec<bool> = (0..rows * cols)
+            .map(|i| {
+                let r = (i / cols) as i64 * self.rows as i64 / rows as i64;
+                let c = (i % cols) as i64 * self.cols as i64 / cols as i64;
+                self.is_open(r as i32, c as i32)
+            })
+            .collect();
+        // A tiny maze may miss every open pixel of a detailed mask so keep at least the middle.
+        if !open.iter().any(|&o| o) {
+            open[((rows / 2) * cols + cols / 2) as usize] = true;
+        }
+        let mut stretched = Mask { rows, cols, open };
+        while let Some(bridge) = stretched.next_bridge() {
+            bridge.iter().for_each(|&i| stretched.open[i] = true);
+        }
+        stretched
+    }
+
+    // Closes the given cells and then any open cells they cut off from the largest region. Bridging
+    // instead would cut straight through whatever the closed cells were reserved for.
+    pub fn close(&self, cells: &[bool]) -> Mask {
+        let open = self
+            .open
+            .iter()
+            .zip(cells)
+            .map(|(&open, &closed)| open && !closed)
+            .collect();
+        let mut closed = Mask {
+            rows: self.rows,
+            cols: self.cols,
+            open,
+        };
+        let (region, sizes) = closed.regions();
+        if let Some(main) = (0..sizes.len()).max_by_key(|&id| (sizes[id], !id)) {
+            for (open, &id) in closed.open.iter_mut().zip(region.iter()) {
+                *open = *open && id == main;
+            }
+        }
+        closed
+    }
+
+    // Labels every open cell with the id of its region and returns the labels with region sizes.
+    fn regions(&self) -> (Vec<usize>, Vec<usize>) {
+        let mut region = vec![usize::MAX; self.open.len()];
+        let mut sizes: Vec<usize> = Vec::new();
+        for start in 0..self.open.len() {
+            if !self.open[start] || region[start] != usize::MAX {
+                continue;
+            }
+            let id = sizes.len();
+            let mut size = 0;
+            let mut dfs = vec![start];
+            region[start] = id;
+            while let Some(cur) = dfs.pop() {
+                size += 1;
+                for next in self.neighbors(cur) {
+                    if self.open[next] && region[next] == usize::MAX {
+                        region[next] = id;
+                        dfs.push(next);
+                    }
+                }
+            }
+            sizes.push(size);
+        }
+        (region, sizes)
+    }
+
+    // Searches outward from the largest region until it reaches another one and returns the
+    // masked cells in between, or None once the open cells are a single region.
+    fn next_bridge(&self) -> Option<Vec<usize>> {
+        let (region, sizes) = self.regions();
+        if sizes.len() <= 1 {
+            return None;
+        }
+        let main = (0..sizes.len()).max_by_key(|&id| (sizes[id], !id))?;
+        let mut parents = vec![usize::MAX; self.open.len()];
+        let mut bfs: VecDeque<usize> = (0..self.open.len())
+            .filter(|&i| region[i] == main)
+            .collect();
+        bfs.iter().for_each(|&i| parents[i] = i);
+        while let Some(cur) = bfs.pop_front() {
+            for next in self.neighbors(cur) {
+                if parents[next] != usize::MAX {
+                    continue;
+                }
+                parents[next] = cur;
+                if self.open[next] {
+                    let mut bridge = Vec::new();
+                    let mut step = cur;
+                    while !self.open[step] {
+                        bridge.push(step);
+                        step = parents[step];
+                    }
+                    return Some(bridge);
+                }
+                bfs.push_back(next);
+            }
+        }
+        None
+    }
+
+    fn neighbors(&self, i: usize) -> impl Iterator<Item = usize> + '_ {
+        let p = Point {
+            row: i as i32 / self.cols,
+            col: i as i32 % self.cols,
+        };
+        crate::CARDINAL_DIRECTIONS
+            .iter()
+            .map(move |d| Point {
+                row: p.row + d.row,
+                col: p.col + d.col,
+            })
+            .filter(|n| n.row >= 0 && n.row < self.rows && n.col >= 0 && n.col < self.cols)
+            .map(|n| (n.row * self.cols + n.col) as usize)
+    }
+}
+
+//
+// Netpbm headers are whitespace separated numbers with # comments running to the end of a line.
+// The binary formats follow the header after exactly one whitespace byte.
+//
+
+struct NetpbmReader<'a> {
+    bytes: &'a [u8],
+    i: usize,
+}
+
+impl NetpbmReader<'_> {
+    fn skip_space(&mut self) {
+        while let Some(&b) = self.bytes.get(self.i) {
+            if b == b'#' {
+                while self.bytes.get(self.i).is_some_and(|&b| b != b'\n') {
+                    self.i += 1;
+                }
+            } else if b.is_ascii_whitespace() {
+                self.i += 1;
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<usize, MaskError> {
+        self.skip_space();
+        let start = self.i;
+        while self.bytes.get(self.i).is_some_and(|b| b.is_ascii_digit()) {
+            self.i += 1;
+        }
+        std::str::from_utf8(&self.bytes[start..self.i])
+            .ok()
+            .and_then(|n| n.parse::<usize>().ok())
+            .ok_or(MaskError::Image(format!(
+                "expected a number at byte {}",
+                start
+            )))
+    }
+
+    // Plain bitmaps may run their 0 and 1 pixels together without any whitespace.
+    fn bit(&mut self) -> Result<bool, MaskError> {
+        self.skip_space();
+        let bit = match self.bytes.get(self.i) {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => {
+                return Err(MaskError::Image(format!(
+                    "expected a bit at byte {}",
+                    self.i
+                )))
+            }
+        };
+        self.i += 1;
+        Ok(bit)
+    }
+
+    fn raster(&mut self, len: usize) -> Result<&[u8], MaskError> {
+        let start = self.i + 1;
+        match self.bytes.get(start..start + len) {
+            Some(raster) => Ok(raster),
+            None => Err(MaskError::Image(format!(
+                "expected {} bytes of pixels found {}",
+                len,
+                self.bytes.len().saturating_sub(start)
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_count(m: &Mask) -> usize {
+        m.open.iter().filter(|&&o| o).count()
+    }
+
+    #[test]
+    fn ascii_marks_drawn_characters_inside() {
+        let m = from_ascii("#.#\n###\n").unwrap();
+        assert_eq!((m.rows(), m.cols()), (2, 3));
+        assert!(m.is_open(0, 0));
+        assert!(!m.is_open(0, 1));
+        assert_eq!(open_count(&m), 5);
+    }
+
+    #[test]
+    fn empty_mask_is_rejected() {
+        assert!(matches!(from_ascii(""), Err(MaskError::Image(_))));
+        assert!(matches!(from_ascii("...\n. .\n"), Err(MaskError::Empty)));
+        assert!(matches!(
+            from_netpbm(b"P1 2 2 0 0 0 0"),
+            Err(MaskError::Empty)
+        ));
+    }
+
+    #[test]
+    fn ragged_lines_are_rejected() {
+        assert!(matches!(
+            from_ascii("###\n#\n###"),
+            Err(MaskError::Image(_))
+        ));
+        assert!(matches!(from_ascii("#\n###"), Err(MaskError::Image(_))));
+        assert!(matches!(
+            from_netpbm(b"P1 3 2 1 1 1 1"),
+            Err(MaskError::Image(_))
+        ));
+        assert!(matches!(
+            from_netpbm(b"P5 3 2 255 \x00\x00"),
+            Err(MaskError::Image(_))
+        ));
+    }
+
+    #[test]
+    fn bad_characters_are_rejected() {
+        assert!(matches!(from_ascii("#\t#\n###"), Err(MaskError::Image(_))));
+        assert!(matches!(from_ascii("#\x07#"), Err(MaskError::Image(_))));
+        assert!(matches!(
+            from_netpbm(b"P1 2 1 1 2"),
+            Err(MaskError::Image(_))
+        ));
+        assert!(matches!(
+            from_netpbm(b"P2 2 1 9 1 x"),
+            Err(MaskError::Image(_))
+        ));
+        assert!(matches!(
+            from_netpbm(b"P2 1 1 0 0"),
+            Err(MaskError::Image(_))
+        ));
+    }
+
+    #[test]
+    fn stretched_mask_has_the_requested_size() {
+        let m = from_ascii("#.\n.#").unwrap();
+        for (rows, cols) in [(1, 1), (3, 7), (10, 4), (25, 40)] {
+            let s = m.stretch(rows, cols);
+            assert_eq!((s.rows(), s.cols()), (rows, cols));
+            assert_eq!(s.open.len(), (rows * cols) as usize);
+        }
+    }
+
+    #[test]
+    fn stretched_pieces_are_bridged_into_one_region() {
+        let m = from_ascii("#...#\n.....\n#...#").unwrap();
+        let s = m.stretch(9, 15);
+        let (_, sizes) = s.regions();
+        assert_eq!(sizes.len(), 1);
+        assert_eq!(sizes[0], open_count(&s));
+    }
+
+    #[test]
+    fn tiny_stretch_keeps_the_middle_open() {
+        let m = from_ascii("....\n.#..\n....\n....").unwrap();
+        let s = m.stretch(1, 3);
+        assert!(s.is_open(0, 1));
+        assert_eq!(open_count(&s), 1);
+    }
+}
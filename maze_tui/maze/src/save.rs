@@ -0,0 +1,816 @@
+use crate::{topology::Topology, Blueprint, Delta, Maze, MazeStyle, Offset, Point, Square, Tape};
+use std::{error, fmt, fs, io, path::Path};
+
+// A saved maze only keeps what a builder leaves behind: the path bit, the wall shape bits, and the
+// tunnel bit of every square. Solver paint, thread caches, and builder marks are never written to disk.
+//
+// Binary layout, all integers little endian.
+//
+// magic--------|MAZE| 4 bytes
+// version------|v| 1 byte
+// wall style---|s| 1 byte
+// rows---------|rrrr| 4 bytes
+// cols---------|cccc| 4 bytes
+// topology-----|t| 1 byte.
+// squares------|...| rows * cols bytes, one per square, row major.
+//
+// Each square byte is the square shifted down by the wall shift so the wall bits land in the
+// low nibble and the path bit lands at 0b0010_0000. The tunnel bit sits far above the rest so it
+// is written as 0b0100_0000 instead. A path square never has wall or tunnel bits.
+//
+// The text format is meant to be read and edited by people. A short header is followed by one
+// line per row where paths are spaces and walls are drawn with the sharp wall style. Tunnels are
+// dashed lines that always connect the two walls on either side of them.
+//
+// maze 1
+// rows 31
+// cols 111
+// style 1
+// topology 0
+// ┌───┬─── ...
+//
+// Slanted walls are written with the sharp style like any other wall because the topology line is
+// enough to draw them slanted again.
+//
+// A history file records everything the TUI needs to replay an animation without rerunning any
+// algorithm: the maze before building started, the seed, and both Tapes. Deltas are stored as
+// varints relative to what the reader will already know. The square index is a signed jump from
+// the previous delta, the before value is xor'd with the square as the reader currently sees it,
+// and the after value is xor'd with before. Most deltas therefore fit in a handful of bytes.
+//
+// magic--------|TAPE| 4 bytes
+// version------|v| 1 byte
+// wall style---|s| 1 byte
+// rows---------|rrrr| 4 bytes
+// cols---------|cccc| 4 bytes
+// seed---------|ssssssss| 8 bytes
+// topology-----|t| 1 byte.
+// squares------|...| rows * cols varints, the maze before building.
+// build tape---|n|...| varint count then n deltas of index, before, after, and burst varints.
+// solve tape---|n|...| same as the build tape starting from the fully built maze.
+
+pub const MAGIC: [u8; 4] = *b"MAZE";
+pub const VERSION: u8 = 1;
+pub const TEXT_EXTENSION: &str = "txt";
+pub const HISTORY_MAGIC: [u8; 4] = *b"TAPE";
+pub const HISTORY_VERSION: u8 = 1;
+
+const HEADER_LEN: usize = 15;
+const HISTORY_HEADER_LEN: usize = 23;
+const TEXT_HEADER_LINES: usize = 5;
+const SAVED_BITS: Square = crate::PATH_BIT | crate::WALL_MASK;
+const PATH_BYTE: u8 = (crate::PATH_BIT >> crate::WALL_SHIFT) as u8;
+const TUNNEL_BYTE: u8 = 0b0100_0000;
+const WALL_BYTE_MASK: u8 = (crate::WALL_MASK >> crate::WALL_SHIFT) as u8;
+const STYLE_COUNT: usize = MazeStyle::Spikes as usize + 1;
+const TEXT_PATH: char = ' ';
+
+#[derive(Debug)]
+pub enum FileError {
+    Io(io::Error),
+    Magic,
+    Version(u8),
+    Truncated { expected: usize, found: usize },
+    TrailingData(usize),
+    Dimensions { rows: i64, cols: i64 },
+    Style(usize),
+    Topology(usize),
+    Square { row: i32, col: i32 },
+    Text { line: usize, reason: String },
+    Delta(usize),
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FileError::Io(e) => write!(f, "maze file error: {}", e),
+            FileError::Magic => write!(f, "not a maze file"),
+            FileError::Version(v) => write!(f, "unsupported maze file version {}", v),
+            FileError::Truncated { expected, found } => write!(
+                f,
+                "maze file truncated: expected {} bytes found {}",
+                expected, found
+            ),
+            FileError::TrailingData(extra) => {
+                write!(f, "maze file has {} unexpected trailing bytes", extra)
+            }
+            FileError::Dimensions { rows, cols } => {
+                write!(f, "maze file has invalid dimensions {}x{}", rows, cols)
+            }
+            FileError::Style(s) => write!(f, "maze file has unknown wall style {}", s),
+            FileError::Topology(t) => write!(f, "maze file has unknown topology {}", t),
+            FileError::Square { row, col } => {
+                write!(
+                    f,
+                    "maze file has inconsistent square at row {} col {}",
+                    row, col
+                )
+            }
+            FileError::Text { line, reason } => {
+                write!(f, "maze text error on line {}: {}", line, reason)
+            }
+            FileError::Delta(i) => write!(f, "maze history has inconsistent delta {}", i),
+        }
+    }
+}
+
+impl error::Error for FileError {}
+
+impl From<io::Error> for FileError {
+    fn from(e: io::Error) -> Self {
+        FileError::Io(e)
+    }
+}
+
+//
+// Disk helpers. The text format is chosen by extension and detected by the missing magic bytes.
+//
+
+pub fn save(path: &Path, maze: &Blueprint) -> Result<(), FileError> {
+    if path.extension().is_some_and(|ext| ext == TEXT_EXTENSION) {
+        fs::write(path, to_text(maze))?;
+    } else {
+        fs::write(path, to_bytes(maze))?;
+    }
+    Ok(())
+}
+
+pub fn load(path: &Path) -> Result<Blueprint, FileError> {
+    let bytes = fs::read(path)?;
+    if bytes.starts_with(&MAGIC) {
+        return from_bytes(&bytes);
+    }
+    match std::str::from_utf8(&bytes) {
+        Ok(text) => from_text(text),
+        Err(_) => Err(FileError::Magic),
+    }
+}
+
+//
+// Binary format.
+//
+
+pub fn to_bytes(maze: &Blueprint) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(HEADER_LEN + maze.buf.len());
+    bytes.extend_from_slice(&MAGIC);
+    bytes.push(VERSION);
+    bytes.push(maze.wall_style_index as u8);
+    bytes.extend_from_slice(&(maze.rows as u32).to_le_bytes());
+    bytes.extend_from_slice(&(maze.cols as u32).to_le_bytes());
+    bytes.push(maze.topology as u8);
+    bytes.extend(maze.buf.iter().map(|&square| {
+        let byte = ((square & SAVED_BITS) >> crate::WALL_SHIFT) as u8;
+        match crate::is_tunnel(square) {
+            true => byte | TUNNEL_BYTE,
+            false => byte,
+        }
+    }));
+    bytes
+}
+
+pub fn from_bytes(bytes: &[u8]) -> Result<Blueprint, FileError> {
+    if bytes.len() < HEADER_LEN {
+        if !MAGIC.starts_with(&bytes[..bytes.len().min(MAGIC.len())]) {
+            return Err(FileError::Magic);
+        }
+        return Err(FileError::Truncated {
+            expected: HEADER_LEN,
+            found: bytes.len(),
+        });
+    }
+    if bytes[0..4] != MAGIC {
+        return Err(FileError::Magic);
+    }
+    if bytes[4] != VERSION {
+        return Err(FileError::Version(bytes[4]));
+    }
+    let topology = topology_from_index(bytes[14] as usize)?;
+    let style = bytes[5] as usize;
+    let rows = u32::from_le_bytes([bytes[6], bytes[7], bytes[8], bytes[9]]) as i64;
+    let cols = u32::from_le_bytes([bytes[10], bytes[11], bytes[12], bytes[13]]) as i64;
+    let squares = square_count(rows, cols)?;
+    let body = &bytes[HEADER_LEN..];
+    if body.len() < squares {
+        return Err(FileError::Truncated {
+            expected: HEADER_LEN + squares,
+            found: bytes.len(),
+        });
+    }
+    if body.len() > squares {
+        return Err(FileError::TrailingData(body.len() - squares));
+    }
+    let mut maze = new_blueprint(rows, cols, style, topology)?;
+    for (i, &b) in body.iter().enumerate() {
+        if b & !(PATH_BYTE | WALL_BYTE_MASK | TUNNEL_BYTE) != 0
+            || (b & PATH_BYTE != 0 && b != PATH_BYTE)
+        {
+            return Err(square_error(&maze, i));
+        }
+        maze.buf[i] = ((b & !TUNNEL_BYTE) as Square) << crate::WALL_SHIFT;
+        if b & TUNNEL_BYTE != 0 {
+            maze.buf[i] |= crate::TUNNEL_BIT;
+        }
+    }
+    validate_perimeter(&maze)?;
+    Ok(maze)
+}
+
+//
+// Text format.
+//
+
+pub fn to_text(maze: &Blueprint) -> String {
+    let mut text = format!(
+        "maze {}\nrows {}\ncols {}\nstyle {}\ntopology {}\n",
+        VERSION, maze.rows, maze.cols, maze.wall_style_index, maze.topology as usize
+    );
+    for row in maze.buf.chunks(maze.cols as usize) {
+        text.extend(row.iter().map(|&square| {
+            if crate::is_path(square) {
+                TEXT_PATH
+            } else if crate::is_tunnel(square) {
+                crate::tunnel_char(square)
+            } else {
+                crate::wall_char(MazeStyle::Sharp as usize, square)
+            }
+        }));
+        text.push('\n');
+    }
+    text
+}
+
+pub fn from_text(text: &str) -> Result<Blueprint, FileError> {
+    let mut lines = text.lines().enumerate().map(|(i, line)| (i + 1, line));
+    let version = header_value(lines.next(), "maze")?;
+    if version != VERSION as i64 {
+        return Err(FileError::Text {
+            line: 1,
+            reason: "unsupported version".to_string(),
+        });
+    }
+    let rows = header_value(lines.next(), "rows")?;
+    let cols = header_value(lines.next(), "cols")?;
+    let style = header_value(lines.next(), "style")?;
+    if style < 0 {
+        return Err(FileError::Style(usize::MAX));
+    }
+    let topology = match header_value(lines.next(), "topology")? {
+        t if t < 0 => return Err(FileError::Topology(usize::MAX)),
+        t => topology_from_index(t as usize)?,
+    };
+    if square_count(rows, cols)? > text.len() {
+        return Err(FileError::Text {
+            line: TEXT_HEADER_LINES + 1,
+            reason: format!("expected {} rows of {} columns", rows, cols),
+        });
+    }
+    let mut maze = new_blueprint(rows, cols, style as usize, topology)?;
+    let glyphs = crate::wall_row(MazeStyle::Sharp as usize);
+    let mut rows_read = 0;
+    for (line_num, line) in lines.by_ref().take(maze.rows as usize) {
+        let row_start = rows_read * maze.cols as usize;
+        let mut cols_read = 0;
+        for c in line.chars() {
+            if cols_read == maze.cols as usize {
+                return Err(FileError::Text {
+                    line: line_num,
+                    reason: format!("expected {} columns", maze.cols),
+                });
+            }
+            maze.buf[row_start + cols_read] = if c == TEXT_PATH {
+                crate::PATH_BIT
+            } else if c == crate::VERTICAL_TUNNEL {
+                crate::TUNNEL_BIT | crate::NORTH_WALL | crate::SOUTH_WALL
+            } else if c == crate::HORIZONTAL_TUNNEL {
+                crate::TUNNEL_BIT | crate::EAST_WALL | crate::WEST_WALL
+            } else {
+                match glyphs.iter().position(|&g| g == c) {
+                    Some(shape) => (shape as Square) << crate::WALL_SHIFT,
+                    None => {
+                        return Err(FileError::Text {
+                            line: line_num,
+                            reason: format!("unknown square '{}'", c),
+                        })
+                    }
+                }
+            };
+            cols_read += 1;
+        }
+        if cols_read != maze.cols as usize {
+            return Err(FileError::Text {
+                line: line_num,
+                reason: format!("expected {} columns found {}", maze.cols, cols_read),
+            });
+        }
+        rows_read += 1;
+    }
+    if rows_read != maze.rows as usize {
+        return Err(FileError::Text {
+            line: rows_read + TEXT_HEADER_LINES + 1,
+            reason: format!("expected {} rows found {}", maze.rows, rows_read),
+        });
+    }
+    if let Some((line_num, _)) = lines.find(|(_, line)| !line.trim().is_empty()) {
+        return Err(FileError::Text {
+            line: line_num,
+            reason: "unexpected text after the last row".to_string(),
+        });
+    }
+    validate_perimeter(&maze)?;
+    Ok(maze)
+}
+
+//
+// History format. The maze handed in should be reset to its starting state with full Tapes.
+//
+
+pub fn save_history(path: &Path, maze: &Maze) -> Result<(), FileError> {
+    fs::write(path, history_to_bytes(maze))?;
+    Ok(())
+}
+
+pub fn load_history(path: &Path) -> Result<Maze, FileError> {
+    history_from_bytes(&fs::read(path)?)
+}
+
+pub fn history_to_bytes(maze: &Maze) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(HISTORY_HEADER_LEN + maze.as_slice().len());
+    bytes.extend_from_slice(&HISTORY_MAGIC);
+    bytes.push(HISTORY_VERSION);
+    bytes.push(maze.maze.wall_style_index as u8);
+    bytes.extend_from_slice(&(maze.rows() as u32).to_le_bytes());
+    bytes.extend_from_slice(&(maze.cols() as u32).to_le_bytes());
+    bytes.extend_from_slice(&maze.seed.to_le_bytes());
+    bytes.push(maze.topology() as u8);
+    for &square in maze.as_slice() {
+        push_varint(&mut bytes, square);
+    }
+    let mut cur = maze.maze.buf.clone();
+    encode_tape(&mut bytes, &maze.build_history, &mut cur, maze.cols());
+    encode_tape(&mut bytes, &maze.solve_history, &mut cur, maze.cols());
+    bytes
+}
+
+pub fn history_from_bytes(bytes: &[u8]) -> Result<Maze, FileError> {
+    if bytes.len() < HISTORY_HEADER_LEN {
+        if !HISTORY_MAGIC.starts_with(&bytes[..bytes.len().min(HISTORY_MAGIC.len())]) {
+            return Err(FileError::Magic);
+        }
+        return Err(FileError::Truncated {
+            expected: HISTORY_HEADER_LEN,
+            found: bytes.len(),
+        });
+    }
+    if bytes[0..4] != HISTORY_MAGIC {
+        return Err(FileError::Magic);
+    }
+    if bytes[4] != HISTORY_VERSION {
+        return Err(FileError::Version(bytes[4]));
+    }
+    let topology = topology_from_index(bytes[22] as usize)?;
+    let style = bytes[5] as usize;
+    let rows = u32::from_le_bytes([bytes[6], bytes[7], bytes[8], bytes[9]]) as i64;
+    let cols = u32::from_le_bytes([bytes[10], bytes[11], bytes[12], bytes[13]]) as i64;
+    // Every square takes at least one byte so the file must hold that many before any are read.
+    let squares = square_count(rows, cols)?;
+    if bytes.len() - HISTORY_HEADER_LEN < squares {
+        return Err(FileError::Truncated {
+            expected: HISTORY_HEADER_LEN + squares,
+            found: bytes.len(),
+        });
+    }
+    let mut seed = [0u8; 8];
+    seed.copy_from_slice(&bytes[14..22]);
+    let mut maze = Maze::from_blueprint(
+        new_blueprint(rows, cols, style, topology)?,
+        u64::from_le_bytes(seed),
+    );
+    let mut reader = Reader {
+        bytes,
+        pos: HISTORY_HEADER_LEN,
+    };
+    for square in maze.maze.buf.iter_mut() {
+        *square = reader.varint()?;
+    }
+    let mut cur = maze.maze.buf.clone();
+    maze.build_history = decode_tape(&mut reader, &mut cur, maze.cols())?;
+    maze.solve_history = decode_tape(&mut reader, &mut cur, maze.cols())?;
+    if reader.pos != bytes.len() {
+        return Err(FileError::TrailingData(bytes.len() - reader.pos));
+    }
+    Ok(maze)
+}
+
+fn encode_tape(bytes: &mut Vec<u8>, tape: &Tape, cur: &mut [Square], cols: i32) {
+    push_varint(bytes, tape.steps.len() as u64);
+    let mut prev_index = 0i64;
+    for delta in tape.steps.iter() {
+        let index = (delta.id.row * cols + delta.id.col) as i64;
+        let jump = index - prev_index;
+        push_varint(bytes, ((jump << 1) ^ (jump >> 63)) as u64);
+        push_varint(bytes, delta.before ^ cur[index as usize]);
+        push_varint(bytes, delta.after ^ delta.before);
+        push_varint(bytes, delta.burst as u64);
+        cur[index as usize] = delta.after;
+        prev_index = index;
+    }
+}
+
+fn decode_tape(reader: &mut Reader, cur: &mut [Square], cols: i32) -> Result<Tape, FileError> {
+    let len = reader.varint()? as usize;
+    // Every delta takes at least four bytes so a count this large can only be a corrupt file.
+    if len > (reader.bytes.len() - reader.pos) / 4 {
+        return Err(FileError::Truncated {
+            expected: reader.pos + len * 4,
+            found: reader.bytes.len(),
+        });
+    }
+    let mut tape = Tape {
+        steps: Vec::with_capacity(len),
+        i: 0,
+    };
+    let mut prev_index = 0i64;
+    for i in 0..len {
+        let zigzag = reader.varint()?;
+        let index = match prev_index.checked_add((zigzag >> 1) as i64 ^ -((zigzag & 1) as i64)) {
+            Some(index) if index >= 0 && (index as usize) < cur.len() => index,
+            _ => return Err(FileError::Delta(i)),
+        };
+        let before = reader.varint()? ^ cur[index as usize];
+        let after = reader.varint()? ^ before;
+        let burst = reader.varint()? as usize;
+        if burst == 0 || burst > len {
+            return Err(FileError::Delta(i));
+        }
+        tape.steps.push(Delta {
+            id: Point {
+                row: index as i32 / cols,
+                col: index as i32 % cols,
+            },
+            before,
+            after,
+            burst,
+        });
+        cur[index as usize] = after;
+        prev_index = index;
+    }
+    // The Tape trusts that the first and last delta of every burst agree on the burst length.
+    let mut i = 0;
+    while i < tape.steps.len() {
+        let burst = tape.steps[i].burst;
+        if i + burst > tape.steps.len() || tape.steps[i + burst - 1].burst != burst {
+            return Err(FileError::Delta(i));
+        }
+        i += burst;
+    }
+    Ok(tape)
+}
+
+fn push_varint(bytes: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        bytes.push((v as u8) | 0x80);
+        v >>= 7;
+    }
+    bytes.push(v as u8);
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn varint(&mut self) -> Result<u64, FileError> {
+        let mut v = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = match self.bytes.get(self.pos) {
+                Some(&b) => b,
+                None => {
+                    return Err(FileError::Truncated {
+                        expected: self.pos + 1,
+                        found: self.bytes.len(),
+                    })
+                }
+            };
+            self.pos += 1;
+            v |= ((b & 0x7F) as u64) << shift;
+            if b & 0x80 == 0 {
+                return Ok(v);
+            }
+        }
+        Err(FileError::Delta(self.pos))
+    }
+}
+
+//
+// Validation shared by both formats.
+//
+
+fn header_value(line: Option<(usize, &str)>, key: &str) -> Result<i64, FileError> {
+    let (line_num, line) = match line {
+        Some(l) => l,
+        None => {
+            return Err(FileError::Text {
+                line: 0,
+                reason: format!("missing {} header", key),
+            })
+        }
+    };
+    let mut words = line.split_whitespace();
+    match (
+        words.next(),
+        words.next().map(|w| w.parse::<i64>()),
+        words.next(),
+    ) {
+        (Some(k), Some(Ok(v)), None) if k == key => Ok(v),
+        _ => Err(FileError::Text {
+            line: line_num,
+            reason: format!("expected '{} <number>'", key),
+        }),
+    }
+}
+
+fn new_blueprint(
+    rows: i64,
+    cols: i64,
+    style: usize,
+    topology: Topology,
+) -> Result<Blueprint, FileError> {
+    let squares = square_count(rows, cols)?;
+    if style >= STYLE_COUNT {
+        return Err(FileError::Style(style));
+    }
+    Ok(Blueprint {
+        buf: vec![0; squares],
+        rows: rows as i32,
+        cols: cols as i32,
+        offset: Offset::default(),
+        wall_style_index: style,
+        topology,
+    })
+}
+
+// Both dimensions come straight from the file so their product is checked before it is trusted.
+fn square_count(rows: i64, cols: i64) -> Result<usize, FileError> {
+    match rows.checked_mul(cols) {
+        Some(squares)
+            if rows >= 3
+                && cols >= 3
+                && rows % 2 == 1
+                && cols % 2 == 1
+                && squares <= i32::MAX as i64 =>
+        {
+            Ok(squares as usize)
+        }
+        _ => Err(FileError::Dimensions { rows, cols }),
+    }
+}
+
+fn topology_from_index(i: usize) -> Result<Topology, FileError> {
+    Topology::from_index(i).ok_or(FileError::Topology(i))
+}
+
+// Solvers never bounds check so a maze that lets them walk off the edge is not a maze. The first
+// column of a polar maze joins the ends of a ring and stepping off it wraps around instead.
+fn validate_perimeter(maze: &Blueprint) -> Result<(), FileError> {
+    let wraps = maze.topology == Topology::Polar;
+    for (i, &square) in maze.buf.iter().enumerate() {
+        let (row, col) = (i as i32 / maze.cols, i as i32 % maze.cols);
+        let on_edge =
+            row == 0 || (col == 0 && !wraps) || row == maze.rows - 1 || col == maze.cols - 1;
+        if on_edge && crate::is_path(square) {
+            return Err(FileError::Square { row, col });
+        }
+    }
+    Ok(())
+}
+
+fn square_error(maze: &Blueprint, i: usize) -> FileError {
+    FileError::Square {
+        row: i as i32 / maze.cols,
+        col: i as i32 % maze.cols,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Every kind of square the formats know about: corners, tees, a cross, a floating end, paths,
+    // and one tunnel running each way.
+    const ROWS: &str = "\
+┌─┬───┐
+│ ╎   │
+├╌┼─╴ │
+│     │
+└─────┘
+";
+
+    fn text() -> String {
+        format!(
+            "maze {}\nrows 5\ncols 7\nstyle 1\ntopology 0\n{}",
+            VERSION, ROWS
+        )
+    }
+
+    fn blueprint() -> Blueprint {
+        from_text(&text()).expect("the fixture is a valid maze")
+    }
+
+    fn assert_same(a: &Blueprint, b: &Blueprint) {
+        assert_eq!((a.rows, a.cols), (b.rows, b.cols));
+        assert_eq!(a.wall_style_index, b.wall_style_index);
+        assert_eq!(a.topology, b.topology);
+        assert_eq!(a.buf, b.buf);
+    }
+
+    fn deltas(tape: &Tape) -> Vec<(Point, Square, Square, usize)> {
+        tape.steps
+            .iter()
+            .map(|d| (d.id, d.before, d.after, d.burst))
+            .collect()
+    }
+
+    fn delta(maze: &Blueprint, row: i32, col: i32, after: Square, burst: usize) -> Delta {
+        Delta {
+            id: Point { row, col },
+            before: maze.get(row, col),
+            after,
+            burst,
+        }
+    }
+
+    // A build that carves two squares in one burst and walls one back in, then a solve that
+    // paints with bits far above the low word and jumps backwards across the maze.
+    fn history() -> Maze {
+        let start = blueprint();
+        let mut maze = Maze::from_blueprint(start.clone(), 0xDEAD_BEEF_F00D);
+        let mut built = start.clone();
+        let carve = [
+            delta(&built, 3, 1, crate::PATH_BIT | 0xFF, 2),
+            delta(&built, 3, 2, crate::PATH_BIT | 0xFF, 2),
+        ];
+        maze.build_history.push_burst(&carve);
+        for d in carve {
+            *built.get_mut(d.id.row, d.id.col) = d.after;
+        }
+        let wall = delta(&built, 3, 2, crate::EAST_WALL | crate::WEST_WALL, 1);
+        maze.build_history.push_burst(&[wall]);
+        *built.get_mut(3, 2) = wall.after;
+        maze.solve_history.push_burst(&[
+            delta(&built, 3, 5, crate::PATH_BIT | 0x6_0000_0000, 2),
+            delta(&built, 1, 1, crate::PATH_BIT | 0x40FF00, 2),
+        ]);
+        maze
+    }
+
+    #[test]
+    fn text_round_trips_walls_paths_and_tunnels() {
+        let maze = blueprint();
+        assert!(crate::is_tunnel(maze.get(1, 2)));
+        assert!(crate::is_tunnel(maze.get(2, 1)));
+        assert_eq!(to_text(&maze), text());
+        assert_same(&from_text(&to_text(&maze)).unwrap(), &maze);
+    }
+
+    #[test]
+    fn binary_round_trips_walls_paths_and_tunnels() {
+        let maze = blueprint();
+        let bytes = to_bytes(&maze);
+        assert_eq!(bytes.len(), HEADER_LEN + maze.buf.len());
+        assert_same(&from_bytes(&bytes).unwrap(), &maze);
+    }
+
+    #[test]
+    fn binary_drops_everything_a_builder_does_not_leave_behind() {
+        let clean = blueprint();
+        let mut painted = clean.clone();
+        *painted.get_mut(1, 1) |= 0x10FF_00FF | 0x6_0000_0000;
+        *painted.get_mut(0, 0) |= 0x00FF00;
+        assert_same(&from_bytes(&to_bytes(&painted)).unwrap(), &clean);
+    }
+
+    #[test]
+    fn history_round_trips_the_start_seed_and_both_tapes() {
+        let maze = history();
+        let loaded = history_from_bytes(&history_to_bytes(&maze)).unwrap();
+        assert_same(&loaded.maze, &maze.maze);
+        assert_eq!(loaded.seed, maze.seed);
+        assert_eq!(deltas(&loaded.build_history), deltas(&maze.build_history));
+        assert_eq!(deltas(&loaded.solve_history), deltas(&maze.solve_history));
+    }
+
+    #[test]
+    fn varints_round_trip_every_width() {
+        let values = [
+            0,
+            1,
+            0x7F,
+            0x80,
+            0x3FFF,
+            0x4000,
+            crate::TUNNEL_BIT,
+            u64::MAX,
+        ];
+        let mut bytes = Vec::new();
+        for &v in values.iter() {
+            push_varint(&mut bytes, v);
+        }
+        let mut reader = Reader {
+            bytes: &bytes,
+            pos: 0,
+        };
+        for &v in values.iter() {
+            assert_eq!(reader.varint().unwrap(), v);
+        }
+        assert_eq!(reader.pos, bytes.len());
+        assert!(matches!(reader.varint(), Err(FileError::Truncated { .. })));
+        let overlong = [0xFF; 11];
+        let mut reader = Reader {
+            bytes: &overlong,
+            pos: 0,
+        };
+        assert!(reader.varint().is_err());
+    }
+
+    #[test]
+    fn truncated_files_are_errors() {
+        let bytes = to_bytes(&blueprint());
+        for len in 0..bytes.len() {
+            assert!(from_bytes(&bytes[..len]).is_err(), "maze cut at {}", len);
+        }
+        let bytes = history_to_bytes(&history());
+        for len in 0..bytes.len() {
+            assert!(
+                history_from_bytes(&bytes[..len]).is_err(),
+                "history cut at {}",
+                len
+            );
+        }
+        let text = text();
+        for (len, _) in text
+            .char_indices()
+            .filter(|&(i, c)| c == '\n' && i + 1 < text.len())
+        {
+            assert!(from_text(&text[..len]).is_err(), "text cut at {}", len);
+        }
+    }
+
+    // Any single corrupt byte must be caught or load as some maze. It must never panic.
+    #[test]
+    fn corrupt_bytes_never_panic() {
+        let maze = to_bytes(&blueprint());
+        let history = history_to_bytes(&history());
+        for flip in [0x01, 0x40, 0x80, 0xFF] {
+            for i in 0..maze.len() {
+                let mut bytes = maze.clone();
+                bytes[i] ^= flip;
+                let _ = from_bytes(&bytes);
+            }
+            for i in 0..history.len() {
+                let mut bytes = history.clone();
+                bytes[i] ^= flip;
+                let _ = history_from_bytes(&bytes);
+            }
+        }
+    }
+
+    #[test]
+    fn impossible_headers_are_errors() {
+        let mut bytes = to_bytes(&blueprint());
+        bytes[6..14].fill(0xFF);
+        assert!(matches!(
+            from_bytes(&bytes),
+            Err(FileError::Dimensions { .. })
+        ));
+        let mut bytes = history_to_bytes(&history());
+        bytes[6..14].fill(0xFF);
+        assert!(matches!(
+            history_from_bytes(&bytes),
+            Err(FileError::Dimensions { .. })
+        ));
+        let mut bytes = to_bytes(&blueprint());
+        bytes[4] = VERSION + 1;
+        assert!(matches!(from_bytes(&bytes), Err(FileError::Version(_))));
+        let huge = text().replace("rows 5", &format!("rows {}", i64::MAX));
+        assert!(from_text(&huge).is_err());
+        let short = text()
+            .replace("rows 5", "rows 40001")
+            .replace("cols 7", "cols 40001");
+        assert!(from_text(&short).is_err());
+    }
+
+    #[test]
+    fn history_delta_jumps_off_the_maze_are_errors() {
+        let mut bytes = history_to_bytes(&Maze::from_blueprint(blueprint(), 7));
+        // Drop both empty tapes for a build tape that lands on a square then leaps past the end.
+        bytes.truncate(bytes.len() - 2);
+        bytes.extend_from_slice(&[2, 16, 0, 0, 1]);
+        push_varint(&mut bytes, u64::MAX - 1);
+        bytes.extend_from_slice(&[0, 0, 1, 0]);
+        assert!(matches!(
+            history_from_bytes(&bytes),
+            Err(FileError::Delta(1))
+        ));
+    }
+}
@@ -0,0 +1,394 @@
+use crate::Point;
+
+// A topology decides which cells of a maze are neighbors. Every topology is laid out in the same
+// square buffer so solvers, painters, and the save formats never need to know which one they have.
+// A cell is anchored on a square in an odd row and neighbors are joined by carving the single wall
+// square between them, so a solver walking path squares walks the topology without being told.
+//
+// square-------|every odd row and odd column is a cell with four neighbors.
+// hex----------|cells are bricks three squares wide and every other row is shifted by half a
+//              |brick. A brick touches one brick on either side and two bricks above and below
+//              |where it overlaps them. That is the six neighbors of a hexagon. Bricks at the
+//              |ends of a row stretch to the border.
+// triangle-----|every odd row and odd column is a cell but only one of north or south is a
+//              |neighbor. Triangles alternate pointing up and down along a row and the one
+//              |pointing up may only reach down through its base.
+// polar--------|every odd row is a ring unrolled clockwise from the top of the circle. The
+//              |first row is the center and each ring has as many or more cells than the ring
+//              |inside it, so a cell has one neighbor inward and one or more outward.
+//
+// Walls between cells that meet at an angle are drawn slanted. Every other wall uses the style.
+//
+// A ring is a loop so the last cell of a polar row is a neighbor of the first. The wall between
+// them is the square in column 0 and stepping off either end of a ring comes back around the
+// other side. A ring splits its cells whenever they would grow much wider than a ring is tall, as
+// long as every cell still gets a square of path and a square of wall. Any columns past the
+// widest ring, and always the last column, stay walls.
+
+pub const HEX_WIDTH: i32 = 4;
+pub const UP_SLANT: char = '╱';
+pub const DOWN_SLANT: char = '╲';
+
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum Topology {
+    #[default]
+    Square = 0,
+    Hex,
+    Triangle,
+    Polar,
+}
+
+// The number of cells in every ring from the center out and how many columns one lap takes.
+#[derive(Debug, Clone)]
+pub struct Rings {
+    pub counts: Vec<i32>,
+    pub width: i32,
+}
+
+// Moving to a neighbor opens the wall square between the two cells.
+#[derive(Debug, Clone, Copy)]
+pub struct Link {
+    pub next: Point,
+    pub wall: Point,
+}
+
+const SQUARE_LINKS: [Link; 4] = [
+    link(-2, 0, -1, 0),
+    link(0, 2, 0, 1),
+    link(2, 0, 1, 0),
+    link(0, -2, 0, -1),
+];
+
+// west, north-west, north-east, east, south-east, south-west
+const HEX_LINKS: [Link; 6] = [
+    link(0, -HEX_WIDTH, 0, -2),
+    link(-2, -2, -1, -1),
+    link(-2, 2, -1, 1),
+    link(0, HEX_WIDTH, 0, 2),
+    link(2, 2, 1, 1),
+    link(2, -2, 1, -1),
+];
+
+const fn link(next_row: i32, next_col: i32, wall_row: i32, wall_col: i32) -> Link {
+    Link {
+        next: Point {
+            row: next_row,
+            col: next_col,
+        },
+        wall: Point {
+            row: wall_row,
+            col: wall_col,
+        },
+    }
+}
+
+impl Topology {
+    pub fn from_index(i: usize) -> Option<Self> {
+        match i {
+            0 => Some(Topology::Square),
+            1 => Some(Topology::Hex),
+            2 => Some(Topology::Triangle),
+            3 => Some(Topology::Polar),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Topology::Square => "square",
+            Topology::Hex => "hex",
+            Topology::Triangle => "triangle",
+            Topology::Polar => "polar",
+        }
+    }
+
+    // Every cell anchor in row major order.
+    pub fn cells(self, rows: i32, cols: i32) -> Vec<Point> {
+        (1..rows - 1)
+            .step_by(2)
+            .flat_map(|row| {
+                (1..cols - 1)
+                    .map(move |col| Point { row, col })
+                    .filter(move |&p| self.is_cell(rows, cols, p))
+            })
+            .collect()
+    }
+
+    pub fn is_cell(self, rows: i32, cols: i32, p: Point) -> bool {
+        if p.row < 1 || p.row >= rows - 1 || p.row % 2 == 0 {
+            return false;
+        }
+        match self {
+            Topology::Square | Topology::Triangle => {
+                p.col >= 1 && p.col < cols - 1 && p.col % 2 == 1
+            }
+            Topology::Hex => {
+                p.col >= 2
+                    && p.col < cols - 2
+                    && (p.col - hex_shift(p.row)).rem_euclid(HEX_WIDTH) == 0
+            }
+            Topology::Polar => {
+                let rings = polar_rings(rows, cols);
+                let span = rings.span((p.row / 2) as usize);
+                span >= 2 && p.col >= 1 && p.col < rings.width && (p.col - 1) % span == 0
+            }
+        }
+    }
+
+    // The squares a cell covers. Carving a cell opens all of them. The first and last brick of a
+    // hex row stretch to the border so no square is left outside of every cell. A polar cell
+    // covers its whole arc of the ring up to the wall before the next cell.
+    pub fn body(self, rows: i32, cols: i32, cell: Point) -> Vec<Point> {
+        match self {
+            Topology::Square | Topology::Triangle => vec![cell],
+            Topology::Hex => {
+                let reaches = |c: i32| {
+                    self.is_cell(
+                        rows,
+                        cols,
+                        Point {
+                            row: cell.row,
+                            col: c,
+                        },
+                    )
+                };
+                let first = match reaches(cell.col - HEX_WIDTH) {
+                    true => cell.col - 1,
+                    false => 1,
+                };
+                let last = match reaches(cell.col + HEX_WIDTH) {
+                    true => cell.col + 1,
+                    false => cols - 2,
+                };
+                (first..=last)
+                    .map(|col| Point { row: cell.row, col })
+                    .collect()
+            }
+            Topology::Polar => {
+                let span = polar_rings(rows, cols).span((cell.row / 2) as usize);
+                (cell.col..cell.col + span - 1)
+                    .map(|col| Point { row: cell.row, col })
+                    .collect()
+            }
+        }
+    }
+
+    // Every neighbor of a cell that lies inside the maze, with links given in absolute positions.
+    pub fn links(self, rows: i32, cols: i32, cell: Point) -> Vec<Link> {
+        let relative: &[Link] = match self {
+            Topology::Polar => return polar_links(&polar_rings(rows, cols), cell),
+            Topology::Square => &SQUARE_LINKS,
+            Topology::Hex => &HEX_LINKS,
+            Topology::Triangle => {
+                if points_up(cell) {
+                    &[SQUARE_LINKS[1], SQUARE_LINKS[2], SQUARE_LINKS[3]]
+                } else {
+                    &[SQUARE_LINKS[0], SQUARE_LINKS[1], SQUARE_LINKS[3]]
+                }
+            }
+        };
+        relative
+            .iter()
+            .map(|l| Link {
+                next: Point {
+                    row: cell.row + l.next.row,
+                    col: cell.col + l.next.col,
+                },
+                wall: Point {
+                    row: cell.row + l.wall.row,
+                    col: cell.col + l.wall.col,
+                },
+            })
+            .filter(|l| self.is_cell(rows, cols, l.next))
+            .collect()
+    }
+
+    // The glyph for an interior wall square that separates two cells meeting at an angle.
+    pub fn slant(self, rows: i32, cols: i32, p: Point) -> Option<char> {
+        if p.row <= 0 || p.row >= rows - 1 || p.col <= 0 || p.col >= cols - 1 {
+            return None;
+        }
+        match self {
+            Topology::Square | Topology::Polar => None,
+            // The ends of the brick above a row of walls are its lower left and right edges.
+            Topology::Hex => {
+                if p.row % 2 == 1 || p.col % 2 == 0 {
+                    return None;
+                }
+                match (p.col - hex_shift(p.row - 1)).rem_euclid(HEX_WIDTH) {
+                    1 => Some(UP_SLANT),
+                    3 => Some(DOWN_SLANT),
+                    _ => None,
+                }
+            }
+            Topology::Triangle => {
+                if p.row % 2 == 0 || p.col % 2 == 1 {
+                    return None;
+                }
+                match points_up(Point {
+                    row: p.row,
+                    col: p.col - 1,
+                }) {
+                    true => Some(DOWN_SLANT),
+                    false => Some(UP_SLANT),
+                }
+            }
+        }
+    }
+
+    // Steps off either end of a polar ring come back around the other side. Nothing else wraps.
+    // Every ring is wider than half the columns so most steps never need to measure the rings.
+    pub fn wrap(self, rows: i32, cols: i32, p: Point) -> Point {
+        if self != Topology::Polar || p.row % 2 == 0 || (p.col > 0 && p.col < cols / 2) {
+            return p;
+        }
+        let width = polar_rings(rows, cols).width;
+        match p.col < 0 || p.col == width {
+            true => Point {
+                row: p.row,
+                col: p.col.rem_euclid(width),
+            },
+            false => p,
+        }
+    }
+}
+
+pub fn polar_rings(rows: i32, cols: i32) -> Rings {
+    let rings = ((rows - 1) / 2).max(1) as usize;
+    let usable = (cols - 1).max(2);
+    let mut counts = vec![1];
+    for ring in 1..rings {
+        let prev = counts[ring - 1];
+        let ideal = (2.0 * std::f64::consts::PI * ring as f64 / prev as f64).round() as i32;
+        counts.push(prev * ideal.min(usable / (2 * prev)).max(1));
+    }
+    let outer = counts[rings - 1];
+    Rings {
+        width: (usable / outer) * outer,
+        counts,
+    }
+}
+
+impl Rings {
+    // How many columns one cell of a ring takes, counting the wall before it.
+    pub fn span(&self, ring: usize) -> i32 {
+        match self.counts.get(ring) {
+            Some(&n) => self.width / n,
+            None => 0,
+        }
+    }
+
+    // The anchor of the cell in a ring that covers a turn t clockwise from the top, t in [0, 1).
+    pub fn cell_at(&self, ring: usize, t: f64) -> Point {
+        let n = self.counts[ring];
+        Point {
+            row: ring as i32 * 2 + 1,
+            col: ((t * n as f64) as i32).clamp(0, n - 1) * self.span(ring) + 1,
+        }
+    }
+
+    // How far around the circle a column sits as a turn clockwise from the top.
+    pub fn turn(&self, col: i32) -> f64 {
+        col as f64 / self.width as f64
+    }
+}
+
+// Counter clockwise, clockwise, inward, then every outward neighbor in clockwise order.
+fn polar_links(rings: &Rings, cell: Point) -> Vec<Link> {
+    let ring = (cell.row / 2) as usize;
+    let n = rings.counts[ring];
+    let span = rings.span(ring);
+    let i = (cell.col - 1) / span;
+    let mut links = Vec::new();
+    if n > 1 {
+        for (next, wall) in [((i + n - 1) % n, i), ((i + 1) % n, (i + 1) % n)] {
+            links.push(link(cell.row, next * span + 1, cell.row, wall * span));
+        }
+    }
+    if ring > 0 {
+        let parent = i * rings.counts[ring - 1] / n;
+        links.push(link(
+            cell.row - 2,
+            parent * rings.span(ring - 1) + 1,
+            cell.row - 1,
+            cell.col,
+        ));
+    }
+    if ring + 1 < rings.counts.len() {
+        let ratio = rings.counts[ring + 1] / n;
+        for child in i * ratio..(i + 1) * ratio {
+            let col = child * rings.span(ring + 1) + 1;
+            links.push(link(cell.row + 2, col, cell.row + 1, col));
+        }
+    }
+    links
+}
+
+// Odd rows of bricks start half a brick further along. Anchors sit in the middle of a brick.
+#[inline]
+fn hex_shift(row: i32) -> i32 {
+    2 + ((row / 2) % 2) * (HEX_WIDTH / 2)
+}
+
+#[inline]
+fn points_up(cell: Point) -> bool {
+    (cell.row / 2 + cell.col / 2) % 2 == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZES: [(i32, i32); 4] = [(5, 5), (11, 21), (15, 31), (21, 111)];
+
+    #[test]
+    fn links_go_both_ways_through_the_same_wall() {
+        for topology in [Topology::Hex, Topology::Triangle, Topology::Polar] {
+            for (rows, cols) in SIZES {
+                for cell in topology.cells(rows, cols) {
+                    for l in topology.links(rows, cols, cell) {
+                        assert!(
+                            topology
+                                .links(rows, cols, l.next)
+                                .iter()
+                                .any(|back| back.next == cell && back.wall == l.wall),
+                            "{} {}x{} links {:?} to {:?} but not back",
+                            topology.name(),
+                            rows,
+                            cols,
+                            cell,
+                            l.next
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_cell_is_reachable_through_links() {
+        for topology in [Topology::Hex, Topology::Triangle, Topology::Polar] {
+            for (rows, cols) in SIZES {
+                let cells = topology.cells(rows, cols);
+                let mut seen = vec![cells[0]];
+                let mut dfs = vec![cells[0]];
+                while let Some(cur) = dfs.pop() {
+                    for l in topology.links(rows, cols, cur) {
+                        if !seen.contains(&l.next) {
+                            seen.push(l.next);
+                            dfs.push(l.next);
+                        }
+                    }
+                }
+                assert_eq!(
+                    seen.len(),
+                    cells.len(),
+                    "{} {}x{}",
+                    topology.name(),
+                    rows,
+                    cols
+                );
+            }
+        }
+    }
+}
@@ -23,6 +23,10 @@ pub struct Monitor {
     pub maze: maze::Maze,
     pub win: Option<usize>,
     pub win_path: Vec<(maze::Point, maze::Square)>,
+    // Solvers that search from both ends record the square where the two searches touched.
+    pub meet: Option<maze::Point>,
+    // The text after the colon in a flag such as `-b growing-tree:newest=0.5`, if one was given.
+    pub arg: Option<String>,
     pub map: MaxMap,
     pub count: usize,
 }
@@ -33,6 +37,8 @@ impl Monitor {
             maze: boxed_maze,
             win: None,
             win_path: Vec::default(),
+            meet: None,
+            arg: None,
             map: MaxMap::default(),
             count: 0,
         }))
@@ -4,11 +4,12 @@ use std::collections::VecDeque;
 
 use std::thread;
 
-use rand::{thread_rng, Rng};
+use rand::Rng;
+use solvers::solve;
 
-///
-/// Data only modifiers
-///
+//
+// Data only modifiers
+//
 
 pub fn paint_distance_from_center(monitor: monitor::MazeReceiver) {
     let mut lk = match monitor.solver.lock() {
@@ -30,10 +31,7 @@ pub fn paint_distance_from_center(monitor: monitor::MazeReceiver) {
             map.max = cur.1;
         }
         for &p in maze::CARDINAL_DIRECTIONS.iter() {
-            let next = maze::Point {
-                row: cur.0.row + p.row,
-                col: cur.0.col + p.col,
-            };
+            let next = lk.maze.step(cur.0, p);
             if (lk.maze.get(next.row, next.col) & maze::PATH_BIT) == 0
                 || (lk.maze.get(next.row, next.col) & rgb::MEASURED) != 0
             {
@@ -48,7 +46,7 @@ pub fn paint_distance_from_center(monitor: monitor::MazeReceiver) {
 }
 
 fn painter(maze: &mut maze::Maze, map: &monitor::MaxMap) {
-    let mut rng = thread_rng();
+    let mut rng = solve::seeded_rng(maze);
     let rand_color_choice: usize = rng.gen_range(0..3);
     for r in 0..maze.rows() {
         for c in 0..maze.cols() {
@@ -59,20 +57,20 @@ fn painter(maze: &mut maze::Maze, map: &monitor::MaxMap) {
                 let bright = 128 + (127f64 * intensity) as u8;
                 let mut c: rgb::Rgb = [dark, dark, dark];
                 c[rand_color_choice] = bright;
-                *maze.get_mut(cur.row, cur.col) |= ((c[0] as u32) << rgb::RED_SHIFT)
-                    | ((c[1] as u32) << rgb::GREEN_SHIFT)
-                    | (c[2] as u32);
+                *maze.get_mut(cur.row, cur.col) |= ((c[0] as maze::Square) << rgb::RED_SHIFT)
+                    | ((c[1] as maze::Square) << rgb::GREEN_SHIFT)
+                    | (c[2] as maze::Square);
             }
         }
     }
 }
 
-///
-/// History based solvers.
-///
+//
+// History based solvers.
+//
 
 pub fn paint_distance_from_center_history(monitor: monitor::MazeMonitor) {
-    let start = if let Ok(mut lk) = monitor.lock() {
+    let (start, rand_color_choice) = if let Ok(mut lk) = monitor.lock() {
         let row_mid = lk.maze.rows() / 2;
         let col_mid = lk.maze.cols() / 2;
         let start = maze::Point {
@@ -87,10 +85,7 @@ pub fn paint_distance_from_center_history(monitor: monitor::MazeMonitor) {
                 lk.map.max = cur.1;
             }
             for &p in maze::CARDINAL_DIRECTIONS.iter() {
-                let next = maze::Point {
-                    row: cur.0.row + p.row,
-                    col: cur.0.col + p.col,
-                };
+                let next = lk.maze.step(cur.0, p);
                 if (lk.maze.get(next.row, next.col) & maze::PATH_BIT) == 0
                     || (lk.maze.get(next.row, next.col) & rgb::MEASURED) != 0
                 {
@@ -101,13 +96,11 @@ pub fn paint_distance_from_center_history(monitor: monitor::MazeMonitor) {
                 bfs.push_back((next, cur.1 + 1));
             }
         }
-        start
+        (start, solve::seeded_rng(&lk.maze).gen_range(0..3))
     } else {
         print::maze_panic!("Thread panic.");
     };
 
-    let mut rng = thread_rng();
-    let rand_color_choice: usize = rng.gen_range(0..3);
     let mut handles = Vec::with_capacity(rgb::NUM_PAINTERS - 1);
     for painter in 1..rgb::NUM_PAINTERS {
         let monitor_clone = monitor.clone();
@@ -161,14 +154,15 @@ fn painter_history(monitor: monitor::MazeMonitor, guide: rgb::ThreadGuide) {
                         id: cur,
                         before,
                         after: before
-                            | ((c[0] as u32) << rgb::RED_SHIFT)
-                            | ((c[1] as u32) << rgb::GREEN_SHIFT)
-                            | (c[2] as u32),
+                            | ((c[0] as maze::Square) << rgb::RED_SHIFT)
+                            | ((c[1] as maze::Square) << rgb::GREEN_SHIFT)
+                            | (c[2] as maze::Square),
                         burst: 1,
                     });
-                    *lk.maze.get_mut(cur.row, cur.col) |= ((c[0] as u32) << rgb::RED_SHIFT)
-                        | ((c[1] as u32) << rgb::GREEN_SHIFT)
-                        | (c[2] as u32);
+                    *lk.maze.get_mut(cur.row, cur.col) |= ((c[0] as maze::Square)
+                        << rgb::RED_SHIFT)
+                        | ((c[1] as maze::Square) << rgb::GREEN_SHIFT)
+                        | (c[2] as maze::Square);
                     lk.count += 1;
                 }
             }
@@ -177,20 +171,17 @@ fn painter_history(monitor: monitor::MazeMonitor, guide: rgb::ThreadGuide) {
         let mut i = guide.bias;
         for _ in 0..rgb::NUM_DIRECTIONS {
             let p = &maze::CARDINAL_DIRECTIONS[i];
-            let next = maze::Point {
-                row: cur.row + p.row,
-                col: cur.col + p.col,
-            };
-            if match monitor.lock() {
+            if let Some(next) = match monitor.lock() {
                 Err(p) => print::maze_panic!("Panic with lock: {}", p),
                 Ok(mut lk) => {
+                    let next = lk.maze.step(cur, *p);
                     let nxt = lk.maze.get(next.row, next.col);
                     let seen = (nxt & guide.cache) != 0;
                     let is_path = maze::is_path(nxt);
                     if !seen && is_path {
                         *lk.maze.get_mut(next.row, next.col) |= guide.cache;
                     }
-                    !seen && is_path
+                    (!seen && is_path).then_some(next)
                 }
             } {
                 bfs.push_back(next);
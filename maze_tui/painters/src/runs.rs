@@ -4,7 +4,8 @@ use maze;
 use std::collections::VecDeque;
 use std::thread;
 
-use rand::{thread_rng, Rng};
+use rand::Rng;
+use solvers::solve;
 
 struct RunPoint {
     len: u64,
@@ -12,9 +13,9 @@ struct RunPoint {
     cur: maze::Point,
 }
 
-///
-/// Data only measurements.
-///
+//
+// Data only measurements.
+//
 
 pub fn paint_run_lengths(monitor: monitor::MazeReceiver) {
     let mut lk = match monitor.solver.lock() {
@@ -39,21 +40,13 @@ pub fn paint_run_lengths(monitor: monitor::MazeReceiver) {
             map.max = cur.len;
         }
         for &p in maze::CARDINAL_DIRECTIONS.iter() {
-            let next = maze::Point {
-                row: cur.cur.row + p.row,
-                col: cur.cur.col + p.col,
-            };
+            let next = lk.maze.step(cur.cur, p);
             if lk.maze.wall_at(next.row, next.col)
                 || (lk.maze.get(next.row, next.col) & rgb::MEASURED) != 0
             {
                 continue;
             }
-            let next_run_len =
-                if (next.row).abs_diff(cur.prev.row) == (next.col).abs_diff(cur.prev.col) {
-                    1
-                } else {
-                    cur.len + 1
-                };
+            let next_run_len = if is_turn(&cur, p) { 1 } else { cur.len + 1 };
             *lk.maze.get_mut(next.row, next.col) |= rgb::MEASURED;
             map.distances.insert(next, next_run_len);
             bfs.push_back(RunPoint {
@@ -66,8 +59,15 @@ pub fn paint_run_lengths(monitor: monitor::MazeReceiver) {
     painter(&mut lk.maze, &map);
 }
 
+// A run continues while every step heads the same way as the last, even one under a crossing.
+#[inline]
+fn is_turn(run: &RunPoint, dir: maze::Point) -> bool {
+    (run.cur.row - run.prev.row).signum() != dir.row
+        || (run.cur.col - run.prev.col).signum() != dir.col
+}
+
 fn painter(maze: &mut maze::Maze, map: &monitor::MaxMap) {
-    let mut rng = thread_rng();
+    let mut rng = solve::seeded_rng(maze);
     let rand_color_choice: usize = rng.gen_range(0..3);
     for r in 0..maze.rows() {
         for c in 0..maze.cols() {
@@ -78,20 +78,20 @@ fn painter(maze: &mut maze::Maze, map: &monitor::MaxMap) {
                 let bright = 128 + (127f64 * intensity) as u8;
                 let mut c: rgb::Rgb = [dark, dark, dark];
                 c[rand_color_choice] = bright;
-                *maze.get_mut(cur.row, cur.col) |= ((c[0] as u32) << rgb::RED_SHIFT)
-                    | ((c[1] as u32) << rgb::GREEN_SHIFT)
-                    | (c[2] as u32);
+                *maze.get_mut(cur.row, cur.col) |= ((c[0] as maze::Square) << rgb::RED_SHIFT)
+                    | ((c[1] as maze::Square) << rgb::GREEN_SHIFT)
+                    | (c[2] as maze::Square);
             }
         }
     }
 }
 
-///
-/// History based solver.
-///
+//
+// History based solver.
+//
 
 pub fn paint_run_lengths_history(monitor: monitor::MazeMonitor) {
-    let start: maze::Point = if let Ok(mut lk) = monitor.lock() {
+    let (start, rand_color_choice) = if let Ok(mut lk) = monitor.lock() {
         let row_mid = lk.maze.rows() / 2;
         let col_mid = lk.maze.cols() / 2;
         let start = maze::Point {
@@ -110,21 +110,13 @@ pub fn paint_run_lengths_history(monitor: monitor::MazeMonitor) {
                 lk.map.max = cur.len;
             }
             for &p in maze::CARDINAL_DIRECTIONS.iter() {
-                let next = maze::Point {
-                    row: cur.cur.row + p.row,
-                    col: cur.cur.col + p.col,
-                };
+                let next = lk.maze.step(cur.cur, p);
                 if (lk.maze.get(next.row, next.col) & maze::PATH_BIT) == 0
                     || (lk.maze.get(next.row, next.col) & rgb::MEASURED) != 0
                 {
                     continue;
                 }
-                let next_run_len =
-                    if (next.row).abs_diff(cur.prev.row) == (next.col).abs_diff(cur.prev.col) {
-                        1
-                    } else {
-                        cur.len + 1
-                    };
+                let next_run_len = if is_turn(&cur, p) { 1 } else { cur.len + 1 };
                 *lk.maze.get_mut(next.row, next.col) |= rgb::MEASURED;
                 lk.map.distances.insert(next, next_run_len);
                 bfs.push_back(RunPoint {
@@ -134,13 +126,11 @@ pub fn paint_run_lengths_history(monitor: monitor::MazeMonitor) {
                 });
             }
         }
-        start
+        (start, solve::seeded_rng(&lk.maze).gen_range(0..3))
     } else {
         print::maze_panic!("Thread panic.");
     };
 
-    let mut rng = thread_rng();
-    let rand_color_choice: usize = rng.gen_range(0..3);
     let mut handles = Vec::with_capacity(rgb::NUM_PAINTERS);
     for painter in 0..rgb::NUM_PAINTERS - 1 {
         let monitor_clone = monitor.clone();
@@ -194,14 +184,15 @@ fn painter_history(monitor: monitor::MazeMonitor, guide: rgb::ThreadGuide) {
                         id: cur,
                         before,
                         after: before
-                            | ((c[0] as u32) << rgb::RED_SHIFT)
-                            | ((c[1] as u32) << rgb::GREEN_SHIFT)
-                            | (c[2] as u32),
+                            | ((c[0] as maze::Square) << rgb::RED_SHIFT)
+                            | ((c[1] as maze::Square) << rgb::GREEN_SHIFT)
+                            | (c[2] as maze::Square),
                         burst: 1,
                     });
-                    *lk.maze.get_mut(cur.row, cur.col) |= ((c[0] as u32) << rgb::RED_SHIFT)
-                        | ((c[1] as u32) << rgb::GREEN_SHIFT)
-                        | (c[2] as u32);
+                    *lk.maze.get_mut(cur.row, cur.col) |= ((c[0] as maze::Square)
+                        << rgb::RED_SHIFT)
+                        | ((c[1] as maze::Square) << rgb::GREEN_SHIFT)
+                        | (c[2] as maze::Square);
                     lk.count += 1;
                 }
             }
@@ -210,20 +201,17 @@ fn painter_history(monitor: monitor::MazeMonitor, guide: rgb::ThreadGuide) {
         let mut i = guide.bias;
         for _ in 0..rgb::NUM_DIRECTIONS {
             let p = &maze::CARDINAL_DIRECTIONS[i];
-            let next = maze::Point {
-                row: cur.row + p.row,
-                col: cur.col + p.col,
-            };
-            if match monitor.lock() {
+            if let Some(next) = match monitor.lock() {
                 Err(p) => print::maze_panic!("Panic with lock: {}", p),
                 Ok(mut lk) => {
+                    let next = lk.maze.step(cur, *p);
                     let nxt = lk.maze.get(next.row, next.col);
                     let seen = (nxt & guide.cache) == 0;
                     let is_path = maze::is_path(nxt);
                     if seen && is_path {
                         *lk.maze.get_mut(next.row, next.col) |= guide.cache;
                     }
-                    seen && is_path
+                    (seen && is_path).then_some(next)
                 }
             } {
                 bfs.push_back(next);
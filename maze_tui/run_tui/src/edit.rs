@@ -0,0 +1,297 @@
+use crate::{game, run, tui};
+use builders::build;
+use crossterm::event::KeyCode;
+use ratatui::prelude::Rect;
+use solvers::solve;
+use std::rc::Rc;
+
+// A brush repeats its edit on every square the cursor moves onto so whole regions can be walled
+// in or cleared out in one sweep. Picking a brush leaves the square under the cursor alone.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Brush {
+    Off,
+    Wall,
+    Path,
+}
+
+// Every change is recorded as one burst of deltas over all the squares it touched. Undoing takes
+// back the last burst and the bursts left over become part of the build history when solving.
+struct Editor {
+    board: maze::Maze,
+    cursor: maze::Point,
+    brush: Brush,
+    edits: maze::Tape,
+    solver: usize,
+}
+
+// A finished edit ready to be solved. Marks the person placed are stripped from the squares and
+// handed to the solvers through the maze instead so every solver can animate them the usual way.
+pub struct Edited {
+    pub board: maze::Maze,
+    pub edits: maze::Tape,
+    pub solve: tables::SolveHistoryFunction,
+}
+
+static EDIT_INSTRUCTIONS: &str = "[SPACE]flip [s]start [f]finish [u]undo [b]brush";
+
+//
+// Hand editing a finished maze. The editor starts from the maze as the builder left it and
+// leaving without solving returns to the animation as it was.
+//
+
+pub fn edit(
+    tui: &mut tui::Tui,
+    finished: &maze::Blueprint,
+    solve_tape: &maze::Tape,
+    seed: maze::Seed,
+    solve: tables::SolveHistoryFunction,
+    render_space: &Rc<[Rect]>,
+) -> tui::Result<Option<Edited>> {
+    let board = game::unsolved(finished, solve_tape, seed);
+    let mut editor = Editor {
+        cursor: maze::Point {
+            row: (board.rows() / 2) | 1,
+            col: (board.cols() / 2) | 1,
+        },
+        board,
+        brush: Brush::Off,
+        edits: maze::Tape::default(),
+        solver: tables::HISTORY_SOLVERS
+            .iter()
+            .position(|(_, f)| std::ptr::fn_addr_eq(*f, solve))
+            .unwrap_or(0),
+    };
+    let mut redraw = true;
+    loop {
+        if redraw {
+            tui.render_game_frame(
+                tui::EditFrame {
+                    maze: &editor.board.maze,
+                    cursor: editor.cursor,
+                },
+                render_space,
+                "edit",
+                &format!(
+                    "{} {}\n[[/]]solver {} [ENTER]solve [ESC]leave",
+                    EDIT_INSTRUCTIONS,
+                    editor.brush.name(),
+                    tables::HISTORY_SOLVERS[editor.solver].0
+                ),
+            )?;
+        }
+        redraw = match tui.events.next() {
+            Some(tui::Pack::Press(k)) => match k.code {
+                KeyCode::Esc => return Ok(None),
+                KeyCode::Enter => return Ok(Some(editor.finish())),
+                code => {
+                    editor.press(code);
+                    true
+                }
+            },
+            Some(tui::Pack::Render) => false,
+            Some(tui::Pack::Resize(_, _)) | None => return Err(Box::new(run::Quit::new())),
+        };
+    }
+}
+
+impl Brush {
+    fn next(self) -> Self {
+        match self {
+            Brush::Off => Brush::Wall,
+            Brush::Wall => Brush::Path,
+            Brush::Path => Brush::Off,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Brush::Off => "off",
+            Brush::Wall => "wall",
+            Brush::Path => "path",
+        }
+    }
+}
+
+impl Editor {
+    fn press(&mut self, code: KeyCode) {
+        let dir = match code {
+            KeyCode::Up => maze::CARDINAL_DIRECTIONS[0],
+            KeyCode::Right => maze::CARDINAL_DIRECTIONS[1],
+            KeyCode::Down => maze::CARDINAL_DIRECTIONS[2],
+            KeyCode::Left => maze::CARDINAL_DIRECTIONS[3],
+            KeyCode::Char(' ') => {
+                let p = self.cursor;
+                match self.board.path_at(p.row, p.col) {
+                    true => self.record(|board| wall(board, p)),
+                    false => self.record(|board| build::build_path(board, p)),
+                }
+                return;
+            }
+            KeyCode::Char('s') => return self.place(solve::START_BIT),
+            KeyCode::Char('f') => return self.place(solve::FINISH_BIT),
+            KeyCode::Char('u') => return self.undo(),
+            KeyCode::Char('b') => {
+                self.brush = self.brush.next();
+                return;
+            }
+            KeyCode::Char('[') => {
+                let n = tables::HISTORY_SOLVERS.len();
+                self.solver = (self.solver + n - 1) % n;
+                return;
+            }
+            KeyCode::Char(']') => {
+                self.solver = (self.solver + 1) % tables::HISTORY_SOLVERS.len();
+                return;
+            }
+            _ => return,
+        };
+        let next = maze::Point {
+            row: self.cursor.row + dir.row,
+            col: self.cursor.col + dir.col,
+        };
+        if self.editable(next) {
+            self.cursor = next;
+            self.paint();
+        }
+    }
+
+    // The outer walls stay so no solver can ever step off the maze. A polar ring may be edited
+    // across the seam in column 0 but never past its widest ring.
+    fn editable(&self, p: maze::Point) -> bool {
+        let (rows, cols) = (self.board.rows(), self.board.cols());
+        match self.board.topology() {
+            maze::topology::Topology::Polar => {
+                p.row > 0
+                    && p.row < rows - 1
+                    && p.col >= 0
+                    && p.col < maze::topology::polar_rings(rows, cols).width
+            }
+            _ => p.row > 0 && p.row < rows - 1 && p.col > 0 && p.col < cols - 1,
+        }
+    }
+
+    fn paint(&mut self) {
+        let p = self.cursor;
+        match self.brush {
+            Brush::Off => {}
+            Brush::Wall => {
+                if self.board.path_at(p.row, p.col) {
+                    self.record(|board| wall(board, p));
+                }
+            }
+            Brush::Path => {
+                if self.board.wall_at(p.row, p.col) {
+                    self.record(|board| build::build_path(board, p));
+                }
+            }
+        }
+    }
+
+    // There is one start and one finish so placing a mark moves it off wherever it was before.
+    fn place(&mut self, mark: solve::ThreadPaint) {
+        let p = self.cursor;
+        let square = self.board.get(p.row, p.col);
+        if !maze::is_path(square) || (square & (solve::START_BIT | solve::FINISH_BIT)) != 0 {
+            return;
+        }
+        let at = (p.row * self.board.cols() + p.col) as usize;
+        self.record(|board| {
+            for (i, s) in board.as_slice_mut().iter_mut().enumerate() {
+                if maze::is_path(*s) && i != at {
+                    *s &= !mark;
+                }
+            }
+            *board.get_mut(p.row, p.col) |= mark;
+        });
+    }
+
+    fn record(&mut self, change: impl FnOnce(&mut maze::Maze)) {
+        let before = self.board.as_slice().to_vec();
+        change(&mut self.board);
+        let cols = self.board.cols();
+        let mut burst: Vec<maze::Delta> = before
+            .iter()
+            .zip(self.board.as_slice())
+            .enumerate()
+            .filter(|(_, (b, a))| b != a)
+            .map(|(i, (&b, &a))| maze::Delta {
+                id: maze::Point {
+                    row: i as i32 / cols,
+                    col: i as i32 % cols,
+                },
+                before: b,
+                after: a,
+                burst: 0,
+            })
+            .collect();
+        let len = burst.len();
+        for d in burst.iter_mut() {
+            d.burst = len;
+        }
+        self.edits.push_burst(&burst);
+    }
+
+    fn undo(&mut self) {
+        for delta in self.edits.pop_burst().iter().rev() {
+            *self.board.get_mut(delta.id.row, delta.id.col) = delta.before;
+        }
+    }
+
+    // The marks come off the squares and the edits forget them too so the build history ends on
+    // the same clean maze the solver starts from.
+    fn finish(mut self) -> Edited {
+        let marks = solve::START_BIT | solve::FINISH_BIT;
+        let strip = |s: maze::Square| match maze::is_path(s) {
+            true => s & !marks,
+            false => s,
+        };
+        let cols = self.board.cols();
+        for (i, s) in self.board.maze.buf.iter_mut().enumerate() {
+            let at = maze::Point {
+                row: i as i32 / cols,
+                col: i as i32 % cols,
+            };
+            if maze::is_path(*s) && solve::is_start(*s) {
+                self.board.placed_start = Some(at);
+            }
+            if maze::is_path(*s) && solve::is_finish(*s) {
+                self.board.placed_finish = Some(at);
+            }
+            *s = strip(*s);
+        }
+        let mut edits = maze::Tape::default();
+        let mut i = 0;
+        while i < self.edits.len() {
+            let len = self.edits[i].burst.max(1);
+            let mut burst: Vec<maze::Delta> = self
+                .edits
+                .slice(i, i + len)
+                .iter()
+                .map(|d| maze::Delta {
+                    before: strip(d.before),
+                    after: strip(d.after),
+                    ..*d
+                })
+                .filter(|d| d.before != d.after)
+                .collect();
+            let kept = burst.len();
+            for d in burst.iter_mut() {
+                d.burst = kept;
+            }
+            edits.push_burst(&burst);
+            i += len;
+        }
+        Edited {
+            board: self.board,
+            edits,
+            solve: tables::HISTORY_SOLVERS[self.solver].1,
+        }
+    }
+}
+
+// Walls are rebuilt from nothing so no color, mark, or tunnel left on the square survives, then
+// joined to the walls around them so every glyph still connects.
+fn wall(board: &mut maze::Maze, p: maze::Point) {
+    *board.get_mut(p.row, p.col) = 0;
+    build::build_wall_carefully(board, p);
+}
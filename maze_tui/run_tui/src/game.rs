@@ -0,0 +1,466 @@
+use crate::{run, tui};
+use crossterm::event::KeyCode;
+use ratatui::prelude::Rect;
+use solvers::{bfs, solve};
+use std::{
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+// Every player paints a trail and the square they stand on like any solver thread so every frame
+// style already knows how to draw them. None of these colors belong to a solver thread so a
+// player is never mistaken for a ghost.
+struct Seat {
+    name: &'static str,
+    trail: solve::ThreadPaint,
+    head: solve::ThreadPaint,
+}
+
+const SEATS: [Seat; 2] = [
+    Seat {
+        name: "arrows",
+        trail: 0xc83c00,
+        head: 0xffffff,
+    },
+    Seat {
+        name: "wasd",
+        trail: 0x0078c8,
+        head: 0xffff00,
+    },
+];
+static WALK_INSTRUCTIONS: &str = "[←↑↓→/WASD]move [ESC]leave";
+static RACE_INSTRUCTIONS: &str = "[←↑↓→]arrows [WASD]wasd [ESC]leave";
+static GHOST_INSTRUCTIONS: &str = "[←↑↓→/WASD]move [</>]ghost speed [ESC]leave";
+// Render ticks keep coming at whatever speed the animation was left at. The clock only needs a
+// redraw this often and skipping the rest keeps key presses from queueing up behind them.
+const CLOCK_TICK: Duration = Duration::from_millis(100);
+
+// A walker is one person finding their way through the maze. Stepping back onto the square they
+// just left takes back the step so the trail is always the path they would give as an answer.
+struct Walker {
+    seat: usize,
+    at: maze::Point,
+    trail: Vec<maze::Point>,
+    moves: usize,
+}
+
+// A ghost replays a solver's recording one burst per render tick, so the playback speed chosen
+// with [</>] is the speed of the ghost. It arrives with the burst that first paints a finish.
+struct Ghost {
+    tape: maze::Tape,
+    next: usize,
+    arrival: usize,
+}
+
+// Only a ghost ever writes to the board. Players are drawn over a copy of it for every frame so
+// they never disturb each other or the ghost.
+struct Course {
+    board: maze::Maze,
+    finishes: Vec<maze::Point>,
+    walkers: Vec<Walker>,
+    ghost: Option<Ghost>,
+}
+
+//
+// Playing a finished maze by hand. Leaving any game returns to the animation as it was because
+// every game happens on a copy of the maze.
+//
+
+// The player starts and finishes where the single walker solvers would for this seed.
+pub fn walk(
+    tui: &mut tui::Tui,
+    finished: &maze::Blueprint,
+    solve_tape: &maze::Tape,
+    seed: maze::Seed,
+    render_space: &Rc<[Rect]>,
+) -> tui::Result<()> {
+    let mut board = unsolved(finished, solve_tape, seed);
+    let mut rng = solve::seeded_rng(&board);
+    let start = solve::pick_random_point(&board, &mut rng);
+    *board.get_mut(start.row, start.col) |= solve::START_BIT;
+    let finish = solve::pick_random_point(&board, &mut rng);
+    *board.get_mut(finish.row, finish.col) |= solve::FINISH_BIT;
+    let shortest = bfs::shortest_path_len(&board, start, finish);
+    let mut course = Course {
+        board,
+        finishes: vec![finish],
+        walkers: vec![Walker::new(0, start)],
+        ghost: None,
+    };
+    let began = Instant::now();
+    if play(tui, &mut course, render_space, WALK_INSTRUCTIONS, began)?.is_none() {
+        return Ok(());
+    }
+    let player = &course.walkers[0];
+    let summary = format!(
+        "you: {} squares, {} steps, {:.1}s\nshortest: {} [any key]leave",
+        player.trail.len() - 1,
+        player.moves,
+        began.elapsed().as_secs_f64(),
+        match shortest {
+            Some(best) => format!("{} squares", best),
+            None => String::from("unreachable"),
+        }
+    );
+    banner(tui, &course, render_space, "solved", &summary)
+}
+
+// Two players start in opposite corners and race to a square as far from one of them as from the
+// other. When there is a choice it is the one farthest from both so the race is worth running.
+pub fn race(
+    tui: &mut tui::Tui,
+    finished: &maze::Blueprint,
+    solve_tape: &maze::Tape,
+    seed: maze::Seed,
+    render_space: &Rc<[Rect]>,
+) -> tui::Result<()> {
+    let mut board = unsolved(finished, solve_tape, seed);
+    let corners = solve::set_corner_starts(&board);
+    let (first, second) = (corners[0], corners[3]);
+    let from_first = bfs::distances(&board, first);
+    let from_second = bfs::distances(&board, second);
+    let finish = match from_first
+        .iter()
+        .filter(|(p, _)| **p != first && **p != second)
+        .filter_map(|(p, &a)| from_second.get(p).map(|&b| (*p, a, b)))
+        .min_by_key(|&(p, a, b)| (a.abs_diff(b), usize::MAX - a.min(b), p.row, p.col))
+    {
+        Some((p, _, _)) => p,
+        None => solve::find_nearest_square(
+            &board,
+            maze::Point {
+                row: board.rows() / 2,
+                col: board.cols() / 2,
+            },
+        ),
+    };
+    for p in [first, second] {
+        *board.get_mut(p.row, p.col) |= solve::START_BIT;
+    }
+    *board.get_mut(finish.row, finish.col) |= solve::FINISH_BIT;
+    let mut course = Course {
+        board,
+        finishes: vec![finish],
+        walkers: vec![Walker::new(0, first), Walker::new(1, second)],
+        ghost: None,
+    };
+    let began = Instant::now();
+    let summary = match play(tui, &mut course, render_space, RACE_INSTRUCTIONS, began)? {
+        None => return Ok(()),
+        Some(winner) => format!(
+            "{} wins in {:.1}s\n[any key]leave",
+            SEATS[winner].name,
+            began.elapsed().as_secs_f64()
+        ),
+    };
+    banner(tui, &course, render_space, "winner", &summary)
+}
+
+// The ghost is a fresh recording of any solver that reaches a finish, chosen along with its speed
+// before the race. The player takes the ghost's start and wins by reaching any of its finishes
+// before the ghost does.
+pub fn race_ghost(
+    tui: &mut tui::Tui,
+    finished: &maze::Blueprint,
+    solve_tape: &maze::Tape,
+    seed: maze::Seed,
+    solve: tables::SolveHistoryFunction,
+    render_space: &Rc<[Rect]>,
+) -> tui::Result<()> {
+    let board = unsolved(finished, solve_tape, seed);
+    let ghost = match summon(tui, &board, solve, render_space)? {
+        Some(ghost) => ghost,
+        None => return Ok(()),
+    };
+    let marked = |bit: maze::Square| -> Vec<maze::Point> {
+        (0..ghost.tape.len())
+            .map(|i| ghost.tape[i])
+            .filter(|d| maze::is_path(d.after) && d.after & bit != 0 && d.before & bit == 0)
+            .map(|d| d.id)
+            .collect()
+    };
+    let start = match marked(solve::START_BIT).first() {
+        Some(&s) => s,
+        None => solve::pick_random_point(&board, &mut solve::seeded_rng(&board)),
+    };
+    let mut course = Course {
+        board,
+        finishes: marked(solve::FINISH_BIT),
+        walkers: vec![Walker::new(0, start)],
+        ghost: Some(ghost),
+    };
+    let began = Instant::now();
+    let (title, outcome) = match play(tui, &mut course, render_space, GHOST_INSTRUCTIONS, began)? {
+        None => return Ok(()),
+        Some(0) => ("winner", "you beat the ghost"),
+        Some(_) => ("ghost", "the ghost got there first"),
+    };
+    let summary = format!(
+        "{} in {:.1}s\n[any key]leave",
+        outcome,
+        began.elapsed().as_secs_f64()
+    );
+    banner(tui, &course, render_space, title, &summary)
+}
+
+// Picking a ghost starts from the solver that just ran. Solvers that only paint or fill the maze
+// never arrive at a finish so they cannot be raced and picking one says so.
+fn summon(
+    tui: &mut tui::Tui,
+    board: &maze::Maze,
+    solve: tables::SolveHistoryFunction,
+    render_space: &Rc<[Rect]>,
+) -> tui::Result<Option<Ghost>> {
+    let mut solver = tables::HISTORY_SOLVERS
+        .iter()
+        .position(|(_, f)| std::ptr::fn_addr_eq(*f, solve))
+        .unwrap_or(0);
+    let mut level = tui::DEFAULT_SPEED;
+    let mut refused = false;
+    let mut redraw = true;
+    loop {
+        if redraw {
+            let name = tables::HISTORY_SOLVERS[solver].0;
+            tui.render_game_frame(
+                tui::SolveFrame { maze: &board.maze },
+                render_space,
+                "ghost",
+                &format!(
+                    "[[/]]ghost {} [-/+]speed {}\n{}",
+                    name,
+                    level,
+                    match refused {
+                        true => format!("{} never reaches a finish [ESC]leave", name),
+                        false => String::from("[ENTER]race [ESC]leave"),
+                    }
+                ),
+            )?;
+        }
+        redraw = match tui.events.next() {
+            Some(tui::Pack::Press(k)) => {
+                let n = tables::HISTORY_SOLVERS.len();
+                match k.code {
+                    KeyCode::Esc => return Ok(None),
+                    KeyCode::Enter => match Ghost::record(board, tables::HISTORY_SOLVERS[solver].1)
+                    {
+                        Some(ghost) => {
+                            tui.events
+                                .set_rate(tui::speed_tick(&solve::SOLVER_SPEEDS, level));
+                            return Ok(Some(ghost));
+                        }
+                        None => refused = true,
+                    },
+                    KeyCode::Char('[') => (solver, refused) = ((solver + n - 1) % n, false),
+                    KeyCode::Char(']') => (solver, refused) = ((solver + 1) % n, false),
+                    KeyCode::Char('-') => level = (level - 1).max(1),
+                    KeyCode::Char('+') | KeyCode::Char('=') => {
+                        level = (level + 1).min(solve::SOLVER_SPEEDS.len() - 1)
+                    }
+                    _ => {}
+                }
+                true
+            }
+            Some(tui::Pack::Render) => false,
+            Some(tui::Pack::Resize(_, _)) | None => return Err(Box::new(run::Quit::new())),
+        };
+    }
+}
+
+// Undoing every delta from last to first leaves each square as it was before the solver first
+// touched it, no matter how far the animation had played.
+pub fn unsolved(
+    finished: &maze::Blueprint,
+    solve_tape: &maze::Tape,
+    seed: maze::Seed,
+) -> maze::Maze {
+    let mut board = maze::Maze::from_blueprint(finished.clone(), seed);
+    for i in (0..solve_tape.len()).rev() {
+        let delta = solve_tape[i];
+        *board.get_mut(delta.id.row, delta.id.col) = delta.before;
+    }
+    board
+}
+
+// Runs a game until someone finishes and returns who did. The ghost, if there is one, takes the
+// seat after the last walker. Leaving early returns nobody.
+fn play(
+    tui: &mut tui::Tui,
+    course: &mut Course,
+    render_space: &Rc<[Rect]>,
+    instructions: &str,
+    began: Instant,
+) -> tui::Result<Option<usize>> {
+    let (mut drawn, mut redraw) = (began, true);
+    loop {
+        if redraw {
+            let steps: Vec<String> = course.walkers.iter().map(|w| w.moves.to_string()).collect();
+            tui.render_game_frame(
+                tui::SolveFrame {
+                    maze: &course.frame(),
+                },
+                render_space,
+                "play",
+                &format!(
+                    "{}\nsteps {} time {:.1}s",
+                    instructions,
+                    steps.join("/"),
+                    began.elapsed().as_secs_f64()
+                ),
+            )?;
+            drawn = Instant::now();
+        }
+        redraw = match tui.events.next() {
+            Some(tui::Pack::Press(k)) => match k.code {
+                KeyCode::Esc => return Ok(None),
+                code => {
+                    if let Some(winner) = course.press(code) {
+                        return Ok(Some(winner));
+                    }
+                    true
+                }
+            },
+            Some(tui::Pack::Render) => {
+                if let Some(ghost) = &mut course.ghost {
+                    if !ghost.haunt(&mut course.board) {
+                        return Ok(Some(course.walkers.len()));
+                    }
+                }
+                drawn.elapsed() >= CLOCK_TICK
+            }
+            Some(tui::Pack::Resize(_, _)) | None => return Err(Box::new(run::Quit::new())),
+        };
+    }
+}
+
+// The last frame of a game stays up until any key is pressed.
+fn banner(
+    tui: &mut tui::Tui,
+    course: &Course,
+    render_space: &Rc<[Rect]>,
+    title: &str,
+    summary: &str,
+) -> tui::Result<()> {
+    tui.render_game_frame(
+        tui::SolveFrame {
+            maze: &course.frame(),
+        },
+        render_space,
+        title,
+        summary,
+    )?;
+    loop {
+        match tui.events.next() {
+            Some(tui::Pack::Press(_)) => return Ok(()),
+            Some(tui::Pack::Render) => {}
+            Some(tui::Pack::Resize(_, _)) | None => return Err(Box::new(run::Quit::new())),
+        }
+    }
+}
+
+// Arrows belong to the first seat and WASD to the second.
+fn heading(code: KeyCode) -> Option<(usize, maze::Point)> {
+    match code {
+        KeyCode::Up => Some((0, maze::CARDINAL_DIRECTIONS[0])),
+        KeyCode::Right => Some((0, maze::CARDINAL_DIRECTIONS[1])),
+        KeyCode::Down => Some((0, maze::CARDINAL_DIRECTIONS[2])),
+        KeyCode::Left => Some((0, maze::CARDINAL_DIRECTIONS[3])),
+        KeyCode::Char('w') => Some((1, maze::CARDINAL_DIRECTIONS[0])),
+        KeyCode::Char('d') => Some((1, maze::CARDINAL_DIRECTIONS[1])),
+        KeyCode::Char('s') => Some((1, maze::CARDINAL_DIRECTIONS[2])),
+        KeyCode::Char('a') => Some((1, maze::CARDINAL_DIRECTIONS[3])),
+        _ => None,
+    }
+}
+
+impl Course {
+    // A lone player may use either set of keys.
+    fn press(&mut self, code: KeyCode) -> Option<usize> {
+        let (seat, dir) = heading(code)?;
+        let seat = match self.walkers.len() {
+            1 => 0,
+            _ => seat,
+        };
+        let walker = &mut self.walkers[seat];
+        if walker.step(&self.board, dir) && self.finishes.contains(&walker.at) {
+            return Some(seat);
+        }
+        None
+    }
+
+    fn frame(&self) -> maze::Blueprint {
+        let mut frame = self.board.maze.clone();
+        for walker in &self.walkers {
+            let seat = &SEATS[walker.seat];
+            for p in &walker.trail {
+                recolor(&mut frame, *p, seat.trail);
+            }
+            recolor(&mut frame, walker.at, seat.head);
+        }
+        frame
+    }
+}
+
+impl Walker {
+    fn new(seat: usize, start: maze::Point) -> Self {
+        Self {
+            seat,
+            at: start,
+            trail: vec![start],
+            moves: 0,
+        }
+    }
+
+    // Walls stop the player. Moving uses the same step as the solvers so tunnels and the rings
+    // of a polar maze behave exactly as they do for them.
+    fn step(&mut self, board: &maze::Maze, dir: maze::Point) -> bool {
+        let next = board.step(self.at, dir);
+        if !board.path_at(next.row, next.col) {
+            return false;
+        }
+        let len = self.trail.len();
+        if len >= 2 && self.trail[len - 2] == next {
+            self.trail.pop();
+        } else {
+            self.trail.push(next);
+        }
+        self.at = next;
+        self.moves += 1;
+        true
+    }
+}
+
+impl Ghost {
+    fn record(board: &maze::Maze, solve: tables::SolveHistoryFunction) -> Option<Self> {
+        let monitor = monitor::Monitor::new(board.clone());
+        solve(monitor.clone());
+        let tape = match monitor.lock() {
+            Ok(lk) => lk.maze.solve_history.clone(),
+            Err(_) => print::maze_panic!("ghost cannot record without lock"),
+        };
+        let arrival = (0..tape.len()).find(|&i| {
+            let d = tape[i];
+            solve::is_finish(d.before) && !solve::is_color(d.before) && solve::is_color(d.after)
+        })?;
+        Some(Self {
+            tape,
+            next: 0,
+            arrival,
+        })
+    }
+
+    // Returns false once the burst carrying the ghost onto a finish has been played.
+    fn haunt(&mut self, board: &mut maze::Maze) -> bool {
+        let end = (self.next + self.tape[self.next].burst.max(1)).min(self.tape.len());
+        for delta in self.tape.slice(self.next, end) {
+            *board.get_mut(delta.id.row, delta.id.col) = delta.after;
+        }
+        self.next = end;
+        self.next <= self.arrival
+    }
+}
+
+#[inline]
+fn recolor(maze: &mut maze::Blueprint, p: maze::Point, paint: solve::ThreadPaint) {
+    let square = &mut maze.buf[(p.row * maze.cols + p.col) as usize];
+    *square = (*square & !solve::THREAD_MASK) | paint;
+}
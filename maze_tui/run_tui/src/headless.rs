@@ -0,0 +1,148 @@
+use crate::run;
+use crate::tui;
+use rand::{thread_rng, Rng};
+use ratatui::{
+    buffer::Buffer,
+    prelude::{Color, Modifier, Rect},
+    widgets::Widget,
+};
+use std::io::{self, BufWriter, Write};
+
+pub const FLAG: &str = "--headless";
+static USAGE: &str = "usage: run_tui --headless [-b builder] [-m modification] [-s solver] \
+[-w wall style] [-seed N] [-save file] [-load file] [-record file] [-replay file] [-svg file] [-mask file] [-text message] \
+[-topology grid] [--rows N] [--cols N] [--color] [--png file] [--gif file] [--cell N] [--frame-skip N]";
+// Builders and solvers pick random squares strictly inside the outer walls and need a few to choose from.
+const MIN_ROWS: i32 = 7;
+const MIN_COLS: i32 = 7;
+
+//
+// Headless runs skip the animation entirely and print the finished maze for scripts and pipes.
+//
+
+pub fn run(args: &[String]) -> tui::Result<()> {
+    let mut run = tables::HistoryRunner::new();
+    run.args.odd_rows = maze::DEFAULT_ROWS;
+    run.args.odd_cols = maze::DEFAULT_COLS;
+    run.args.seed = thread_rng().gen();
+    let mut color = false;
+    let mut png = None;
+    let mut gif = None;
+    let mut raster = export::raster::RasterArgs::default();
+    let mut words = args.iter();
+    while let Some(flag) = words.next() {
+        if flag == "--color" {
+            color = true;
+            continue;
+        }
+        let arg = match words.next() {
+            Some(arg) => arg,
+            None => return Err(format!("flag[{}] with missing arg[?]\n{}", flag, USAGE).into()),
+        };
+        match flag.as_str() {
+            "--rows" => run.args.odd_rows = parse_dimension(flag, arg, MIN_ROWS)?,
+            "--cols" => run.args.odd_cols = parse_dimension(flag, arg, MIN_COLS)?,
+            "--png" => png = Some(std::path::PathBuf::from(arg)),
+            "--gif" => gif = Some(std::path::PathBuf::from(arg)),
+            "--cell" => raster.cell_size = parse_count(flag, arg)? as u32,
+            "--frame-skip" => raster.frame_skip = parse_count(flag, arg)?,
+            _ => match tables::search_table(flag, &tables::FLAGS) {
+                Some(flag) => run::set_arg(&mut run, &tables::FlagArg { flag, arg })?,
+                None => return Err(format!("unknown flag[{}]\n{}", flag, USAGE).into()),
+            },
+        }
+    }
+    if run.args.style == maze::MazeStyle::Mini {
+        run.args.odd_rows *= 2;
+    }
+    let mut finished = match run.replay.take() {
+        Some(replay) => replay,
+        None => run::record_maze(&run)?,
+    };
+    if let Some(path) = &gif {
+        export::raster::save_gif(path, &finished, &raster)?;
+    }
+    finished.play_to_end();
+    if let Some(path) = &png {
+        export::raster::save_png(path, &finished.maze, raster.cell_size)?;
+    }
+    if let Some(path) = &run.svg {
+        export::svg::save(path, &finished.maze, export::svg::DEFAULT_CELL_SIZE, true)?;
+    }
+    print_maze(&finished.maze, color)?;
+    Ok(())
+}
+
+fn parse_dimension(flag: &str, arg: &str, min: i32) -> Result<i32, String> {
+    match arg.parse::<i32>() {
+        Ok(n) if n >= min => Ok(n),
+        _ => Err(format!(
+            "invalid flag[{}] arg[{}] combo, need at least {}",
+            flag, arg, min
+        )),
+    }
+}
+
+fn parse_count(flag: &str, arg: &str) -> Result<usize, String> {
+    match arg.parse::<usize>() {
+        Ok(n) if n >= 1 => Ok(n),
+        _ => Err(format!(
+            "invalid flag[{}] arg[{}] combo, need at least 1",
+            flag, arg
+        )),
+    }
+}
+
+// The TUI frames already know how to decode every square into a cell so render the finished maze
+// into an off screen buffer and translate each cell into text and optional ANSI escape codes.
+fn print_maze(maze: &maze::Blueprint, color: bool) -> io::Result<()> {
+    let height = if maze.is_mini() {
+        maze.rows / 2 + 1
+    } else {
+        maze.rows
+    };
+    let area = Rect::new(0, 0, maze.cols as u16, height as u16);
+    let mut buf = Buffer::empty(area);
+    tui::SolveFrame { maze }.render(area, &mut buf);
+    let mut out = BufWriter::new(io::stdout().lock());
+    for y in 0..area.height {
+        let mut prev_style = String::new();
+        for x in 0..area.width {
+            let cell = buf.get(x, y);
+            if color {
+                let style = ansi_style(cell.fg, cell.bg, cell.modifier);
+                if style != prev_style {
+                    write!(out, "{}", style)?;
+                    prev_style = style;
+                }
+            }
+            write!(out, "{}", cell.symbol)?;
+        }
+        if color {
+            write!(out, "\x1b[0m")?;
+        }
+        writeln!(out)?;
+    }
+    out.flush()
+}
+
+// Unpainted squares decode to black which only looks right on a dark terminal so leave them alone.
+fn ansi_style(fg: Color, bg: Color, modifier: Modifier) -> String {
+    let mut codes = vec!["0".to_string()];
+    if modifier.contains(Modifier::BOLD) {
+        codes.push("1".to_string());
+    }
+    match fg {
+        Color::Rgb(0, 0, 0) => {}
+        Color::Rgb(r, g, b) => codes.push(format!("38;2;{};{};{}", r, g, b)),
+        Color::Indexed(i) => codes.push(format!("38;5;{}", i)),
+        _ => {}
+    }
+    match bg {
+        Color::Rgb(0, 0, 0) => {}
+        Color::Rgb(r, g, b) => codes.push(format!("48;2;{};{};{}", r, g, b)),
+        Color::Indexed(i) => codes.push(format!("48;5;{}", i)),
+        _ => {}
+    }
+    format!("\x1b[{}m", codes.join(";"))
+}
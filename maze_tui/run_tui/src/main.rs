@@ -1,7 +1,18 @@
+mod edit;
+mod game;
+mod headless;
 mod run;
 mod tui;
 
 fn main() -> tui::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|a| a == headless::FLAG) {
+        if let Err(e) = headless::run(&args[1..]) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
     let status = run::run();
     status?;
     Ok(())
@@ -1,22 +1,34 @@
-use crate::tui;
+use crate::{edit, game, tui};
 use builders::build;
 use crossterm::event::KeyCode;
-use rand::{distributions::Bernoulli, distributions::Distribution, seq::SliceRandom, thread_rng};
+use rand::{
+    distributions::Bernoulli, distributions::Distribution, seq::SliceRandom, thread_rng, Rng,
+};
 use ratatui::{
     prelude::{CrosstermBackend, Rect, Terminal},
     widgets::ScrollDirection,
 };
-use std::{error, fmt, rc::Rc, sync::Arc, sync::Mutex};
+use std::{cmp, error, fmt, rc::Rc, sync::Arc, sync::Mutex, time::Duration};
 use tui_textarea::{Input, Key};
 
-static VALID_FLAGS: &str = "VALID FLAGS:[-b][-ba][-s][-sa][-w][-m]";
-static VALID_ARGS: [(&str, &str); 6] = [
+static VALID_FLAGS: &str =
+    "VALID FLAGS:[-b][-ba][-s][-sa][-w][-m][-seed][-save][-load][-record][-replay][-svg][-mask][-text][-topology]";
+static VALID_ARGS: [(&str, &str); 15] = [
     ("-b", "see BUILDER FLAG section"),
     ("-m", "see MODIFICATION FLAG section"),
     ("-w", "see WALL FLAG section"),
     ("-s", "see SOLVER FLAG section"),
     ("-sa", "see SOLVER ANIMATION section"),
     ("-ba", "see BUILDER ANIMATION section"),
+    ("-seed", "see SEED FLAG section"),
+    ("-save", "see SAVE AND LOAD section"),
+    ("-load", "see SAVE AND LOAD section"),
+    ("-record", "see RECORD AND REPLAY section"),
+    ("-replay", "see RECORD AND REPLAY section"),
+    ("-svg", "see EXPORT section"),
+    ("-mask", "see MASK FLAG section"),
+    ("-text", "see TEXT FLAG section"),
+    ("-topology", "see TOPOLOGY FLAG section"),
 ];
 
 #[derive(Debug)]
@@ -44,13 +56,18 @@ struct Playback {
     maze: maze::Blueprint,
     build_tape: maze::Tape,
     solve_tape: maze::Tape,
+    seed: maze::Seed,
     forward: bool,
     pause: bool,
+    // Timeline positions marked with [m], kept in order.
+    bookmarks: Vec<usize>,
+    // Set when a jump lands on the other side of the build and solve split so the loop switches.
+    crossed: Option<tui::Process>,
 }
 
-///
-/// Main TUI program running and logic.
-///
+//
+// Main TUI program running and logic.
+//
 
 /// The main render loop from the home page. This loop is relatively simple. When the more
 /// complex functionality of a maze animation is requested we will hand that off to another fn.
@@ -72,9 +89,11 @@ pub fn run() -> tui::Result<()> {
                 Input { key: Key::Up, .. } => tui.scroll(ScrollDirection::Backward),
                 Input {
                     key: Key::Enter, ..
-                } => match set_command_args(tui.cmd.lines()[0].to_string(), &mut tui) {
-                    Ok(run) => {
-                        render_maze(run, &mut tui)?;
+                } => match set_command_args(tui.cmd.lines()[0].to_string(), &mut tui)
+                    .and_then(|run| new_tape(&run).map(|tape| (run, tape)))
+                {
+                    Ok((run, tape)) => {
+                        render_maze(run, tape, &mut tui)?;
                     }
                     Err(msg) => 'reading_message: loop {
                         if let Some(ev) = tui.events.next() {
@@ -109,10 +128,18 @@ pub fn run() -> tui::Result<()> {
 // Keeping the three loops visible in one function like this makes it easier to reason about
 // playing the animation forward or in reverse. The handle_press function can mutate the
 // play direction but needed to extract repetitive logic that made this function harder to read.
-fn render_maze(this_run: tables::HistoryRunner, tui: &mut tui::Tui) -> tui::Result<()> {
+fn render_maze(
+    this_run: tables::HistoryRunner,
+    mut play: Playback,
+    tui: &mut tui::Tui,
+) -> tui::Result<()> {
     let render_space = tui.inner_maze_rect();
-    let mut play = new_tape(&this_run);
     'rendering: loop {
+        tui.events.set_rate(
+            this_run
+                .build_speed
+                .unwrap_or(tui::speed_tick(&build::BUILDER_SPEEDS, tui::DEFAULT_SPEED)),
+        );
         'building: while let Some(ev) = tui.events.next() {
             match ev {
                 tui::Pack::Press(ev) => {
@@ -126,23 +153,31 @@ fn render_maze(this_run: tables::HistoryRunner, tui: &mut tui::Tui) -> tui::Resu
                     ) {
                         break 'rendering;
                     }
+                    if play.crossed.take().is_some() {
+                        break 'building;
+                    }
                 }
                 tui::Pack::Render => {
                     if !play.build_delta() {
                         break 'building;
                     }
                     tui.render_maze_frame(
-                        tui::BuildFrame {
-                            maze: &mut play.maze,
-                        },
+                        tui::BuildFrame { maze: &play.maze },
                         &render_space,
+                        play.seed,
                         play.forward,
                         play.pause,
+                        &play.timeline(tui::Process::Building),
                     )?;
                 }
                 tui::Pack::Resize(_, _) => break 'rendering,
             }
         }
+        tui.events
+            .set_rate(this_run.solve_speed.unwrap_or(tui::speed_tick(
+                &tables::solve::SOLVER_SPEEDS,
+                tui::DEFAULT_SPEED,
+            )));
         'solving: while let Some(ev) = tui.events.next() {
             match ev {
                 tui::Pack::Press(ev) => {
@@ -156,6 +191,9 @@ fn render_maze(this_run: tables::HistoryRunner, tui: &mut tui::Tui) -> tui::Resu
                     ) {
                         break 'rendering;
                     }
+                    if play.crossed.take().is_some() {
+                        break 'solving;
+                    }
                 }
                 tui::Pack::Render => {
                     if !play.solve_delta() {
@@ -164,8 +202,10 @@ fn render_maze(this_run: tables::HistoryRunner, tui: &mut tui::Tui) -> tui::Resu
                     tui.render_maze_frame(
                         tui::SolveFrame { maze: &play.maze },
                         &render_space,
+                        play.seed,
                         play.forward,
                         play.pause,
+                        &play.timeline(tui::Process::Solving),
                     )?;
                 }
                 tui::Pack::Resize(_, _) => break 'rendering,
@@ -197,7 +237,80 @@ fn handle_press(
                 return false;
             }
         }
+        KeyCode::Char('e') if matches!(process, tui::Process::Solving) => {
+            match edit::edit(
+                tui,
+                &play.maze,
+                &play.solve_tape,
+                play.seed,
+                args.solve,
+                render_space,
+            ) {
+                Ok(Some(edited)) => play.solve_edited(edited),
+                Ok(None) => {}
+                Err(_) => return false,
+            }
+        }
+        KeyCode::Char('p') if matches!(process, tui::Process::Solving) => {
+            if game::walk(tui, &play.maze, &play.solve_tape, play.seed, render_space).is_err() {
+                return false;
+            }
+        }
+        KeyCode::Char('r') if matches!(process, tui::Process::Solving) => {
+            if game::race(tui, &play.maze, &play.solve_tape, play.seed, render_space).is_err() {
+                return false;
+            }
+        }
+        KeyCode::Char('g') if matches!(process, tui::Process::Solving) => {
+            if game::race_ghost(
+                tui,
+                &play.maze,
+                &play.solve_tape,
+                play.seed,
+                args.solve,
+                render_space,
+            )
+            .is_err()
+            {
+                return false;
+            }
+        }
         KeyCode::Char(' ') => play.pause = !play.pause,
+        KeyCode::Char('[') => {
+            let at = play.position(process);
+            play.seek(process, at.saturating_sub(play.tenth()));
+        }
+        KeyCode::Char(']') => {
+            let at = play.position(process);
+            play.seek(process, at + play.tenth());
+        }
+        KeyCode::Char(d @ '0'..='9') => {
+            let total = play.build_tape.len() + play.solve_tape.len();
+            play.seek(process, total * (d as usize - '0' as usize) / 10);
+        }
+        KeyCode::Home => play.seek(process, 0),
+        KeyCode::End => play.seek(process, usize::MAX),
+        KeyCode::Char('m') => {
+            let at = play.position(process);
+            match play.bookmarks.binary_search(&at) {
+                Ok(i) => {
+                    play.bookmarks.remove(i);
+                }
+                Err(i) => play.bookmarks.insert(i, at),
+            }
+        }
+        KeyCode::Char('n') => {
+            let at = play.position(process);
+            if let Some(&mark) = play.bookmarks.iter().find(|&&m| m > at) {
+                play.seek(process, mark);
+            }
+        }
+        KeyCode::Char('b') => {
+            let at = play.position(process);
+            if let Some(&mark) = play.bookmarks.iter().rev().find(|&&m| m < at) {
+                play.seek(process, mark);
+            }
+        }
         KeyCode::Right => {
             play.forward = true;
             play.pause = true;
@@ -248,31 +361,92 @@ fn handle_reader(
     Ok(())
 }
 
-///
-/// Maze generation and solving. It is simple because we don't have to worry about animations
-/// until the maze generation and solving histories have been recorded. Then we decide how
-/// we want to play all of that back with the help of builder and solver decoding functions.
-///
+//
+// Maze generation and solving. It is simple because we don't have to worry about animations
+// until the maze generation and solving histories have been recorded. Then we decide how
+// we want to play all of that back with the help of builder and solver decoding functions.
+//
+
+// A new tape plays back a recording as is or records a fresh run of every requested algorithm.
+fn new_tape(run: &tables::HistoryRunner) -> Result<Playback, String> {
+    let mut recorded = match &run.replay {
+        Some(replay) => replay.clone(),
+        None => record_maze(run).map_err(|e| format!("{}\npress any key to continue", e))?,
+    };
+    recorded.maze.offset = run.args.offset;
+    if let Some(path) = &run.svg {
+        let mut finished = recorded.clone();
+        finished.play_to_end();
+        export::svg::save(path, &finished.maze, export::svg::DEFAULT_CELL_SIZE, true)
+            .map_err(|e| format!("svg export failed: {}\npress any key to continue", e))?;
+    }
+    Ok(Playback {
+        maze: recorded.maze,
+        build_tape: recorded.build_history,
+        solve_tape: recorded.solve_history,
+        seed: recorded.seed,
+        forward: true,
+        pause: false,
+        bookmarks: Vec::new(),
+        crossed: None,
+    })
+}
 
-// A new tape runs to completion then resets the maze buffer to its starting state.
-fn new_tape(run: &tables::HistoryRunner) -> Playback {
-    let monitor = monitor::Monitor::new(maze::Maze::new(run.args));
-    (run.build)(monitor.clone());
-    if let Some(m) = run.modify {
-        (m)(monitor.clone());
+// Runs the algorithms to completion then resets the maze buffer to its starting state. A loaded
+// maze has no build history so it only resets to the finished maze the solver started with.
+pub fn record_maze(run: &tables::HistoryRunner) -> Result<maze::Maze, String> {
+    let monitor = match &run.load {
+        Some(blueprint) => {
+            let mut loaded = maze::Maze::from_blueprint(blueprint.clone(), run.args.seed);
+            loaded.maze.offset = run.args.offset;
+            monitor::Monitor::new(loaded)
+        }
+        None => {
+            let mut maze = maze::Maze::new(run.args);
+            check_topology(run, &maze)?;
+            if let Some(mask) = &run.mask {
+                if !tables::supports_mask(&run.build) {
+                    return Err(String::from(
+                        "builder cannot follow a mask, try rdfs, kruskal, prim, wilson, or growing-tree",
+                    ));
+                }
+                maze.set_mask(mask);
+            }
+            if let Some(text) = &run.text {
+                let layout = tables::text::layout(text, &maze)?;
+                if tables::supports_mask(&run.build) {
+                    maze.reserve(layout.letters());
+                }
+            }
+            let monitor = monitor::Monitor::new(maze);
+            run_with_arg(&monitor, run.build, &run.build_arg);
+            if let Some(m) = run.modify {
+                run_with_arg(&monitor, m, &run.modify_arg);
+            }
+            if run.text.is_some() {
+                run_with_arg(&monitor, tables::text::add_text_history, &run.text);
+            }
+            monitor
+        }
+    };
+    if let Some(path) = &run.save {
+        match monitor.lock() {
+            Ok(lk) => maze::save::save(path, &lk.maze.maze).map_err(|e| e.to_string())?,
+            Err(_) => print::maze_panic!("rendering cannot progress without lock"),
+        }
     }
     (run.solve)(monitor.clone());
     match Arc::into_inner(monitor) {
         Some(a) => match Mutex::into_inner(a) {
             Ok(mut solver) => {
-                build::reset_build(&mut solver.maze);
-                Playback {
-                    maze: solver.maze.maze,
-                    build_tape: solver.maze.build_history,
-                    solve_tape: solver.maze.solve_history,
-                    forward: true,
-                    pause: false,
+                match run.load {
+                    Some(_) => solvers::solve::reset_solve(&mut solver.maze),
+                    None => build::reset_build(&mut solver.maze),
+                }
+                if let Some(path) = &run.record {
+                    maze::save::save_history(path, &solver.maze).map_err(|e| e.to_string())?;
                 }
+                Ok(solver.maze)
             }
             Err(_) => print::maze_panic!("rendering cannot progress without lock"),
         },
@@ -280,6 +454,48 @@ fn new_tape(run: &tables::HistoryRunner) -> Playback {
     }
 }
 
+// Hex and triangle grids only work with the builders that know their neighbors and nothing that
+// assumes square cells may run after them.
+fn check_topology(run: &tables::HistoryRunner, maze: &maze::Maze) -> Result<(), String> {
+    let topology = maze.topology();
+    if topology == maze::topology::Topology::Square {
+        return Ok(());
+    }
+    if !tables::supports_topology(&run.build) {
+        return Err(format!(
+            "builder cannot build {} grids, try rdfs or kruskal",
+            topology.name()
+        ));
+    }
+    if run.modify.is_some() || run.mask.is_some() || run.text.is_some() {
+        return Err(format!(
+            "{} grids cannot be combined with -m, -mask, or -text",
+            topology.name()
+        ));
+    }
+    if topology.cells(maze.rows(), maze.cols()).is_empty() {
+        return Err(format!("maze is too small for a {} grid", topology.name()));
+    }
+    Ok(())
+}
+
+// The monitor only carries the argument while its algorithm runs so the next one never sees it.
+fn run_with_arg(
+    monitor: &monitor::MazeMonitor,
+    func: tables::BuildHistoryFunction,
+    arg: &Option<String>,
+) {
+    match monitor.lock() {
+        Ok(mut lk) => lk.arg.clone_from(arg),
+        Err(_) => print::maze_panic!("rendering cannot progress without lock"),
+    }
+    func(monitor.clone());
+    match monitor.lock() {
+        Ok(mut lk) => lk.arg = None,
+        Err(_) => print::maze_panic!("rendering cannot progress without lock"),
+    }
+}
+
 // A new home tape solves everything but then only resets the solver for less distracting home.
 fn new_home_tape(rect: Rect) -> Playback {
     let run_bg = set_random_args(&rect);
@@ -297,8 +513,11 @@ fn new_home_tape(rect: Rect) -> Playback {
                     maze: solver.maze.maze,
                     build_tape: solver.maze.build_history,
                     solve_tape: solver.maze.solve_history,
+                    seed: solver.maze.seed,
                     forward: true,
                     pause: false,
+                    bookmarks: Vec::new(),
+                    crossed: None,
                 }
             }
             Err(_) => print::maze_panic!("rendering cannot progress without lock"),
@@ -307,9 +526,9 @@ fn new_home_tape(rect: Rect) -> Playback {
     }
 }
 
-///
-/// Argument parsing from the tui-textarea or random generation if empty
-///
+//
+// Argument parsing from the tui-textarea or random generation if empty
+//
 
 pub fn set_command_args(cmd: String, tui: &mut tui::Tui) -> Result<tables::HistoryRunner, String> {
     if cmd.is_empty() {
@@ -320,9 +539,10 @@ pub fn set_command_args(cmd: String, tui: &mut tui::Tui) -> Result<tables::Histo
     run.args.odd_rows = dimensions.rows;
     run.args.odd_cols = dimensions.cols;
     run.args.offset = dimensions.offset;
+    run.args.seed = thread_rng().gen();
     let mut prev_flag: &str = "";
     let mut process_current = false;
-    for a in cmd.split_whitespace() {
+    for a in split_command(&cmd)? {
         if process_current {
             if let Err(msg) = set_arg(
                 &mut run,
@@ -359,23 +579,93 @@ pub fn set_command_args(cmd: String, tui: &mut tui::Tui) -> Result<tables::Histo
     if run.args.style == maze::MazeStyle::Mini {
         run.args.odd_rows *= 2;
     }
+    if let Some(loaded) = &run.load {
+        check_fit(loaded, &dimensions)?;
+    }
+    if let Some(replay) = &run.replay {
+        check_fit(&replay.maze, &dimensions)?;
+    }
     Ok(run)
 }
 
-fn set_arg(run: &mut tables::HistoryRunner, args: &tables::FlagArg) -> Result<(), String> {
+// Mazes from files were sized for someone else's terminal so make sure they fit in this one.
+fn check_fit(loaded: &maze::Blueprint, dimensions: &tui::Dimension) -> Result<(), String> {
+    let max_rows = if loaded.is_mini() {
+        dimensions.rows * 2
+    } else {
+        dimensions.rows
+    };
+    if loaded.rows > max_rows + 1 - (max_rows % 2)
+        || loaded.cols > dimensions.cols + 1 - (dimensions.cols % 2)
+    {
+        return Err(format!(
+            "maze is {}x{} but only {}x{} fits.\nzoom out or enlarge the terminal\npress any key to continue",
+            loaded.rows, loaded.cols, max_rows, dimensions.cols
+        ));
+    }
+    Ok(())
+}
+
+pub fn set_arg(run: &mut tables::HistoryRunner, args: &tables::FlagArg) -> Result<(), String> {
     match args.flag {
-        "-b" => tables::search_table(args.arg, &tables::HISTORY_BUILDERS)
-            .map(|func_pair| run.build = func_pair)
-            .ok_or(err_string(args)),
-        "-m" => tables::search_table(args.arg, &tables::HISTORY_MODIFICATIONS)
-            .map(|mod_tuple| run.modify = Some(mod_tuple))
-            .ok_or(err_string(args)),
+        "-b" => {
+            let (name, arg) = split_algorithm_arg(args)?;
+            run.build =
+                tables::search_table(name, &tables::HISTORY_BUILDERS).ok_or(err_string(args))?;
+            check_algorithm_arg(args, &run.build, arg)?;
+            run.build_arg = arg.map(String::from);
+            Ok(())
+        }
+        "-m" => {
+            let (name, arg) = split_algorithm_arg(args)?;
+            let modify = tables::search_table(name, &tables::HISTORY_MODIFICATIONS)
+                .ok_or(err_string(args))?;
+            check_algorithm_arg(args, &modify, arg)?;
+            run.modify = Some(modify);
+            run.modify_arg = arg.map(String::from);
+            Ok(())
+        }
         "-s" => tables::search_table(args.arg, &tables::HISTORY_SOLVERS)
             .map(|solve_tuple| run.solve = solve_tuple)
             .ok_or(err_string(args)),
         "-w" => tables::search_table(args.arg, &tables::WALL_STYLES)
             .map(|wall_style| run.args.style = wall_style)
             .ok_or(err_string(args)),
+        "-ba" => parse_speed(args, &build::BUILDER_SPEEDS).map(|tick| run.build_speed = Some(tick)),
+        "-sa" => parse_speed(args, &tables::solve::SOLVER_SPEEDS)
+            .map(|tick| run.solve_speed = Some(tick)),
+        "-seed" => args
+            .arg
+            .parse::<maze::Seed>()
+            .map(|seed| run.args.seed = seed)
+            .map_err(|_| err_string(args)),
+        "-save" => {
+            run.save = Some(std::path::PathBuf::from(args.arg));
+            Ok(())
+        }
+        "-load" => maze::save::load(std::path::Path::new(args.arg))
+            .map(|blueprint| run.load = Some(blueprint))
+            .map_err(|e| e.to_string()),
+        "-record" => {
+            run.record = Some(std::path::PathBuf::from(args.arg));
+            Ok(())
+        }
+        "-replay" => maze::save::load_history(std::path::Path::new(args.arg))
+            .map(|replay| run.replay = Some(replay))
+            .map_err(|e| e.to_string()),
+        "-svg" => {
+            run.svg = Some(std::path::PathBuf::from(args.arg));
+            Ok(())
+        }
+        "-mask" => maze::mask::load(std::path::Path::new(args.arg))
+            .map(|mask| run.mask = Some(mask))
+            .map_err(|e| e.to_string()),
+        "-topology" => tables::search_table(args.arg, &tables::TOPOLOGIES)
+            .map(|topology| run.args.topology = topology)
+            .ok_or(err_string(args)),
+        "-text" => tables::text::check_message(args.arg)
+            .map(|_| run.text = Some(String::from(args.arg)))
+            .map_err(|msg| format!("{}: {}", err_string(args), msg)),
         _ => Err(err_string(args)),
     }
 }
@@ -389,6 +679,7 @@ fn set_random_args(rect: &Rect) -> tables::HistoryRunner {
         add_rows: rect.y as i32,
         add_cols: rect.x as i32,
     };
+    this_run.args.seed = rng.gen();
     let modification_probability = Bernoulli::new(0.2);
     this_run.args.style = match tables::WALL_STYLES.choose(&mut rng) {
         Some(&style) => style.1,
@@ -418,6 +709,60 @@ fn set_random_args(rect: &Rect) -> tables::HistoryRunner {
     this_run
 }
 
+// Arguments with spaces, such as a -text message, may be wrapped in double quotes.
+fn split_command(cmd: &str) -> Result<Vec<&str>, String> {
+    let mut words = Vec::new();
+    let mut rest = cmd.trim_start();
+    while !rest.is_empty() {
+        let (word, remaining) = match rest.strip_prefix('"') {
+            Some(quoted) => match quoted.split_once('"') {
+                Some(split) => split,
+                None => {
+                    return Err(format!(
+                        "missing closing quote for [{}]\npress any key to continue",
+                        rest
+                    ))
+                }
+            },
+            None => rest.split_at(rest.find(char::is_whitespace).unwrap_or(rest.len())),
+        };
+        words.push(word);
+        rest = remaining.trim_start();
+    }
+    Ok(words)
+}
+
+// Builders and modifications may take an argument after a colon as in `growing-tree:newest=0.5`.
+fn split_algorithm_arg<'a>(
+    args: &tables::FlagArg<'_, 'a>,
+) -> Result<(&'a str, Option<&'a str>), String> {
+    match args.arg.split_once(':') {
+        Some((_, "")) => Err(err_string(args)),
+        Some((name, arg)) => Ok((name, Some(arg))),
+        None => Ok((args.arg, None)),
+    }
+}
+
+fn check_algorithm_arg(
+    args: &tables::FlagArg,
+    func: &tables::BuildHistoryFunction,
+    arg: Option<&str>,
+) -> Result<(), String> {
+    match arg {
+        Some(arg) => {
+            tables::check_arg(func, arg).map_err(|msg| format!("{}: {}", err_string(args), msg))
+        }
+        None => Ok(()),
+    }
+}
+
+fn parse_speed(args: &tables::FlagArg, speeds: &[u64]) -> Result<Duration, String> {
+    match args.arg.parse::<usize>() {
+        Ok(level) if (1..speeds.len()).contains(&level) => Ok(tui::speed_tick(speeds, level)),
+        _ => Err(err_string(args)),
+    }
+}
+
 fn err_string(args: &tables::FlagArg) -> String {
     format!("invalid flag[{}] arg[{}] combo", args.flag, args.arg)
 }
@@ -430,12 +775,63 @@ fn get_arg_section(flag: &str) -> &'static str {
         .1
 }
 
-///
-/// History function wrappers to help simplify what the runner is responsible for with playback.
-///
+//
+// History function wrappers to help simplify what the runner is responsible for with playback.
+//
 
 // A step just progresses the Tape based on whatever the current direction state is.
 impl Playback {
+    // The timeline is the build history followed by the solve history. A finished solve counts
+    // its last burst so the end of the timeline is reachable.
+    fn position(&self, process: tui::Process) -> usize {
+        match process {
+            tui::Process::Building => self.build_tape.pos(),
+            tui::Process::Solving => {
+                let solve = match self.solve_tape.at_end() {
+                    true => self.solve_tape.len(),
+                    false => self.solve_tape.pos(),
+                };
+                self.build_tape.len() + solve
+            }
+        }
+    }
+
+    fn tenth(&self) -> usize {
+        cmp::max((self.build_tape.len() + self.solve_tape.len()) / 10, 1)
+    }
+
+    // Landing in the build takes back the whole solve first and landing in the solve finishes
+    // the build first, so the squares always match one point of the combined history. Jumps
+    // pause so the frame landed on stays up.
+    fn seek(&mut self, process: tui::Process, target: usize) {
+        let build_len = self.build_tape.len();
+        let landed = match target < build_len {
+            true => {
+                self.solve_tape.seek(&mut self.maze, 0);
+                self.build_tape.seek(&mut self.maze, target);
+                tui::Process::Building
+            }
+            false => {
+                self.build_tape.seek(&mut self.maze, build_len);
+                self.solve_tape.seek(&mut self.maze, target - build_len);
+                tui::Process::Solving
+            }
+        };
+        self.pause = true;
+        if landed != process {
+            self.crossed = Some(landed);
+        }
+    }
+
+    fn timeline(&self, process: tui::Process) -> tui::Timeline<'_> {
+        tui::Timeline {
+            at: self.position(process),
+            solve_from: self.build_tape.len(),
+            len: self.build_tape.len() + self.solve_tape.len(),
+            bookmarks: &self.bookmarks,
+        }
+    }
+
     fn build_step(&mut self) -> bool {
         if let Some(history) = self.build_tape.cur_step() {
             if self.forward {
@@ -486,6 +882,37 @@ impl Playback {
         true
     }
 
+    // The edits join the end of the build history so reversing into the build undoes them, and
+    // the chosen solver records a fresh solve of the edited maze to play from the start.
+    fn solve_edited(&mut self, edited: edit::Edited) {
+        let monitor = monitor::Monitor::new(edited.board);
+        (edited.solve)(monitor.clone());
+        let mut solved = match Arc::into_inner(monitor) {
+            Some(a) => match Mutex::into_inner(a) {
+                Ok(solver) => solver.maze,
+                Err(_) => print::maze_panic!("rendering cannot progress without lock"),
+            },
+            None => print::maze_panic!("rendering cannot progress without lock"),
+        };
+        solvers::solve::reset_solve(&mut solved);
+        // Marks in the old solve no longer point anywhere once the solve is recorded again.
+        let build_len = self.build_tape.len();
+        self.bookmarks.retain(|&m| m < build_len);
+        let mut i = 0;
+        while i < edited.edits.len() {
+            let len = edited.edits[i].burst.max(1);
+            self.build_tape.push_burst(edited.edits.slice(i, i + len));
+            i += len;
+        }
+        if !self.build_tape.is_empty() {
+            self.build_tape.end();
+        }
+        self.maze = solved.maze;
+        self.solve_tape = solved.solve_history;
+        self.forward = true;
+        self.pause = false;
+    }
+
     fn solve_delta(&mut self) -> bool {
         if self.pause {
             return true;
@@ -3,12 +3,13 @@ use crossbeam_channel::{self, unbounded};
 use crossterm::event::{self, DisableMouseCapture, EnableMouseCapture, KeyCode, KeyEvent};
 use crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen};
 use ratatui::{
-    buffer::Buffer,
+    buffer::{Buffer, Cell},
     layout::{Constraint, Direction, Layout},
     prelude::{Alignment, Color, Modifier, Rect},
     style::Style,
     symbols::border::Set,
     widgets::{
+        block::{Position, Title},
         Block, BorderType, Borders, Clear, Paragraph, ScrollDirection, Scrollbar,
         ScrollbarOrientation, ScrollbarState, Widget, Wrap,
     },
@@ -29,10 +30,10 @@ pub type Err = Box<dyn std::error::Error>;
 pub type Result<T> = std::result::Result<T, Err>;
 
 static INSTRUCTIONS: &str = include_str!("../../res/instructions.txt");
-static INSTRUCTIONS_LINE_COUNT: usize = 70;
+static INSTRUCTIONS_LINE_COUNT: usize = 155;
 static DESCRIPTION_LINE_COUNT: usize = 50;
 static POPUP_INSTRUCTIONS: &str =
-    "[i]info [ESC]exit [SPACE]play/pause\n[←/→]backstep/nextstep [</>]slower/faster";
+    "[i]info [e]edit [p]walk [r]race [g]ghost [ESC]exit\n[SPACE]play/pause [←/→]step [</>]slower/faster";
 const RED_PAUSE: Color = Color::Rgb(201, 77, 83);
 const GREEN_FORWARD: Color = Color::Rgb(77, 201, 81);
 const BLUE_REVERSE: Color = Color::Rgb(42, 111, 222);
@@ -59,8 +60,15 @@ static REVERSE_INDICICATOR: Set = Set {
 const MAX_DURATION: Duration = Duration::from_secs(5);
 const MIN_DURATION: Duration = Duration::from_millis(1);
 const MIN_POLL: Duration = Duration::from_millis(1);
+// Speed levels count from 1 for the slowest tick up to the last entry of a speed table. Every
+// playback starts at the default level unless a flag picks another.
+pub const DEFAULT_SPEED: usize = 4;
 
-#[derive(Copy, Clone)]
+pub fn speed_tick(speeds: &[u64], level: usize) -> Duration {
+    Duration::from_micros(speeds[level])
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Process {
     Building,
     Solving,
@@ -77,6 +85,7 @@ pub enum Pack {
 #[derive(Debug)]
 pub struct EventHandler {
     pub receiver: crossbeam_channel::Receiver<Pack>,
+    rates: crossbeam_channel::Sender<Duration>,
 }
 
 pub struct Tui<'a> {
@@ -107,6 +116,20 @@ pub struct SolveFrame<'a> {
     pub maze: &'a maze::Blueprint,
 }
 
+// Where playback sits along the build history followed by the solve history, counted in deltas,
+// and the positions marked to come back to.
+pub struct Timeline<'a> {
+    pub at: usize,
+    pub solve_from: usize,
+    pub len: usize,
+    pub bookmarks: &'a [usize],
+}
+
+pub struct EditFrame<'a> {
+    pub maze: &'a maze::Blueprint,
+    pub cursor: maze::Point,
+}
+
 impl<'a> Tui<'a> {
     pub fn new(terminal: CrosstermTerminal, events: EventHandler) -> Self {
         let mut cmd_prompt = TextArea::default();
@@ -424,48 +447,45 @@ impl<'a> Tui<'a> {
         &mut self,
         frame: impl Widget,
         rect: &Rc<[Rect]>,
+        seed: maze::Seed,
         forward: bool,
         pause: bool,
+        timeline: &Timeline,
     ) -> Result<()> {
-        let popup_layout_v = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Percentage((100 - 17) / 2),
-                Constraint::Min(4),
-                Constraint::Percentage((100 - 17) / 2),
-            ])
-            .split(rect[1]);
-        let popup_layout_h = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Percentage((100 - 50) / 2),
-                Constraint::Percentage(50),
-                Constraint::Percentage((100 - 50) / 2),
-            ])
-            .split(popup_layout_v[1])[1];
+        let popup_layout_h = status_area(rect[1]);
+        let indicator = match (pause, forward) {
+            (true, true) => Block::default()
+                .borders(Borders::ALL)
+                .border_set(FORWARD_INDICICATOR)
+                .border_style(Style::new().fg(RED_PAUSE))
+                .style(Style::default().bg(Color::Black)),
+            (true, false) => Block::default()
+                .borders(Borders::ALL)
+                .border_set(REVERSE_INDICICATOR)
+                .border_style(Style::new().fg(RED_PAUSE))
+                .style(Style::default().bg(Color::Black)),
+            (false, true) => Block::default()
+                .borders(Borders::ALL)
+                .border_set(FORWARD_INDICICATOR)
+                .border_style(Style::new().fg(GREEN_FORWARD))
+                .style(Style::default().bg(Color::Black)),
+            (false, false) => Block::default()
+                .borders(Borders::ALL)
+                .border_set(REVERSE_INDICICATOR)
+                .border_style(Style::new().fg(BLUE_REVERSE))
+                .style(Style::default().bg(Color::Black)),
+        };
+        // Showing the seed lets anyone reproduce this exact maze with the -seed flag.
         let popup_instructions = Paragraph::new(POPUP_INSTRUCTIONS)
-            .block(match (pause, forward) {
-                (true, true) => Block::default()
-                    .borders(Borders::ALL)
-                    .border_set(FORWARD_INDICICATOR)
-                    .border_style(Style::new().fg(RED_PAUSE))
-                    .style(Style::default().bg(Color::Black)),
-                (true, false) => Block::default()
-                    .borders(Borders::ALL)
-                    .border_set(REVERSE_INDICICATOR)
-                    .border_style(Style::new().fg(RED_PAUSE))
-                    .style(Style::default().bg(Color::Black)),
-                (false, true) => Block::default()
-                    .borders(Borders::ALL)
-                    .border_set(FORWARD_INDICICATOR)
-                    .border_style(Style::new().fg(GREEN_FORWARD))
-                    .style(Style::default().bg(Color::Black)),
-                (false, false) => Block::default()
-                    .borders(Borders::ALL)
-                    .border_set(REVERSE_INDICICATOR)
-                    .border_style(Style::new().fg(BLUE_REVERSE))
-                    .style(Style::default().bg(Color::Black)),
-            })
+            .block(
+                indicator
+                    .title(format!("seed {}", seed))
+                    .title(
+                        Title::from(timeline.bar(popup_layout_h.width.saturating_sub(10) as usize))
+                            .position(Position::Bottom),
+                    )
+                    .title_alignment(Alignment::Center),
+            )
             .alignment(Alignment::Center);
         self.terminal.draw(|f| {
             f.render_widget(frame, rect[0]);
@@ -473,6 +493,88 @@ impl<'a> Tui<'a> {
         })?;
         Ok(())
     }
+
+    // Games replace the playback controls under the maze with their own status.
+    pub fn render_game_frame(
+        &mut self,
+        frame: impl Widget,
+        rect: &Rc<[Rect]>,
+        title: &str,
+        msg: &str,
+    ) -> Result<()> {
+        let popup_layout_h = status_area(rect[1]);
+        let status = Paragraph::new(msg.to_owned())
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Double)
+                    .border_style(Style::new().fg(Color::Yellow))
+                    .style(Style::default().bg(Color::Black))
+                    .title(title.to_owned())
+                    .title_alignment(Alignment::Center),
+            )
+            .alignment(Alignment::Center);
+        self.terminal.draw(|f| {
+            f.render_widget(frame, rect[0]);
+            f.render_widget(Clear, popup_layout_h);
+            f.render_widget(status, popup_layout_h);
+        })?;
+        Ok(())
+    }
+}
+
+// The box under the maze that holds the playback controls or the status of a game.
+fn status_area(below_maze: Rect) -> Rect {
+    let popup_layout_v = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - 17) / 2),
+            Constraint::Min(4),
+            Constraint::Percentage((100 - 17) / 2),
+        ])
+        .split(below_maze);
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - 50) / 2),
+            Constraint::Percentage(50),
+            Constraint::Percentage((100 - 50) / 2),
+        ])
+        .split(popup_layout_v[1])[1]
+}
+
+impl<'a> Timeline<'a> {
+    // The bar is drawn along the bottom border of the playback controls. The build and solve
+    // meet at the tick and bookmarks show as diamonds ahead of or behind the head.
+    fn bar(&self, width: usize) -> String {
+        if width == 0 || self.len == 0 {
+            return String::new();
+        }
+        let column = |p: usize| cmp::min(p * width / self.len, width - 1);
+        let head = column(self.at);
+        let split = column(self.solve_from);
+        let mut bar: Vec<char> = (0..width)
+            .map(|c| match c.cmp(&head) {
+                cmp::Ordering::Less => '━',
+                cmp::Ordering::Equal => '●',
+                cmp::Ordering::Greater => '─',
+            })
+            .collect();
+        if split != head && self.solve_from > 0 && self.solve_from < self.len {
+            bar[split] = '┼';
+        }
+        for &mark in self.bookmarks {
+            let c = column(mark);
+            if c != head {
+                bar[c] = '◆';
+            }
+        }
+        format!(
+            "{}{:>4}%",
+            bar.into_iter().collect::<String>(),
+            cmp::min(self.at, self.len) * 100 / self.len
+        )
+    }
 }
 
 impl EventHandler {
@@ -480,35 +582,34 @@ impl EventHandler {
     pub fn new(delta_rate: f64) -> Self {
         let mut deltas = Duration::from_secs_f64(1.0 / delta_rate);
         let (sender, receiver) = unbounded();
+        let (rates, new_rates) = unbounded();
         let sender = sender.clone();
         thread::spawn(move || {
             let mut last_delta = Instant::now();
             loop {
+                while let Ok(rate) = new_rates.try_recv() {
+                    deltas = rate;
+                }
                 // If we poll at the min acceptable duration always then when the user speeds
                 // up or slows down the deltas for the maze rendering speed we still have a
                 // responsive UI not tied to rendering speed and we have a CPU utilization cap.
                 if event::poll(MIN_POLL).expect("no events available") {
                     match event::read().expect("unable to read event") {
-                        CtEvent::Key(e) => {
-                            if e.kind == event::KeyEventKind::Press {
-                                match e.code {
-                                    KeyCode::Char('>') => {
-                                        deltas = match deltas.checked_div(2) {
-                                            Some(t) => t,
-                                            None => MIN_DURATION,
-                                        };
-                                        deltas = std::cmp::max(deltas, MIN_DURATION);
-                                    }
-                                    KeyCode::Char('<') => {
-                                        deltas =
-                                            std::cmp::min(deltas.saturating_mul(2), MAX_DURATION);
-                                    }
-                                    _ => {
-                                        sender.send(Pack::Press(e)).expect("couldn't send press.");
-                                    }
-                                }
+                        CtEvent::Key(e) if e.kind == event::KeyEventKind::Press => match e.code {
+                            KeyCode::Char('>') => {
+                                deltas = match deltas.checked_div(2) {
+                                    Some(t) => t,
+                                    None => MIN_DURATION,
+                                };
+                                deltas = std::cmp::max(deltas, MIN_DURATION);
                             }
-                        }
+                            KeyCode::Char('<') => {
+                                deltas = std::cmp::min(deltas.saturating_mul(2), MAX_DURATION);
+                            }
+                            _ => {
+                                sender.send(Pack::Press(e)).expect("couldn't send press.");
+                            }
+                        },
                         CtEvent::Resize(_, _) => {
                             sender
                                 .send(Pack::Resize((), ()))
@@ -522,7 +623,14 @@ impl EventHandler {
                 }
             }
         });
-        Self { receiver }
+        Self { receiver, rates }
+    }
+
+    // Replaces the time between render ticks the same way [</>] would, within the same limits.
+    pub fn set_rate(&self, deltas: Duration) {
+        self.rates
+            .send(deltas.clamp(MIN_DURATION, MAX_DURATION))
+            .expect("could not send rate.");
     }
 
     pub fn next(&self) -> Option<Pack> {
@@ -530,76 +638,203 @@ impl EventHandler {
     }
 }
 
-///
-/// Supporting implementations
-///
+//
+// Supporting implementations
+//
 
 impl<'a> Widget for BuildFrame<'a> {
     fn render(self, _area: Rect, buf: &mut Buffer) {
-        if self.maze.is_mini() {
-            let buf_area = buf.area;
-            let row_len = cmp::min(buf_area.height, (self.maze.rows / 2) as u16);
-            let col_len = cmp::min(buf_area.width, self.maze.cols as u16);
-            for y in 0..row_len * 2 + 1 {
-                for x in 0..col_len {
-                    *buf.get_mut(x, y / 2) = build::decode_mini_square(
-                        self.maze,
-                        maze::Point {
-                            row: y as i32,
-                            col: x as i32,
-                        },
-                    );
-                }
-            }
+        if self.maze.topology == maze::topology::Topology::Polar {
+            render_polar(self.maze, buf, build::decode_square, |square| {
+                build::is_marked(square)
+            });
+        } else if self.maze.is_mini() {
+            render_mini(self.maze, buf, build::decode_mini_square);
         } else {
-            let buf_area = buf.area;
-            let row_len = cmp::min(buf_area.height, self.maze.rows as u16);
-            let col_len = cmp::min(buf_area.width, self.maze.cols as u16);
-            let wall_row = &maze::wall_row(self.maze.wall_style_index);
-            let cols = col_len as usize;
-            for y in 0..row_len {
-                for x in 0..col_len {
-                    *buf.get_mut(x, y) = build::decode_square(
-                        wall_row,
-                        self.maze.buf[y as usize * cols + x as usize],
-                    );
-                }
-            }
+            render_squares(self.maze, buf, build::decode_square);
         }
     }
 }
 
 impl<'a> Widget for SolveFrame<'a> {
     fn render(self, _area: Rect, buf: &mut Buffer) {
-        if self.maze.is_mini() {
-            let buf_area = buf.area;
-            let row_len = cmp::min(buf_area.height, (self.maze.rows / 2) as u16);
-            let col_len = cmp::min(buf_area.width, self.maze.cols as u16);
-            for y in 0..row_len * 2 + 1 {
-                for x in 0..col_len {
-                    *buf.get_mut(x, y / 2) = solve::decode_mini_path(
-                        self.maze,
-                        maze::Point {
-                            row: y as i32,
-                            col: x as i32,
-                        },
+        if self.maze.topology == maze::topology::Topology::Polar {
+            render_polar(self.maze, buf, solve::decode_square, |square| {
+                solve::is_start(square) || solve::is_finish(square)
+            });
+        } else if self.maze.is_mini() {
+            render_mini(self.maze, buf, solve::decode_mini_path);
+        } else {
+            render_squares(self.maze, buf, solve::decode_square);
+        }
+    }
+}
+
+// The editor works on the squares themselves so even a polar maze is shown unrolled, one ring
+// per row, and the cursor is the highlighted character over the square it sits on.
+impl<'a> Widget for EditFrame<'a> {
+    fn render(self, _area: Rect, buf: &mut Buffer) {
+        let y = match self.maze.is_mini() {
+            true => {
+                render_mini(self.maze, buf, solve::decode_mini_path);
+                self.cursor.row / 2
+            }
+            false => {
+                render_squares(self.maze, buf, solve::decode_square);
+                self.cursor.row
+            }
+        };
+        let (x, y) = (self.cursor.col as u16, y as u16);
+        if x < buf.area.width && y < buf.area.height {
+            buf.get_mut(x, y).set_bg(Color::Yellow);
+        }
+    }
+}
+
+fn render_squares(
+    maze: &maze::Blueprint,
+    buf: &mut Buffer,
+    decode: fn(&[char], maze::Square) -> Cell,
+) {
+    let buf_area = buf.area;
+    let row_len = cmp::min(buf_area.height, maze.rows as u16);
+    let col_len = cmp::min(buf_area.width, maze.cols as u16);
+    let wall_row = &maze::wall_row(maze.wall_style_index);
+    let cols = col_len as usize;
+    for y in 0..row_len {
+        for x in 0..col_len {
+            let mut cell = decode(wall_row, maze.buf[y as usize * cols + x as usize]);
+            if let Some(slant) = maze.slant_at(y as i32, x as i32) {
+                cell.set_char(slant);
+            }
+            *buf.get_mut(x, y) = cell;
+        }
+    }
+}
+
+// Mini mazes pack two rows of squares into every character.
+fn render_mini(
+    maze: &maze::Blueprint,
+    buf: &mut Buffer,
+    decode: fn(&maze::Blueprint, maze::Point) -> Cell,
+) {
+    let buf_area = buf.area;
+    let row_len = cmp::min(buf_area.height, (maze.rows / 2) as u16);
+    let col_len = cmp::min(buf_area.width, maze.cols as u16);
+    for y in 0..row_len * 2 + 1 {
+        for x in 0..col_len {
+            *buf.get_mut(x, y / 2) = decode(
+                maze,
+                maze::Point {
+                    row: y as i32,
+                    col: x as i32,
+                },
+            );
+        }
+    }
+}
+
+// A polar maze is drawn as a circle of braille dots, two across and four down in every character
+// so that a dot is about as tall as it is wide. Walls come from the same wall squares the builders
+// carve and a character takes its color from the square of the ring under its middle. Squares
+// that must never be missed, like the start and finish, are pinned over the dots where they sit.
+fn render_polar(
+    maze: &maze::Blueprint,
+    buf: &mut Buffer,
+    decode: fn(&[char], maze::Square) -> Cell,
+    pinned: impl Fn(maze::Square) -> bool,
+) {
+    const DOTS: [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+    let rings = maze::topology::polar_rings(maze.rows, maze.cols);
+    let wall_row = &maze::wall_row(maze.wall_style_index);
+    let height = cmp::min(buf.area.height, maze.rows as u16);
+    let width = cmp::min(buf.area.width, maze.cols as u16);
+    let (center_x, center_y) = (width as f64, height as f64 * 2.0);
+    let radius = (center_x.min(center_y) - 1.0).max(1.0);
+    let thickness = radius / rings.counts.len() as f64;
+    // Rings and turns clockwise from the top in dots, the way the svg export lays them out.
+    let polar = |x: f64, y: f64| {
+        let (dx, dy) = (x - center_x, y - center_y);
+        let turn = dx.atan2(-dy) / std::f64::consts::TAU;
+        ((dx * dx + dy * dy).sqrt(), turn.rem_euclid(1.0))
+    };
+    let is_wall = |r: f64, turn: f64| {
+        if r > radius + 0.5 {
+            return false;
+        }
+        let boundary = (r / thickness).round();
+        if (r - boundary * thickness).abs() < 0.5 && boundary >= 1.0 {
+            let ring = boundary as usize;
+            if ring >= rings.counts.len() {
+                return true;
+            }
+            let cell = rings.cell_at(ring, turn);
+            if !maze.path_at(cell.row - 1, cell.col) {
+                return true;
+            }
+        }
+        let ring = ((r / thickness) as usize).min(rings.counts.len() - 1);
+        let n = rings.counts[ring] as f64;
+        let nearest = (turn * n).round();
+        if n < 2.0 || ((turn * n - nearest) / n * std::f64::consts::TAU * r).abs() >= 0.5 {
+            return false;
+        }
+        let edge = (nearest as i32 % rings.counts[ring]) * rings.span(ring);
+        !maze.path_at(ring as i32 * 2 + 1, edge)
+    };
+    let square_under = |x: f64, y: f64| {
+        let (r, turn) = polar(x, y);
+        if r > radius {
+            return None;
+        }
+        let ring = ((r / thickness) as i32).min(rings.counts.len() as i32 - 1);
+        Some(maze.get(ring * 2 + 1, (turn * rings.width as f64) as i32))
+    };
+    for y in 0..height {
+        for x in 0..width {
+            let mut dots = 0;
+            for (dx, column) in DOTS.iter().enumerate() {
+                for (dy, bit) in column.iter().enumerate() {
+                    let (r, turn) = polar(
+                        x as f64 * 2.0 + dx as f64 + 0.5,
+                        y as f64 * 4.0 + dy as f64 + 0.5,
                     );
+                    if is_wall(r, turn) {
+                        dots |= bit;
+                    }
                 }
             }
-        } else {
-            let buf_area = buf.area;
-            let row_len = cmp::min(buf_area.height, self.maze.rows as u16);
-            let col_len = cmp::min(buf_area.width, self.maze.cols as u16);
-            let wall_row = &maze::wall_row(self.maze.wall_style_index);
-            let cols = col_len as usize;
-            for y in 0..row_len {
-                for x in 0..col_len {
-                    *buf.get_mut(x, y) = solve::decode_square(
-                        wall_row,
-                        self.maze.buf[y as usize * cols + x as usize],
-                    );
+            let cell = buf.get_mut(x, y);
+            cell.reset();
+            if let Some(square) = square_under(x as f64 * 2.0 + 1.0, y as f64 * 4.0 + 2.0) {
+                let under = decode(wall_row, square);
+                if maze::is_path(square) {
+                    cell.set_bg(match under.symbol.as_str() {
+                        "█" => under.fg,
+                        _ => under.bg,
+                    });
                 }
             }
+            cell.set_char(char::from_u32(0x2800 + dots).unwrap_or(' '));
+        }
+    }
+    for ring in 0..rings.counts.len() {
+        let row = ring as i32 * 2 + 1;
+        for col in 0..rings.width {
+            let square = maze.get(row, col);
+            if !pinned(square) {
+                continue;
+            }
+            let (r, turn) = (
+                (ring as f64 + 0.5) * thickness,
+                (col as f64 + 0.5) / rings.width as f64,
+            );
+            let angle = turn * std::f64::consts::TAU;
+            let x = (center_x + r * angle.sin()) / 2.0;
+            let y = (center_y - r * angle.cos()) / 4.0;
+            if x >= 0.0 && y >= 0.0 && (x as u16) < width && (y as u16) < height {
+                *buf.get_mut(x as u16, y as u16) = decode(wall_row, square);
+            }
         }
     }
 }
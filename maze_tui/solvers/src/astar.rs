@@ -0,0 +1,26 @@
+use crate::best_first;
+
+//
+// A* orders its open set by path cost plus the Manhattan distance to the nearest finish. The
+// estimate never overshoots on a grid so the first finish a thread settles is a shortest path.
+//
+
+fn manhattan(p: maze::Point, finishes: &[maze::Point]) -> i32 {
+    finishes
+        .iter()
+        .map(|f| (f.row - p.row).abs() + (f.col - p.col).abs())
+        .min()
+        .unwrap_or(0)
+}
+
+pub fn hunt_history(monitor: monitor::MazeMonitor) {
+    best_first::hunt_history(monitor, manhattan);
+}
+
+pub fn gather_history(monitor: monitor::MazeMonitor) {
+    best_first::gather_history(monitor, manhattan);
+}
+
+pub fn corner_history(monitor: monitor::MazeMonitor) {
+    best_first::corner_history(monitor, manhattan);
+}
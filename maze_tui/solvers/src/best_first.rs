@@ -0,0 +1,456 @@
+use crate::solve;
+
+use rand::prelude::*;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::thread;
+
+const BURST: usize = 4;
+// Squares waiting in the open set are drawn at half brightness so the frontier stands out from
+// the squares a thread has already settled.
+const FRONTIER_DIM: solve::ThreadPaint = 0x7F7F7F;
+
+//
+// Dijkstra and A* only differ in the estimate they add to the cost of a square so both share this
+// search. Every path step costs one, the estimate is the only thing that changes the exploration.
+//
+
+// Estimates the remaining distance from a square to the nearest of the finishes.
+pub type Heuristic = fn(maze::Point, &[maze::Point]) -> i32;
+
+// The open set orders by estimated total, then prefers squares closer to a finish to break ties.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Node {
+    total: i32,
+    estimate: i32,
+    cost: i32,
+    row: i32,
+    col: i32,
+}
+
+// Each thread runs its own search and only shares the maze, not what it has discovered.
+struct Search<'a> {
+    parents: HashMap<maze::Point, maze::Point>,
+    costs: HashMap<maze::Point, i32>,
+    open: BinaryHeap<Reverse<Node>>,
+    finishes: &'a [maze::Point],
+    heuristic: Heuristic,
+}
+
+#[inline]
+fn frontier_paint(paint: solve::ThreadPaint) -> solve::ThreadPaint {
+    (paint >> 1) & FRONTIER_DIM
+}
+
+pub fn hunt_history(monitor: monitor::MazeMonitor, heuristic: Heuristic) {
+    let (all_start, finish) = if let Ok(mut lk) = monitor.lock() {
+        let mut rng = solve::seeded_rng(&lk.maze);
+        let start = solve::pick_random_point(&lk.maze, &mut rng);
+        let start_square = lk.maze.get(start.row, start.col);
+        lk.maze.solve_history.push(maze::Delta {
+            id: start,
+            before: start_square,
+            after: start_square | solve::START_BIT,
+            burst: BURST,
+        });
+        *lk.maze.get_mut(start.row, start.col) |= solve::START_BIT;
+        let finish: maze::Point = solve::pick_random_point(&lk.maze, &mut rng);
+        let finish_square = lk.maze.get(finish.row, finish.col);
+        lk.maze.solve_history.push(maze::Delta {
+            id: finish,
+            before: finish_square,
+            after: finish_square | solve::FINISH_BIT,
+            burst: BURST,
+        });
+        *lk.maze.get_mut(finish.row, finish.col) |= solve::FINISH_BIT;
+        (start, finish)
+    } else {
+        print::maze_panic!("Thread panic.");
+    };
+
+    let mut handles = Vec::with_capacity(solve::NUM_THREADS - 1);
+    for (i_thread, &mask) in solve::THREAD_MASKS.iter().skip(1).enumerate() {
+        let monitor_clone = monitor.clone();
+        handles.push(thread::spawn(move || {
+            hunter_history(
+                monitor_clone,
+                solve::ThreadGuide {
+                    index: i_thread + 1,
+                    paint: mask,
+                    cache: solve::THREAD_CACHES[i_thread + 1],
+                    start: all_start,
+                    speed: 0,
+                },
+                &[finish],
+                heuristic,
+            );
+        }));
+    }
+    hunter_history(
+        monitor.clone(),
+        solve::ThreadGuide {
+            index: 0,
+            paint: solve::THREAD_MASKS[0],
+            cache: solve::THREAD_CACHES[0],
+            start: all_start,
+            speed: 0,
+        },
+        &[finish],
+        heuristic,
+    );
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    paint_win_paths(&monitor);
+}
+
+pub fn corner_history(monitor: monitor::MazeMonitor, heuristic: Heuristic) {
+    let (all_starts, finish) = if let Ok(mut lk) = monitor.lock() {
+        let mut all_starts = solve::set_corner_starts(&lk.maze);
+        all_starts.shuffle(&mut solve::seeded_rng(&lk.maze));
+        for s in all_starts {
+            let start_square = lk.maze.get(s.row, s.col);
+            lk.maze.solve_history.push(maze::Delta {
+                id: s,
+                before: start_square,
+                after: start_square | solve::START_BIT,
+                burst: BURST,
+            });
+            *lk.maze.get_mut(s.row, s.col) |= solve::START_BIT;
+        }
+        let finish = solve::set_corner_finish_history(&mut lk.maze, BURST);
+        (all_starts, finish)
+    } else {
+        print::maze_panic!("Thread panick.");
+    };
+
+    let mut handles = Vec::with_capacity(solve::NUM_THREADS - 1);
+    for (i_thread, &mask) in solve::THREAD_MASKS.iter().skip(1).enumerate() {
+        let monitor_clone = monitor.clone();
+        handles.push(thread::spawn(move || {
+            hunter_history(
+                monitor_clone,
+                solve::ThreadGuide {
+                    index: i_thread + 1,
+                    paint: mask,
+                    cache: solve::THREAD_CACHES[i_thread + 1],
+                    start: all_starts[i_thread + 1],
+                    speed: 0,
+                },
+                &[finish],
+                heuristic,
+            );
+        }));
+    }
+    hunter_history(
+        monitor.clone(),
+        solve::ThreadGuide {
+            index: 0,
+            paint: solve::THREAD_MASKS[0],
+            cache: solve::THREAD_CACHES[0],
+            start: all_starts[0],
+            speed: 0,
+        },
+        &[finish],
+        heuristic,
+    );
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    paint_win_paths(&monitor);
+}
+
+pub fn gather_history(monitor: monitor::MazeMonitor, heuristic: Heuristic) {
+    let (all_start, finishes) = if let Ok(mut lk) = monitor.lock() {
+        let mut rng = solve::seeded_rng(&lk.maze);
+        let start = solve::pick_random_point(&lk.maze, &mut rng);
+        let start_square = lk.maze.get(start.row, start.col);
+        lk.maze.solve_history.push(maze::Delta {
+            id: start,
+            before: start_square,
+            after: start_square | solve::START_BIT,
+            burst: BURST,
+        });
+        *lk.maze.get_mut(start.row, start.col) |= solve::START_BIT;
+        let mut finishes = Vec::with_capacity(solve::NUM_GATHER_FINISHES);
+        for _ in 0..solve::NUM_GATHER_FINISHES {
+            let finish: maze::Point = solve::pick_random_point(&lk.maze, &mut rng);
+            let finish_square = lk.maze.get(finish.row, finish.col);
+            lk.maze.solve_history.push(maze::Delta {
+                id: finish,
+                before: finish_square,
+                after: finish_square | solve::FINISH_BIT,
+                burst: BURST,
+            });
+            *lk.maze.get_mut(finish.row, finish.col) |= solve::FINISH_BIT;
+            finishes.push(finish);
+        }
+        (start, finishes)
+    } else {
+        print::maze_panic!("Thread panick.");
+    };
+
+    let mut handles = Vec::with_capacity(solve::NUM_THREADS - 1);
+    for (i_thread, &mask) in solve::THREAD_MASKS.iter().skip(1).enumerate() {
+        let monitor_clone = monitor.clone();
+        let finishes_clone = finishes.clone();
+        handles.push(thread::spawn(move || {
+            gatherer_history(
+                monitor_clone,
+                solve::ThreadGuide {
+                    index: i_thread + 1,
+                    paint: mask,
+                    cache: solve::THREAD_CACHES[i_thread + 1],
+                    start: all_start,
+                    speed: 0,
+                },
+                &finishes_clone,
+                heuristic,
+            );
+        }));
+    }
+    gatherer_history(
+        monitor.clone(),
+        solve::ThreadGuide {
+            index: 0,
+            paint: solve::THREAD_MASKS[0],
+            cache: solve::THREAD_CACHES[0],
+            start: all_start,
+            speed: 0,
+        },
+        &finishes,
+        heuristic,
+    );
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    paint_win_paths(&monitor);
+}
+
+// The winning threads left their shortest paths in the monitor. Tidy the 4-bursts the racing
+// threads claimed so reversal lines up and then trace each path one square at a time.
+fn paint_win_paths(monitor: &monitor::MazeMonitor) {
+    if let Ok(mut lk) = monitor.lock() {
+        let len = lk.maze.solve_history.len();
+        if len % BURST != 0 {
+            lk.maze
+                .solve_history
+                .slice_mut(len - (len % BURST), len)
+                .iter_mut()
+                .for_each(|s| s.burst = 1);
+        }
+        for i in 0..lk.win_path.len() {
+            let p = lk.win_path[i];
+            let square = lk.maze.get(p.0.row, p.0.col);
+            lk.maze.solve_history.push(maze::Delta {
+                id: p.0,
+                before: square,
+                after: (square & !solve::THREAD_MASK) | p.1,
+                burst: 1,
+            });
+            *lk.maze.get_mut(p.0.row, p.0.col) = (square & !solve::THREAD_MASK) | p.1;
+        }
+        return;
+    }
+    print::maze_panic!("Thread panicked with the lock!");
+}
+
+fn hunter_history(
+    monitor: monitor::MazeMonitor,
+    guide: solve::ThreadGuide,
+    finishes: &[maze::Point],
+    heuristic: Heuristic,
+) {
+    let mut search = Search::new(guide.start, finishes, heuristic);
+    while let Some(node) = search.pop() {
+        let cur = maze::Point {
+            row: node.row,
+            col: node.col,
+        };
+        if let Ok(mut lk) = monitor.lock() {
+            if lk.win.is_some() {
+                return;
+            }
+            let square = lk.maze.get(cur.row, cur.col);
+            lk.maze.solve_history.push(maze::Delta {
+                id: cur,
+                before: square,
+                after: square | guide.paint,
+                burst: BURST,
+            });
+            *lk.maze.get_mut(cur.row, cur.col) |= guide.paint;
+            if solve::is_finish(square) {
+                lk.win.get_or_insert(guide.index);
+                search.trace_path(&mut lk.win_path, cur, guide.paint);
+                return;
+            }
+        } else {
+            print::maze_panic!("Thread panicked!");
+        }
+        search.push_neighbors(&monitor, &guide, &node);
+    }
+}
+
+fn gatherer_history(
+    monitor: monitor::MazeMonitor,
+    guide: solve::ThreadGuide,
+    finishes: &[maze::Point],
+    heuristic: Heuristic,
+) {
+    let mut search = Search::new(guide.start, finishes, heuristic);
+    while let Some(node) = search.pop() {
+        let cur = maze::Point {
+            row: node.row,
+            col: node.col,
+        };
+        if let Ok(mut lk) = monitor.lock() {
+            let before = lk.maze.get(cur.row, cur.col);
+            // We can only stop looking if we are the first to this finish. Keep looking otherwise.
+            match (solve::is_finish(before), solve::is_first(before)) {
+                (true, true) => {
+                    lk.maze.solve_history.push(maze::Delta {
+                        id: cur,
+                        before,
+                        after: before | guide.paint | guide.cache,
+                        burst: BURST,
+                    });
+                    *lk.maze.get_mut(cur.row, cur.col) |= guide.paint | guide.cache;
+                    search.trace_path(&mut lk.win_path, cur, guide.paint);
+                    return;
+                }
+                (true, false) => {
+                    lk.maze.solve_history.push(maze::Delta {
+                        id: cur,
+                        before,
+                        after: before | guide.cache,
+                        burst: BURST,
+                    });
+                    *lk.maze.get_mut(cur.row, cur.col) |= guide.cache;
+                }
+                _ => {
+                    lk.maze.solve_history.push(maze::Delta {
+                        id: cur,
+                        before,
+                        after: before | guide.paint,
+                        burst: BURST,
+                    });
+                    *lk.maze.get_mut(cur.row, cur.col) |= guide.paint;
+                }
+            }
+        } else {
+            print::maze_panic!("Thread panicked!");
+        }
+        search.push_neighbors(&monitor, &guide, &node);
+    }
+}
+
+impl<'a> Search<'a> {
+    fn new(start: maze::Point, finishes: &'a [maze::Point], heuristic: Heuristic) -> Self {
+        let mut search = Self {
+            parents: HashMap::from([(start, maze::Point { row: -1, col: -1 })]),
+            costs: HashMap::from([(start, 0)]),
+            open: BinaryHeap::new(),
+            finishes,
+            heuristic,
+        };
+        search.open.push(Reverse(search.new_node(start, 0)));
+        search
+    }
+
+    // A square may sit in the open set more than once if a cheaper way to it showed up later.
+    fn pop(&mut self) -> Option<Node> {
+        while let Some(Reverse(node)) = self.open.pop() {
+            let p = maze::Point {
+                row: node.row,
+                col: node.col,
+            };
+            if self.costs.get(&p).is_some_and(|&c| c == node.cost) {
+                return Some(node);
+            }
+        }
+        None
+    }
+
+    // Every square that joins the open set is recorded so playback shows the frontier spreading.
+    fn push_neighbors(
+        &mut self,
+        monitor: &monitor::MazeMonitor,
+        guide: &solve::ThreadGuide,
+        node: &Node,
+    ) {
+        let cur = maze::Point {
+            row: node.row,
+            col: node.col,
+        };
+        let mut i = guide.index;
+        for _ in 0..solve::NUM_DIRECTIONS {
+            let p = &maze::CARDINAL_DIRECTIONS[i];
+            i = (i + 1) % solve::NUM_DIRECTIONS;
+            let next = match monitor.lock() {
+                Err(p) => print::maze_panic!("Thread panicked: {}", p),
+                Ok(lk) => lk.maze.step(cur, *p),
+            };
+            // A passage under a crossing covers more ground than one step so the heuristic holds.
+            let cost = node.cost + (next.row - cur.row).abs() + (next.col - cur.col).abs();
+            if self.costs.get(&next).is_some_and(|&c| c <= cost) {
+                continue;
+            }
+            match monitor.lock() {
+                Err(p) => print::maze_panic!("Thread panicked: {}", p),
+                Ok(mut lk) => {
+                    let square = lk.maze.get(next.row, next.col);
+                    if !maze::is_path(square) {
+                        continue;
+                    }
+                    let after = square | frontier_paint(guide.paint);
+                    if after != square {
+                        lk.maze.solve_history.push(maze::Delta {
+                            id: next,
+                            before: square,
+                            after,
+                            burst: BURST,
+                        });
+                        *lk.maze.get_mut(next.row, next.col) = after;
+                    }
+                }
+            }
+            self.costs.insert(next, cost);
+            self.parents.insert(next, cur);
+            let next_node = self.new_node(next, cost);
+            self.open.push(Reverse(next_node));
+        }
+    }
+
+    fn new_node(&self, p: maze::Point, cost: i32) -> Node {
+        let estimate = (self.heuristic)(p, self.finishes);
+        Node {
+            total: cost + estimate,
+            estimate,
+            cost,
+            row: p.row,
+            col: p.col,
+        }
+    }
+
+    fn trace_path(
+        &self,
+        win_path: &mut Vec<(maze::Point, maze::Square)>,
+        finish: maze::Point,
+        paint: solve::ThreadPaint,
+    ) {
+        let mut prev = match self.parents.get(&finish) {
+            Some(p) => p,
+            None => print::maze_panic!("Best first search could not find parent."),
+        };
+        while prev.row > 0 {
+            win_path.push((*prev, paint));
+            prev = match self.parents.get(prev) {
+                Some(parent) => parent,
+                None => print::maze_panic!("Best first search could not find parent."),
+            };
+        }
+    }
+}
@@ -8,15 +8,16 @@ use std::thread;
 
 const BURST: usize = 4;
 
-///
-/// Data only solvers------------------------------------------------------------------------------
-///
+//
+// Data only solvers------------------------------------------------------------------------------
+//
 
 pub fn hunt(monitor: monitor::MazeReceiver) {
     let all_start: maze::Point = if let Ok(mut lk) = monitor.solver.lock() {
-        let start = solve::pick_random_point(&lk.maze);
+        let mut rng = solve::seeded_rng(&lk.maze);
+        let start = solve::pick_random_point(&lk.maze, &mut rng);
         *lk.maze.get_mut(start.row, start.col) |= solve::START_BIT;
-        let finish: maze::Point = solve::pick_random_point(&lk.maze);
+        let finish: maze::Point = solve::pick_random_point(&lk.maze, &mut rng);
         *lk.maze.get_mut(finish.row, finish.col) |= solve::FINISH_BIT;
         start
     } else {
@@ -66,33 +67,18 @@ pub fn hunt(monitor: monitor::MazeReceiver) {
 }
 
 pub fn corner(monitor: monitor::MazeReceiver) {
-    let mut all_starts: [maze::Point; 4] = if let Ok(mut lk) = monitor.solver.lock() {
-        let all_starts = solve::set_corner_starts(&lk.maze);
+    let all_starts: [maze::Point; 4] = if let Ok(mut lk) = monitor.solver.lock() {
+        let mut all_starts = solve::set_corner_starts(&lk.maze);
+        all_starts.shuffle(&mut solve::seeded_rng(&lk.maze));
         for s in all_starts {
             *lk.maze.get_mut(s.row, s.col) |= solve::START_BIT;
         }
-        let finish = maze::Point {
-            row: lk.maze.rows() / 2,
-            col: lk.maze.cols() / 2,
-        };
-        for p in maze::ALL_DIRECTIONS {
-            let next = maze::Point {
-                row: finish.row + p.row,
-                col: finish.col + p.col,
-            };
-            *lk.maze.get_mut(next.row, next.col) =
-                (lk.maze.get(next.row, next.col) & !maze::WALL_MASK) | maze::PATH_BIT;
-        }
-        *lk.maze.get_mut(finish.row, finish.col) = (lk.maze.get(finish.row, finish.col)
-            & !maze::WALL_MASK)
-            | solve::FINISH_BIT
-            | maze::PATH_BIT;
+        solve::set_corner_finish(&mut lk.maze);
         all_starts
     } else {
         print::maze_panic!("Thread panick.");
     };
 
-    all_starts.shuffle(&mut thread_rng());
     let mut handles = Vec::with_capacity(solve::NUM_THREADS - 1);
     for (i_thread, &mask) in solve::THREAD_MASKS.iter().skip(1).enumerate() {
         let monitor_clone = monitor.clone();
@@ -168,15 +154,14 @@ fn hunter(monitor: monitor::MazeReceiver, guide: solve::ThreadGuide) {
         let mut i = guide.index;
         for _ in 0..solve::NUM_DIRECTIONS {
             let p = &maze::CARDINAL_DIRECTIONS[i];
-            let next = maze::Point {
-                row: cur.row + p.row,
-                col: cur.col + p.col,
-            };
-            if match monitor.solver.lock() {
+            let (next, open) = match monitor.solver.lock() {
                 Err(p) => print::maze_panic!("Thread panicked: {}", p),
-                Ok(lk) => lk.maze.path_at(next.row, next.col),
-            } && !parents.contains_key(&next)
-            {
+                Ok(lk) => {
+                    let next = lk.maze.step(cur, *p);
+                    (next, lk.maze.path_at(next.row, next.col))
+                }
+            };
+            if open && !parents.contains_key(&next) {
                 parents.insert(next, cur);
                 bfs.push_back(next);
             }
@@ -187,10 +172,11 @@ fn hunter(monitor: monitor::MazeReceiver, guide: solve::ThreadGuide) {
 
 pub fn gather(monitor: monitor::MazeReceiver) {
     let all_start: maze::Point = if let Ok(mut lk) = monitor.solver.lock() {
-        let start = solve::pick_random_point(&lk.maze);
+        let mut rng = solve::seeded_rng(&lk.maze);
+        let start = solve::pick_random_point(&lk.maze, &mut rng);
         *lk.maze.get_mut(start.row, start.col) |= solve::START_BIT;
         for _ in 0..solve::NUM_GATHER_FINISHES {
-            let finish: maze::Point = solve::pick_random_point(&lk.maze);
+            let finish: maze::Point = solve::pick_random_point(&lk.maze, &mut rng);
             *lk.maze.get_mut(finish.row, finish.col) |= solve::FINISH_BIT;
         }
         start
@@ -257,15 +243,14 @@ fn gatherer(monitor: monitor::MazeReceiver, guide: solve::ThreadGuide) {
         let mut i = guide.index;
         for _ in 0..solve::NUM_DIRECTIONS {
             let p = &maze::CARDINAL_DIRECTIONS[i];
-            let next = maze::Point {
-                row: cur.row + p.row,
-                col: cur.col + p.col,
-            };
-            if match monitor.solver.lock() {
+            let (next, open) = match monitor.solver.lock() {
                 Err(p) => print::maze_panic!("Thread panicked: {}", p),
-                Ok(lk) => lk.maze.path_at(next.row, next.col),
-            } && !parents.contains_key(&next)
-            {
+                Ok(lk) => {
+                    let next = lk.maze.step(cur, *p);
+                    (next, lk.maze.path_at(next.row, next.col))
+                }
+            };
+            if open && !parents.contains_key(&next) {
                 parents.insert(next, cur);
                 bfs.push_back(next);
             }
@@ -274,13 +259,14 @@ fn gatherer(monitor: monitor::MazeReceiver, guide: solve::ThreadGuide) {
     }
 }
 
-///
-/// History based solvers for recording and playback-----------------------------------------------
-///
+//
+// History based solvers for recording and playback-----------------------------------------------
+//
 
 pub fn hunt_history(monitor: monitor::MazeMonitor) {
     let all_start: maze::Point = if let Ok(mut lk) = monitor.lock() {
-        let start = solve::pick_random_point(&lk.maze);
+        let mut rng = solve::seeded_rng(&lk.maze);
+        let start = solve::pick_random_point(&lk.maze, &mut rng);
         let start_square = lk.maze.get(start.row, start.col);
         lk.maze.solve_history.push(maze::Delta {
             id: start,
@@ -289,7 +275,7 @@ pub fn hunt_history(monitor: monitor::MazeMonitor) {
             burst: BURST,
         });
         *lk.maze.get_mut(start.row, start.col) |= solve::START_BIT;
-        let finish: maze::Point = solve::pick_random_point(&lk.maze);
+        let finish: maze::Point = solve::pick_random_point(&lk.maze, &mut rng);
         let finish_square = lk.maze.get(finish.row, finish.col);
         lk.maze.solve_history.push(maze::Delta {
             id: finish,
@@ -361,8 +347,9 @@ pub fn hunt_history(monitor: monitor::MazeMonitor) {
 }
 
 pub fn corner_history(monitor: monitor::MazeMonitor) {
-    let mut all_starts: [maze::Point; 4] = if let Ok(mut lk) = monitor.lock() {
-        let all_starts = solve::set_corner_starts(&lk.maze);
+    let all_starts: [maze::Point; 4] = if let Ok(mut lk) = monitor.lock() {
+        let mut all_starts = solve::set_corner_starts(&lk.maze);
+        all_starts.shuffle(&mut solve::seeded_rng(&lk.maze));
         for s in all_starts {
             let start_square = lk.maze.get(s.row, s.col);
             lk.maze.solve_history.push(maze::Delta {
@@ -373,40 +360,12 @@ pub fn corner_history(monitor: monitor::MazeMonitor) {
             });
             *lk.maze.get_mut(s.row, s.col) |= solve::START_BIT;
         }
-        let finish = maze::Point {
-            row: lk.maze.rows() / 2,
-            col: lk.maze.cols() / 2,
-        };
-        for p in maze::ALL_DIRECTIONS {
-            let next = maze::Point {
-                row: finish.row + p.row,
-                col: finish.col + p.col,
-            };
-            let next_square = lk.maze.get(next.row, next.col);
-            lk.maze.solve_history.push(maze::Delta {
-                id: next,
-                before: next_square,
-                after: (next_square & !maze::WALL_MASK) | maze::PATH_BIT,
-                burst: BURST,
-            });
-            *lk.maze.get_mut(next.row, next.col) =
-                (next_square & !maze::WALL_MASK) | maze::PATH_BIT;
-        }
-        let finish_square = lk.maze.get(finish.row, finish.col);
-        lk.maze.solve_history.push(maze::Delta {
-            id: finish,
-            before: finish_square,
-            after: (finish_square & !maze::WALL_MASK) | solve::FINISH_BIT | maze::PATH_BIT,
-            burst: BURST,
-        });
-        *lk.maze.get_mut(finish.row, finish.col) =
-            (finish_square & !maze::WALL_MASK) | solve::FINISH_BIT | maze::PATH_BIT;
+        solve::set_corner_finish_history(&mut lk.maze, BURST);
         all_starts
     } else {
         print::maze_panic!("Thread panick.");
     };
 
-    all_starts.shuffle(&mut thread_rng());
     let mut handles = Vec::with_capacity(solve::NUM_THREADS - 1);
     for (i_thread, &mask) in solve::THREAD_MASKS.iter().skip(1).enumerate() {
         let monitor_clone = monitor.clone();
@@ -509,15 +468,14 @@ fn hunter_history(monitor: monitor::MazeMonitor, guide: solve::ThreadGuide) {
         let mut i = guide.index;
         for _ in 0..solve::NUM_DIRECTIONS {
             let p = &maze::CARDINAL_DIRECTIONS[i];
-            let next = maze::Point {
-                row: cur.row + p.row,
-                col: cur.col + p.col,
-            };
-            if match monitor.lock() {
+            let (next, open) = match monitor.lock() {
                 Err(p) => print::maze_panic!("Thread panicked: {}", p),
-                Ok(lk) => maze::is_path(lk.maze.get(next.row, next.col)),
-            } && !parents.contains_key(&next)
-            {
+                Ok(lk) => {
+                    let next = lk.maze.step(cur, *p);
+                    (next, maze::is_path(lk.maze.get(next.row, next.col)))
+                }
+            };
+            if open && !parents.contains_key(&next) {
                 parents.insert(next, cur);
                 bfs.push_back(next);
             }
@@ -528,7 +486,8 @@ fn hunter_history(monitor: monitor::MazeMonitor, guide: solve::ThreadGuide) {
 
 pub fn gather_history(monitor: monitor::MazeMonitor) {
     let all_start: maze::Point = if let Ok(mut lk) = monitor.lock() {
-        let start = solve::pick_random_point(&lk.maze);
+        let mut rng = solve::seeded_rng(&lk.maze);
+        let start = solve::pick_random_point(&lk.maze, &mut rng);
         let start_square = lk.maze.get(start.row, start.col);
         lk.maze.solve_history.push(maze::Delta {
             id: start,
@@ -538,7 +497,7 @@ pub fn gather_history(monitor: monitor::MazeMonitor) {
         });
         *lk.maze.get_mut(start.row, start.col) |= solve::START_BIT;
         for _ in 0..solve::NUM_GATHER_FINISHES {
-            let finish: maze::Point = solve::pick_random_point(&lk.maze);
+            let finish: maze::Point = solve::pick_random_point(&lk.maze, &mut rng);
             let finish_square = lk.maze.get(finish.row, finish.col);
             lk.maze.solve_history.push(maze::Delta {
                 id: finish,
@@ -640,15 +599,14 @@ fn gatherer_history(monitor: monitor::MazeMonitor, guide: solve::ThreadGuide) {
         let mut i = guide.index;
         for _ in 0..solve::NUM_DIRECTIONS {
             let p = &maze::CARDINAL_DIRECTIONS[i];
-            let next = maze::Point {
-                row: cur.row + p.row,
-                col: cur.col + p.col,
-            };
-            if match monitor.lock() {
+            let (next, open) = match monitor.lock() {
                 Err(p) => print::maze_panic!("Thread panicked: {}", p),
-                Ok(lk) => maze::is_path(lk.maze.get(next.row, next.col)),
-            } && !parents.contains_key(&next)
-            {
+                Ok(lk) => {
+                    let next = lk.maze.step(cur, *p);
+                    (next, maze::is_path(lk.maze.get(next.row, next.col)))
+                }
+            };
+            if open && !parents.contains_key(&next) {
                 parents.insert(next, cur);
                 bfs.push_back(next);
             }
@@ -656,3 +614,33 @@ fn gatherer_history(monitor: monitor::MazeMonitor, guide: solve::ThreadGuide) {
         }
     }
 }
+
+//
+// Plain searches for anyone who only needs the answer and not the animation----------------------
+//
+
+// The fewest steps between two squares, or None when the finish cannot be reached from the start.
+pub fn shortest_path_len(
+    maze: &maze::Maze,
+    start: maze::Point,
+    finish: maze::Point,
+) -> Option<usize> {
+    distances(maze, start).get(&finish).copied()
+}
+
+// The fewest steps from the start to every square that can be reached from it.
+pub fn distances(maze: &maze::Maze, start: maze::Point) -> HashMap<maze::Point, usize> {
+    let mut steps = HashMap::from([(start, 0)]);
+    let mut bfs: VecDeque<maze::Point> = VecDeque::from([start]);
+    while let Some(cur) = bfs.pop_front() {
+        let taken = steps[&cur];
+        for p in maze::CARDINAL_DIRECTIONS {
+            let next = maze.step(cur, p);
+            if maze.path_at(next.row, next.col) && !steps.contains_key(&next) {
+                steps.insert(next, taken + 1);
+                bfs.push_back(next);
+            }
+        }
+    }
+    steps
+}
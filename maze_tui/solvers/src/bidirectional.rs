@@ -0,0 +1,227 @@
+use crate::solve;
+
+use std::collections::{HashMap, VecDeque};
+use std::thread;
+
+const BURST: usize = 4;
+// Threads 0 and 1 leave from the start while threads 2 and 3 leave from the finish.
+const START_CACHES: solve::ThreadCache = solve::ZERO_SEEN | solve::ONE_SEEN;
+const FINISH_CACHES: solve::ThreadCache = solve::TWO_SEEN | solve::THREE_SEEN;
+
+//
+// Bidirectional search sends half of the threads out from each end of the maze. Every thread
+// leaves its cache bit on the squares it reaches so the first thread to step onto a square the
+// other side has seen knows the searches have met. That thread tells the others through the
+// monitor and, once everyone is home, the two halves of the path are stitched together at the
+// meeting square from the parents each thread hands back.
+//
+
+#[derive(Clone, Copy)]
+enum Frontier {
+    Queue,
+    Stack,
+}
+
+type Parents = HashMap<maze::Point, maze::Point>;
+
+pub fn bfs_history(monitor: monitor::MazeMonitor) {
+    search_history(monitor, Frontier::Queue);
+}
+
+pub fn dfs_history(monitor: monitor::MazeMonitor) {
+    search_history(monitor, Frontier::Stack);
+}
+
+fn search_history(monitor: monitor::MazeMonitor, frontier: Frontier) {
+    let (start, finish) = if let Ok(mut lk) = monitor.lock() {
+        let mut rng = solve::seeded_rng(&lk.maze);
+        let start = solve::pick_random_point(&lk.maze, &mut rng);
+        let start_square = lk.maze.get(start.row, start.col);
+        lk.maze.solve_history.push(maze::Delta {
+            id: start,
+            before: start_square,
+            after: start_square | solve::START_BIT,
+            burst: BURST,
+        });
+        *lk.maze.get_mut(start.row, start.col) |= solve::START_BIT;
+        let finish: maze::Point = solve::pick_random_point(&lk.maze, &mut rng);
+        let finish_square = lk.maze.get(finish.row, finish.col);
+        lk.maze.solve_history.push(maze::Delta {
+            id: finish,
+            before: finish_square,
+            after: finish_square | solve::FINISH_BIT,
+            burst: BURST,
+        });
+        *lk.maze.get_mut(finish.row, finish.col) |= solve::FINISH_BIT;
+        (start, finish)
+    } else {
+        print::maze_panic!("Thread panic.");
+    };
+
+    let mut handles = Vec::with_capacity(solve::NUM_THREADS - 1);
+    for (i_thread, &mask) in solve::THREAD_MASKS.iter().enumerate().skip(1) {
+        let monitor_clone = monitor.clone();
+        handles.push(thread::spawn(move || {
+            searcher_history(
+                monitor_clone,
+                solve::ThreadGuide {
+                    index: i_thread,
+                    paint: mask,
+                    cache: solve::THREAD_CACHES[i_thread],
+                    start: if is_start_side(i_thread) {
+                        start
+                    } else {
+                        finish
+                    },
+                    speed: 0,
+                },
+                frontier,
+            )
+        }));
+    }
+    let mut all_parents = vec![searcher_history(
+        monitor.clone(),
+        solve::ThreadGuide {
+            index: 0,
+            paint: solve::THREAD_MASKS[0],
+            cache: solve::THREAD_CACHES[0],
+            start,
+            speed: 0,
+        },
+        frontier,
+    )];
+    for handle in handles {
+        all_parents.push(handle.join().unwrap());
+    }
+
+    if let Ok(mut lk) = monitor.lock() {
+        let len = lk.maze.solve_history.len();
+        if len % BURST != 0 {
+            lk.maze
+                .solve_history
+                .slice_mut(len - (len % BURST), len)
+                .iter_mut()
+                .for_each(|s| s.burst = 1);
+        }
+        if let (Some(winner), Some(meet)) = (lk.win, lk.meet) {
+            // Any thread from the other side that reached the meeting square knows the way home.
+            let partner = (0..solve::NUM_THREADS)
+                .filter(|&i| is_start_side(i) != is_start_side(winner))
+                .find(|&i| all_parents[i].contains_key(&meet));
+            let mut path = Vec::new();
+            trace_path(
+                &mut path,
+                &all_parents[winner],
+                meet,
+                solve::THREAD_MASKS[winner],
+            );
+            if let Some(partner) = partner {
+                let mut other_half = Vec::new();
+                trace_path(
+                    &mut other_half,
+                    &all_parents[partner],
+                    meet,
+                    solve::THREAD_MASKS[partner],
+                );
+                // The meeting square is already in the winner's half and the other half should
+                // trace outward from the middle to its end rather than back in.
+                other_half.remove(0);
+                path.reverse();
+                path.extend(other_half);
+            }
+            lk.win_path = path;
+        }
+        for i in 0..lk.win_path.len() {
+            let p = lk.win_path[i];
+            let square = lk.maze.get(p.0.row, p.0.col);
+            lk.maze.solve_history.push(maze::Delta {
+                id: p.0,
+                before: square,
+                after: (square & !solve::THREAD_MASK) | p.1,
+                burst: 1,
+            });
+            *lk.maze.get_mut(p.0.row, p.0.col) = (square & !solve::THREAD_MASK) | p.1;
+        }
+        return;
+    }
+    print::maze_panic!("Thread panicked with the lock!");
+}
+
+fn searcher_history(
+    monitor: monitor::MazeMonitor,
+    guide: solve::ThreadGuide,
+    frontier: Frontier,
+) -> Parents {
+    let (other_caches, other_end) = if is_start_side(guide.index) {
+        (FINISH_CACHES, solve::FINISH_BIT)
+    } else {
+        (START_CACHES, solve::START_BIT)
+    };
+    let mut parents = HashMap::from([(guide.start, maze::Point { row: -1, col: -1 })]);
+    let mut search: VecDeque<maze::Point> = VecDeque::from([guide.start]);
+    while let Some(cur) = match frontier {
+        Frontier::Queue => search.pop_front(),
+        Frontier::Stack => search.pop_back(),
+    } {
+        if let Ok(mut lk) = monitor.lock() {
+            if lk.win.is_some() {
+                return parents;
+            }
+            let square = lk.maze.get(cur.row, cur.col);
+            lk.maze.solve_history.push(maze::Delta {
+                id: cur,
+                before: square,
+                after: square | guide.paint | guide.cache,
+                burst: BURST,
+            });
+            *lk.maze.get_mut(cur.row, cur.col) |= guide.paint | guide.cache;
+            if (square & other_caches) != 0 || (square & other_end) != 0 {
+                lk.win.get_or_insert(guide.index);
+                lk.meet.get_or_insert(cur);
+                return parents;
+            }
+        } else {
+            print::maze_panic!("Thread panicked!");
+        }
+
+        let mut i = guide.index;
+        for _ in 0..solve::NUM_DIRECTIONS {
+            let p = &maze::CARDINAL_DIRECTIONS[i];
+            let (next, open) = match monitor.lock() {
+                Err(p) => print::maze_panic!("Thread panicked: {}", p),
+                Ok(lk) => {
+                    let next = lk.maze.step(cur, *p);
+                    (next, maze::is_path(lk.maze.get(next.row, next.col)))
+                }
+            };
+            if open && !parents.contains_key(&next) {
+                parents.insert(next, cur);
+                search.push_back(next);
+            }
+            i = (i + 1) % solve::NUM_DIRECTIONS;
+        }
+    }
+    parents
+}
+
+#[inline]
+fn is_start_side(thread_index: usize) -> bool {
+    thread_index < solve::NUM_THREADS / 2
+}
+
+// Walks from the meeting square back to the thread's own end, meeting square first.
+fn trace_path(
+    path: &mut Vec<(maze::Point, maze::Square)>,
+    parents: &Parents,
+    meet: maze::Point,
+    paint: solve::ThreadPaint,
+) {
+    let mut cur = meet;
+    while cur.row > 0 {
+        path.push((cur, paint));
+        cur = match parents.get(&cur) {
+            Some(parent) => *parent,
+            None => print::maze_panic!("Bidirectional search could not find parent."),
+        };
+    }
+}
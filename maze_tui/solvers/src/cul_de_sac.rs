@@ -0,0 +1,244 @@
+use crate::solve;
+
+use std::thread;
+
+const BURST: usize = 4;
+const SETUP_LEN: usize = 2;
+const UNSEEN: usize = usize::MAX;
+
+//
+// Cul-de-sac filling handles what dead-end filling cannot. A loop that hangs off the rest of the
+// maze by a single square has no dead end to start from but it is just as useless to a solver.
+// One depth first pass from the start finds every square that, if removed, cuts a region off from
+// the start. Any such region without the finish in it is a cul-de-sac or a plain dead end and is
+// filled from its deepest squares back toward its entrance, each thread taking its own quarter.
+//
+
+pub fn fill_history(monitor: monitor::MazeMonitor) {
+    let fills = if let Ok(mut lk) = monitor.lock() {
+        let (start, finish) = solve::set_start_and_finish_history(&mut lk.maze);
+        let mut fills: Vec<Vec<maze::Point>> = vec![Vec::new(); solve::NUM_THREADS];
+        for p in find_blind_squares(&lk.maze, start, finish) {
+            let index = (0..solve::NUM_THREADS)
+                .find(|&i| {
+                    let (rows, cols) = solve::thread_quadrant(&lk.maze, i);
+                    rows.contains(&p.row) && cols.contains(&p.col)
+                })
+                .unwrap_or(0);
+            fills[index].push(p);
+        }
+        fills
+    } else {
+        print::maze_panic!("Thread panic.");
+    };
+
+    let mut handles = Vec::with_capacity(solve::NUM_THREADS - 1);
+    for (i_thread, &mask) in solve::THREAD_MASKS.iter().enumerate().skip(1) {
+        let monitor_clone = monitor.clone();
+        let fill = fills[i_thread].clone();
+        handles.push(thread::spawn(move || {
+            filler_history(monitor_clone, &fill, mask);
+        }));
+    }
+    filler_history(monitor.clone(), &fills[0], solve::THREAD_MASKS[0]);
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    if let Ok(mut lk) = monitor.lock() {
+        // The start and finish went in as their own pair so the 4-bursts are counted after them.
+        let len = lk.maze.solve_history.len();
+        let tail = (len - SETUP_LEN) % BURST;
+        if tail != 0 {
+            lk.maze
+                .solve_history
+                .slice_mut(len - tail, len)
+                .iter_mut()
+                .for_each(|s| s.burst = 1);
+        }
+        return;
+    }
+    print::maze_panic!("Thread panicked with the lock!");
+}
+
+fn filler_history(monitor: monitor::MazeMonitor, fill: &[maze::Point], paint: solve::ThreadPaint) {
+    for &p in fill {
+        if let Ok(mut lk) = monitor.lock() {
+            let before = lk.maze.get(p.row, p.col);
+            lk.maze.solve_history.push(maze::Delta {
+                id: p,
+                before,
+                after: before | paint,
+                burst: BURST,
+            });
+            *lk.maze.get_mut(p.row, p.col) |= paint;
+        } else {
+            print::maze_panic!("Thread panicked!");
+        }
+    }
+}
+
+// Tarjan's cut vertex search without recursion so large mazes do not overflow the stack. Squares
+// are numbered in visit order which makes every subtree a contiguous run of numbers. The squares
+// to fill come back deepest first so a fill always works from the back of a region to its mouth.
+fn find_blind_squares(
+    maze: &maze::Maze,
+    start: maze::Point,
+    finish: maze::Point,
+) -> Vec<maze::Point> {
+    let cols = maze.cols();
+    let at = |p: maze::Point| (p.row * cols + p.col) as usize;
+    let size = (maze.rows() * cols) as usize;
+    let mut disc = vec![UNSEEN; size];
+    let mut low = vec![UNSEEN; size];
+    let mut order: Vec<maze::Point> = Vec::new();
+    let mut blind: Vec<bool> = Vec::new();
+    let mut dfs: Vec<(maze::Point, usize)> = vec![(start, 0)];
+    disc[at(start)] = 0;
+    low[at(start)] = 0;
+    order.push(start);
+    blind.push(false);
+    while let Some((cur, dir)) = dfs.last_mut() {
+        let cur = *cur;
+        if *dir < solve::NUM_DIRECTIONS {
+            let d = &maze::CARDINAL_DIRECTIONS[*dir];
+            *dir += 1;
+            let next = maze.step(cur, *d);
+            if !maze.path_at(next.row, next.col) {
+                continue;
+            }
+            if disc[at(next)] == UNSEEN {
+                disc[at(next)] = order.len();
+                low[at(next)] = order.len();
+                order.push(next);
+                blind.push(false);
+                dfs.push((next, 0));
+            } else {
+                low[at(cur)] = low[at(cur)].min(disc[at(next)]);
+            }
+            continue;
+        }
+        dfs.pop();
+        let parent = match dfs.last() {
+            Some(&(parent, _)) => parent,
+            None => break,
+        };
+        low[at(parent)] = low[at(parent)].min(low[at(cur)]);
+        let subtree = disc[at(cur)]..order.len();
+        // The parent is the only way into this subtree. Without the finish inside it is blind.
+        if low[at(cur)] >= disc[at(parent)] && !subtree.contains(&disc[at(finish)]) {
+            blind[subtree].iter_mut().for_each(|b| *b = true);
+        }
+    }
+    order
+        .into_iter()
+        .zip(blind)
+        .rev()
+        .filter_map(|(p, b)| b.then_some(p))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // S and F are the start and finish. The loop around S is part of the way through but the
+    // loop hanging off the corridor by a single square and the short spur are not.
+    const MAZE: [&str; 7] = [
+        "#############",
+        "#S.........F#",
+        "#.#.#.###.###",
+        "#...#...#.###",
+        "#####.#.#####",
+        "#####...#####",
+        "#############",
+    ];
+
+    fn drawn(art: &[&str]) -> (maze::Maze, maze::Point, maze::Point) {
+        let mut maze = maze::Maze::new(maze::MazeArgs {
+            odd_rows: art.len() as i32,
+            odd_cols: art[0].len() as i32,
+            ..Default::default()
+        });
+        assert_eq!((maze.rows(), maze.cols()), (7, 13));
+        let (mut start, mut finish) = (maze::Point::default(), maze::Point::default());
+        for (row, line) in art.iter().enumerate() {
+            for (col, ch) in line.chars().enumerate() {
+                let p = maze::Point {
+                    row: row as i32,
+                    col: col as i32,
+                };
+                match ch {
+                    '#' => continue,
+                    'S' => start = p,
+                    'F' => finish = p,
+                    _ => {}
+                }
+                *maze.get_mut(p.row, p.col) = maze::PATH_BIT;
+            }
+        }
+        (maze, start, finish)
+    }
+
+    fn at(row: i32, col: i32) -> maze::Point {
+        maze::Point { row, col }
+    }
+
+    #[test]
+    fn loop_hanging_off_one_square_is_filled_from_the_back() {
+        let (maze, start, finish) = drawn(&MAZE);
+        let blind = find_blind_squares(&maze, start, finish);
+        let hanging_loop = [
+            at(2, 5),
+            at(3, 5),
+            at(3, 6),
+            at(3, 7),
+            at(4, 5),
+            at(4, 7),
+            at(5, 5),
+            at(5, 6),
+            at(5, 7),
+        ];
+        let spur = [at(2, 9), at(3, 9)];
+        let mut expected: Vec<maze::Point> = hanging_loop.iter().chain(&spur).copied().collect();
+        let mut filled = blind.clone();
+        expected.sort_by_key(|p| (p.row, p.col));
+        filled.sort_by_key(|p| (p.row, p.col));
+        assert_eq!(filled, expected);
+
+        // Every surviving path square is on the corridor or the loop around the start.
+        for (row, line) in MAZE.iter().enumerate() {
+            for (col, ch) in line.chars().enumerate() {
+                let p = at(row as i32, col as i32);
+                if ch != '#' && !expected.contains(&p) {
+                    assert!(
+                        row == 1 || (col <= 3 && row <= 3),
+                        "{:?} should be filled",
+                        p
+                    );
+                }
+            }
+        }
+
+        // A region is filled from its deepest squares so the mouth always goes last.
+        let position = |p: maze::Point| blind.iter().position(|&b| b == p).unwrap();
+        assert!(hanging_loop[1..]
+            .iter()
+            .all(|&p| position(p) < position(at(2, 5))));
+        assert!(position(at(3, 9)) < position(at(2, 9)));
+    }
+
+    #[test]
+    fn loop_holding_the_finish_survives_and_the_corridor_past_it_fills() {
+        let mut art = MAZE;
+        art[1] = "#S..........#";
+        art[5] = "#####..F#####";
+        let (maze, start, finish) = drawn(&art);
+        let mut blind = find_blind_squares(&maze, start, finish);
+        blind.sort_by_key(|p| (p.row, p.col));
+        let mut expected: Vec<maze::Point> = (6..=11).map(|col| at(1, col)).collect();
+        expected.extend([at(2, 9), at(3, 9)]);
+        assert_eq!(blind, expected);
+    }
+}
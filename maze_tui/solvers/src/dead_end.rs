@@ -0,0 +1,106 @@
+use crate::solve;
+
+use std::thread;
+
+const BURST: usize = 4;
+const SETUP_LEN: usize = 2;
+
+//
+// Dead-end filling never walks the maze. Any path square boxed in on three sides cannot be part of
+// a solution so it is filled in, which may box in the square next to it, and so on. Each thread
+// hunts for dead ends in its own quarter of the maze but follows a filled corridor wherever it
+// leads. When every thread is done only the corridors between start and finish are left open.
+//
+
+pub fn fill_history(monitor: monitor::MazeMonitor) {
+    if let Ok(mut lk) = monitor.lock() {
+        solve::set_start_and_finish_history(&mut lk.maze);
+    } else {
+        print::maze_panic!("Thread panic.");
+    }
+
+    let mut handles = Vec::with_capacity(solve::NUM_THREADS - 1);
+    for (i_thread, &mask) in solve::THREAD_MASKS.iter().skip(1).enumerate() {
+        let monitor_clone = monitor.clone();
+        handles.push(thread::spawn(move || {
+            filler_history(monitor_clone, i_thread + 1, mask);
+        }));
+    }
+    filler_history(monitor.clone(), 0, solve::THREAD_MASKS[0]);
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    if let Ok(mut lk) = monitor.lock() {
+        // The start and finish went in as their own pair so the 4-bursts are counted after them.
+        let len = lk.maze.solve_history.len();
+        let tail = (len - SETUP_LEN) % BURST;
+        if tail != 0 {
+            lk.maze
+                .solve_history
+                .slice_mut(len - tail, len)
+                .iter_mut()
+                .for_each(|s| s.burst = 1);
+        }
+        return;
+    }
+    print::maze_panic!("Thread panicked with the lock!");
+}
+
+fn filler_history(monitor: monitor::MazeMonitor, index: usize, paint: solve::ThreadPaint) {
+    let (rows, cols) = match monitor.lock() {
+        Ok(lk) => solve::thread_quadrant(&lk.maze, index),
+        Err(p) => print::maze_panic!("Thread panicked: {}", p),
+    };
+    for row in rows {
+        for col in cols.clone() {
+            let mut cur = maze::Point { row, col };
+            // Filling one dead end may open up the next so follow the corridor until it branches.
+            loop {
+                let mut lk = match monitor.lock() {
+                    Ok(lk) => lk,
+                    Err(p) => print::maze_panic!("Thread panicked: {}", p),
+                };
+                let exit = match open_exit(&lk.maze, cur) {
+                    Some(exit) => exit,
+                    None => break,
+                };
+                let before = lk.maze.get(cur.row, cur.col);
+                lk.maze.solve_history.push(maze::Delta {
+                    id: cur,
+                    before,
+                    after: before | paint,
+                    burst: BURST,
+                });
+                *lk.maze.get_mut(cur.row, cur.col) |= paint;
+                match exit {
+                    Some(next) => cur = next,
+                    None => break,
+                }
+            }
+        }
+    }
+}
+
+// A fillable square is open path with at most one open neighbor, which is returned if it exists.
+fn open_exit(maze: &maze::Maze, p: maze::Point) -> Option<Option<maze::Point>> {
+    let square = maze.get(p.row, p.col);
+    if !is_open(maze, p) || solve::is_start(square) || solve::is_finish(square) {
+        return None;
+    }
+    let mut exits = maze::CARDINAL_DIRECTIONS.iter().filter_map(|d| {
+        let next = maze.step(p, *d);
+        is_open(maze, next).then_some(next)
+    });
+    let exit = exits.next();
+    match exits.next() {
+        Some(_) => None,
+        None => Some(exit),
+    }
+}
+
+#[inline]
+fn is_open(maze: &maze::Maze, p: maze::Point) -> bool {
+    maze.path_at(p.row, p.col) && !solve::is_color(maze.get(p.row, p.col))
+}
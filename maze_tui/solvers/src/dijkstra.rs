@@ -0,0 +1,22 @@
+use crate::best_first;
+
+//
+// Dijkstra orders its open set by path cost alone so the frontier grows evenly in every
+// direction until it touches a finish.
+//
+
+fn no_estimate(_: maze::Point, _: &[maze::Point]) -> i32 {
+    0
+}
+
+pub fn hunt_history(monitor: monitor::MazeMonitor) {
+    best_first::hunt_history(monitor, no_estimate);
+}
+
+pub fn gather_history(monitor: monitor::MazeMonitor) {
+    best_first::gather_history(monitor, no_estimate);
+}
+
+pub fn corner_history(monitor: monitor::MazeMonitor) {
+    best_first::corner_history(monitor, no_estimate);
+}
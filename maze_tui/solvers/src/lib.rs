@@ -1,5 +1,13 @@
+pub mod astar;
+mod best_first;
 pub mod bfs;
+pub mod bidirectional;
+pub mod cul_de_sac;
+pub mod dead_end;
 pub mod dfs;
+pub mod dijkstra;
 pub mod floodfs;
 pub mod rdfs;
 pub mod solve;
+pub mod tremaux;
+pub mod wall_follower;
@@ -5,15 +5,16 @@ use print;
 use rand::prelude::*;
 use std::thread;
 
-///
-/// Data only solvers------------------------------------------------------------------------------
-///
+//
+// Data only solvers------------------------------------------------------------------------------
+//
 
 pub fn hunt(monitor: monitor::MazeReceiver) {
     let all_start: maze::Point = if let Ok(mut lk) = monitor.solver.lock() {
-        let all_start = solve::pick_random_point(&lk.maze);
+        let mut rng = solve::seeded_rng(&lk.maze);
+        let all_start = solve::pick_random_point(&lk.maze, &mut rng);
         *lk.maze.get_mut(all_start.row, all_start.col) |= solve::START_BIT;
-        let finish: maze::Point = solve::pick_random_point(&lk.maze);
+        let finish: maze::Point = solve::pick_random_point(&lk.maze, &mut rng);
         *lk.maze.get_mut(finish.row, finish.col) |= solve::FINISH_BIT;
         all_start
     } else {
@@ -52,34 +53,19 @@ pub fn hunt(monitor: monitor::MazeReceiver) {
 }
 
 pub fn corner(monitor: monitor::MazeReceiver) {
-    let mut corner_starts: [maze::Point; 4] = if let Ok(mut lk) = monitor.solver.lock() {
-        let corner_starts = solve::set_corner_starts(&lk.maze);
+    let corner_starts: [maze::Point; 4] = if let Ok(mut lk) = monitor.solver.lock() {
+        let mut corner_starts = solve::set_corner_starts(&lk.maze);
+        corner_starts.shuffle(&mut solve::seeded_rng(&lk.maze));
         for p in corner_starts {
             *lk.maze.get_mut(p.row, p.col) |= solve::START_BIT;
         }
 
-        let finish = maze::Point {
-            row: lk.maze.rows() / 2,
-            col: lk.maze.cols() / 2,
-        };
-        for d in maze::ALL_DIRECTIONS {
-            let next = maze::Point {
-                row: finish.row + d.row,
-                col: finish.col + d.col,
-            };
-            *lk.maze.get_mut(next.row, next.col) =
-                (lk.maze.get(next.row, next.col) & !maze::WALL_MASK) | maze::PATH_BIT;
-        }
-        *lk.maze.get_mut(finish.row, finish.col) = (lk.maze.get(finish.row, finish.col)
-            & !maze::WALL_MASK)
-            | solve::FINISH_BIT
-            | maze::PATH_BIT;
+        solve::set_corner_finish(&mut lk.maze);
         corner_starts
     } else {
         print::maze_panic!("Thread panic.");
     };
 
-    corner_starts.shuffle(&mut thread_rng());
     let mut handles = Vec::with_capacity(solve::NUM_THREADS - 1);
     for (i_thread, &mask) in solve::THREAD_MASKS.iter().skip(1).enumerate() {
         let monitor_clone = monitor.clone();
@@ -115,7 +101,10 @@ fn hunter(monitor: monitor::MazeReceiver, guide: solve::ThreadGuide) {
     let mut dfs: Vec<maze::Point> = Vec::with_capacity(solve::INITIAL_PATH_LEN);
     dfs.push(guide.start);
 
-    let mut rng = thread_rng();
+    let mut rng = match monitor.solver.lock() {
+        Ok(lk) => solve::seeded_thread_rng(&lk.maze, guide.index),
+        Err(p) => print::maze_panic!("Thread panicked: {}", p),
+    };
     let mut rng_arr: [usize; solve::NUM_DIRECTIONS] = [0, 1, 2, 3];
     'branching: while let Some(&cur) = dfs.last() {
         if let Ok(mut lk) = monitor.solver.lock() {
@@ -143,18 +132,15 @@ fn hunter(monitor: monitor::MazeReceiver, guide: solve::ThreadGuide) {
         rng_arr.shuffle(&mut rng);
         for &i in &rng_arr {
             let p: &maze::Point = &maze::CARDINAL_DIRECTIONS[i];
-            let next = maze::Point {
-                row: cur.row + p.row,
-                col: cur.col + p.col,
-            };
-
-            if match monitor.solver.lock() {
+            let (next, open) = match monitor.solver.lock() {
                 Ok(lk) => {
+                    let next = lk.maze.step(cur, *p);
                     let square = lk.maze.get(next.row, next.col);
-                    (square & guide.cache) == 0 && maze::is_path(square)
+                    (next, (square & guide.cache) == 0 && maze::is_path(square))
                 }
                 Err(p) => print::maze_panic!("Solve thread panic: {}", p),
-            } {
+            };
+            if open {
                 dfs.push(next);
                 continue 'branching;
             }
@@ -165,10 +151,11 @@ fn hunter(monitor: monitor::MazeReceiver, guide: solve::ThreadGuide) {
 
 pub fn gather(monitor: monitor::MazeReceiver) {
     let all_start: maze::Point = if let Ok(mut lk) = monitor.solver.lock() {
-        let all_start = solve::pick_random_point(&lk.maze);
+        let mut rng = solve::seeded_rng(&lk.maze);
+        let all_start = solve::pick_random_point(&lk.maze, &mut rng);
         *lk.maze.get_mut(all_start.row, all_start.col) |= solve::START_BIT;
         for _ in 0..solve::NUM_GATHER_FINISHES {
-            let finish: maze::Point = solve::pick_random_point(&lk.maze);
+            let finish: maze::Point = solve::pick_random_point(&lk.maze, &mut rng);
             *lk.maze.get_mut(finish.row, finish.col) |= solve::FINISH_BIT;
         }
         all_start
@@ -210,7 +197,10 @@ pub fn gather(monitor: monitor::MazeReceiver) {
 fn gatherer(monitor: monitor::MazeReceiver, guide: solve::ThreadGuide) {
     let mut dfs: Vec<maze::Point> = Vec::with_capacity(solve::INITIAL_PATH_LEN);
     dfs.push(guide.start);
-    let mut rng = thread_rng();
+    let mut rng = match monitor.solver.lock() {
+        Ok(lk) => solve::seeded_thread_rng(&lk.maze, guide.index),
+        Err(p) => print::maze_panic!("Thread panicked: {}", p),
+    };
     let mut rng_arr: [usize; solve::NUM_DIRECTIONS] = [0, 1, 2, 3];
     'branching: while let Some(&cur) = dfs.last() {
         if let Ok(mut lk) = monitor.solver.lock() {
@@ -234,18 +224,15 @@ fn gatherer(monitor: monitor::MazeReceiver, guide: solve::ThreadGuide) {
         rng_arr.shuffle(&mut rng);
         for &i in &rng_arr {
             let p: &maze::Point = &maze::CARDINAL_DIRECTIONS[i];
-            let next = maze::Point {
-                row: cur.row + p.row,
-                col: cur.col + p.col,
-            };
-
-            if match monitor.solver.lock() {
+            let (next, open) = match monitor.solver.lock() {
                 Ok(lk) => {
+                    let next = lk.maze.step(cur, *p);
                     let square = lk.maze.get(next.row, next.col);
-                    (square & guide.cache) == 0 && maze::is_path(square)
+                    (next, (square & guide.cache) == 0 && maze::is_path(square))
                 }
                 Err(p) => print::maze_panic!("Solve thread panic: {}", p),
-            } {
+            };
+            if open {
                 dfs.push(next);
                 continue 'branching;
             }
@@ -254,13 +241,14 @@ fn gatherer(monitor: monitor::MazeReceiver, guide: solve::ThreadGuide) {
     }
 }
 
-///
-/// History based solvers for recording and playback-----------------------------------------------
-///
+//
+// History based solvers for recording and playback-----------------------------------------------
+//
 
 pub fn hunt_history(monitor: monitor::MazeMonitor) {
     let all_start: maze::Point = if let Ok(mut lk) = monitor.lock() {
-        let all_start = solve::pick_random_point(&lk.maze);
+        let mut rng = solve::seeded_rng(&lk.maze);
+        let all_start = solve::pick_random_point(&lk.maze, &mut rng);
         let start_square = lk.maze.get(all_start.row, all_start.col);
         lk.maze.solve_history.push(maze::Delta {
             id: all_start,
@@ -269,7 +257,7 @@ pub fn hunt_history(monitor: monitor::MazeMonitor) {
             burst: 1,
         });
         *lk.maze.get_mut(all_start.row, all_start.col) |= solve::START_BIT;
-        let finish: maze::Point = solve::pick_random_point(&lk.maze);
+        let finish: maze::Point = solve::pick_random_point(&lk.maze, &mut rng);
         let finish_square = lk.maze.get(finish.row, finish.col);
         lk.maze.solve_history.push(maze::Delta {
             id: finish,
@@ -315,8 +303,9 @@ pub fn hunt_history(monitor: monitor::MazeMonitor) {
 }
 
 pub fn corner_history(monitor: monitor::MazeMonitor) {
-    let mut corner_starts: [maze::Point; 4] = if let Ok(mut lk) = monitor.lock() {
-        let corner_starts = solve::set_corner_starts(&lk.maze);
+    let corner_starts: [maze::Point; 4] = if let Ok(mut lk) = monitor.lock() {
+        let mut corner_starts = solve::set_corner_starts(&lk.maze);
+        corner_starts.shuffle(&mut solve::seeded_rng(&lk.maze));
         for p in corner_starts {
             let start_square = lk.maze.get(p.row, p.col);
             lk.maze.solve_history.push(maze::Delta {
@@ -328,40 +317,12 @@ pub fn corner_history(monitor: monitor::MazeMonitor) {
             *lk.maze.get_mut(p.row, p.col) |= solve::START_BIT;
         }
 
-        let finish = maze::Point {
-            row: lk.maze.rows() / 2,
-            col: lk.maze.cols() / 2,
-        };
-        for d in maze::ALL_DIRECTIONS {
-            let next = maze::Point {
-                row: finish.row + d.row,
-                col: finish.col + d.col,
-            };
-            let next_square = lk.maze.get(next.row, next.col);
-            lk.maze.solve_history.push(maze::Delta {
-                id: next,
-                before: next_square,
-                after: (next_square & !maze::WALL_MASK) | maze::PATH_BIT,
-                burst: 1,
-            });
-            *lk.maze.get_mut(next.row, next.col) =
-                (next_square & !maze::WALL_MASK) | maze::PATH_BIT;
-        }
-        let finish_square = lk.maze.get(finish.row, finish.col);
-        lk.maze.solve_history.push(maze::Delta {
-            id: finish,
-            before: finish_square,
-            after: (finish_square & !maze::WALL_MASK) | solve::FINISH_BIT | maze::PATH_BIT,
-            burst: 1,
-        });
-        *lk.maze.get_mut(finish.row, finish.col) =
-            (finish_square & !maze::WALL_MASK) | solve::FINISH_BIT | maze::PATH_BIT;
+        solve::set_corner_finish_history(&mut lk.maze, 1);
         corner_starts
     } else {
         print::maze_panic!("Thread panic.");
     };
 
-    corner_starts.shuffle(&mut thread_rng());
     let mut handles = Vec::with_capacity(solve::NUM_THREADS - 1);
     for (i_thread, &mask) in solve::THREAD_MASKS.iter().skip(1).enumerate() {
         let mc = monitor.clone();
@@ -396,7 +357,10 @@ pub fn corner_history(monitor: monitor::MazeMonitor) {
 fn hunter_history(monitor: monitor::MazeMonitor, guide: solve::ThreadGuide) {
     let mut dfs: Vec<maze::Point> = Vec::with_capacity(solve::INITIAL_PATH_LEN);
     dfs.push(guide.start);
-    let mut rng = thread_rng();
+    let mut rng = match monitor.lock() {
+        Ok(lk) => solve::seeded_thread_rng(&lk.maze, guide.index),
+        Err(p) => print::maze_panic!("Thread panicked: {}", p),
+    };
     let mut rng_arr: [usize; solve::NUM_DIRECTIONS] = [0, 1, 2, 3];
     'branching: while let Some(&cur) = dfs.last() {
         if let Ok(mut lk) = monitor.lock() {
@@ -430,18 +394,15 @@ fn hunter_history(monitor: monitor::MazeMonitor, guide: solve::ThreadGuide) {
         rng_arr.shuffle(&mut rng);
         for &i in &rng_arr {
             let p: &maze::Point = &maze::CARDINAL_DIRECTIONS[i];
-            let next = maze::Point {
-                row: cur.row + p.row,
-                col: cur.col + p.col,
-            };
-
-            if match monitor.lock() {
+            let (next, open) = match monitor.lock() {
                 Ok(lk) => {
+                    let next = lk.maze.step(cur, *p);
                     let square = lk.maze.get(next.row, next.col);
-                    (square & guide.cache) == 0 && maze::is_path(square)
+                    (next, (square & guide.cache) == 0 && maze::is_path(square))
                 }
                 Err(p) => print::maze_panic!("Solve thread panic: {}", p),
-            } {
+            };
+            if open {
                 dfs.push(next);
                 continue 'branching;
             }
@@ -466,7 +427,8 @@ fn hunter_history(monitor: monitor::MazeMonitor, guide: solve::ThreadGuide) {
 
 pub fn gather_history(monitor: monitor::MazeMonitor) {
     let all_start: maze::Point = if let Ok(mut lk) = monitor.lock() {
-        let all_start = solve::pick_random_point(&lk.maze);
+        let mut rng = solve::seeded_rng(&lk.maze);
+        let all_start = solve::pick_random_point(&lk.maze, &mut rng);
         let start_square = lk.maze.get(all_start.row, all_start.col);
         lk.maze.solve_history.push(maze::Delta {
             id: all_start,
@@ -476,7 +438,7 @@ pub fn gather_history(monitor: monitor::MazeMonitor) {
         });
         *lk.maze.get_mut(all_start.row, all_start.col) |= solve::START_BIT;
         for _ in 0..solve::NUM_GATHER_FINISHES {
-            let finish: maze::Point = solve::pick_random_point(&lk.maze);
+            let finish: maze::Point = solve::pick_random_point(&lk.maze, &mut rng);
             let finish_square = lk.maze.get(finish.row, finish.col);
             lk.maze.solve_history.push(maze::Delta {
                 id: finish,
@@ -525,7 +487,10 @@ pub fn gather_history(monitor: monitor::MazeMonitor) {
 fn gatherer_history(monitor: monitor::MazeMonitor, guide: solve::ThreadGuide) {
     let mut dfs: Vec<maze::Point> = Vec::with_capacity(solve::INITIAL_PATH_LEN);
     dfs.push(guide.start);
-    let mut rng = thread_rng();
+    let mut rng = match monitor.lock() {
+        Ok(lk) => solve::seeded_thread_rng(&lk.maze, guide.index),
+        Err(p) => print::maze_panic!("Thread panicked: {}", p),
+    };
     let mut rng_arr: [usize; solve::NUM_DIRECTIONS] = [0, 1, 2, 3];
     'branching: while let Some(&cur) = dfs.last() {
         if let Ok(mut lk) = monitor.lock() {
@@ -555,18 +520,15 @@ fn gatherer_history(monitor: monitor::MazeMonitor, guide: solve::ThreadGuide) {
         rng_arr.shuffle(&mut rng);
         for &i in &rng_arr {
             let p: &maze::Point = &maze::CARDINAL_DIRECTIONS[i];
-            let next = maze::Point {
-                row: cur.row + p.row,
-                col: cur.col + p.col,
-            };
-
-            if match monitor.lock() {
+            let (next, open) = match monitor.lock() {
                 Ok(lk) => {
+                    let next = lk.maze.step(cur, *p);
                     let square = lk.maze.get(next.row, next.col);
-                    (square & guide.cache) == 0 && maze::is_path(square)
+                    (next, (square & guide.cache) == 0 && maze::is_path(square))
                 }
                 Err(p) => print::maze_panic!("Solve thread panic: {}", p),
-            } {
+            };
+            if open {
                 dfs.push(next);
                 continue 'branching;
             }
@@ -1,14 +1,15 @@
 use maze;
 use print::maze_panic;
-use rand::prelude::*;
+use rand::{rngs::StdRng, Rng};
 use ratatui::{
     buffer::Cell,
     style::{Color as RatColor, Modifier},
 };
+use std::ops::Range;
 
 // Types available to all solvers.
-pub type ThreadPaint = u32;
-pub type ThreadCache = u32;
+pub type ThreadPaint = maze::Square;
+pub type ThreadCache = maze::Square;
 pub type SolveSpeedUnit = u64;
 pub struct ThreadGuide {
     pub index: usize,
@@ -41,11 +42,19 @@ pub const ONE_SEEN: ThreadCache = 0x2000000;
 pub const TWO_SEEN: ThreadCache = 0x4000000;
 pub const THREE_SEEN: ThreadCache = 0x8000000;
 pub const THREAD_CACHES: [ThreadCache; 4] = [ZERO_SEEN, ONE_SEEN, TWO_SEEN, THREE_SEEN];
-pub const SOLVER_SPEEDS: [SolveSpeedUnit; 8] = [0, 20000, 10000, 5000, 2000, 1000, 500, 250];
+// Trémaux chalk marks are counted above the low word where no paint or cache can overwrite them.
+pub const MARK_SHIFT: usize = 33;
+pub const MARK_MASK: ThreadPaint = 0b11 << MARK_SHIFT;
+pub const SOLVER_SEED_STREAM: maze::Seed = 0x5EED_501E;
+// The render tick of each -sa level in microseconds, laid out like the builder speeds. Solvers
+// paint several squares a step so their fast levels slow down a little sooner.
+pub const SOLVER_SPEEDS: [SolveSpeedUnit; 8] = [
+    0, 2_000_000, 1_000_000, 500_000, 250_000, 100_000, 25_000, 1_000,
+];
 
-///
-/// Logical helpers for bitwise operations.
-///
+//
+// Logical helpers for bitwise operations.
+//
 
 #[inline]
 pub fn is_start(square: maze::Square) -> bool {
@@ -62,6 +71,11 @@ pub fn is_color(square: maze::Square) -> bool {
     (square & THREAD_MASK) != 0
 }
 
+#[inline]
+pub fn marks(square: maze::Square) -> maze::Square {
+    (square & MARK_MASK) >> MARK_SHIFT
+}
+
 #[inline]
 pub fn is_first(square: maze::Square) -> bool {
     (square & CACHE_MASK) == 0
@@ -88,13 +102,24 @@ fn is_valid_start_or_finish(maze: &maze::Maze, choice: maze::Point) -> bool {
         && choice.col > 0
         && choice.col < maze.cols() - 1
         && maze.path_at(choice.row, choice.col)
+        && !maze.is_masked(choice)
         && !is_finish(maze.get(choice.row, choice.col))
         && !is_start(maze.get(choice.row, choice.col))
 }
 
-///
-/// Setup functions for starting and finishing a solver section.
-///
+//
+// Setup functions for starting and finishing a solver section.
+//
+
+pub fn seeded_rng(maze: &maze::Maze) -> StdRng {
+    maze.seeded_rng(SOLVER_SEED_STREAM)
+}
+
+// Each thread gets a stream of its own. The threads still race for the lock so only their
+// individual choices, not the order of their paint on the maze, are reproducible.
+pub fn seeded_thread_rng(maze: &maze::Maze, thread_index: usize) -> StdRng {
+    maze.seeded_rng(SOLVER_SEED_STREAM ^ (thread_index as maze::Seed + 1))
+}
 
 pub fn reset_solve(maze: &mut maze::Maze) {
     for square in maze.as_slice_mut().iter_mut() {
@@ -104,6 +129,46 @@ pub fn reset_solve(maze: &mut maze::Maze) {
     }
 }
 
+// The single walker solvers record their start and finish as a pair so they appear together.
+pub fn set_start_and_finish_history(maze: &mut maze::Maze) -> (maze::Point, maze::Point) {
+    let mut rng = seeded_rng(maze);
+    let start = pick_random_point(maze, &mut rng);
+    let start_square = maze.get(start.row, start.col);
+    maze.solve_history.push(maze::Delta {
+        id: start,
+        before: start_square,
+        after: start_square | START_BIT,
+        burst: 2,
+    });
+    *maze.get_mut(start.row, start.col) |= START_BIT;
+    let finish = pick_random_point(maze, &mut rng);
+    let finish_square = maze.get(finish.row, finish.col);
+    maze.solve_history.push(maze::Delta {
+        id: finish,
+        before: finish_square,
+        after: finish_square | FINISH_BIT,
+        burst: 2,
+    });
+    *maze.get_mut(finish.row, finish.col) |= FINISH_BIT;
+    (start, finish)
+}
+
+// Solvers that work on the whole maze rather than walking it give each thread a quarter.
+pub fn thread_quadrant(maze: &maze::Maze, thread_index: usize) -> (Range<i32>, Range<i32>) {
+    let (mid_row, mid_col) = (maze.rows() / 2, maze.cols() / 2);
+    let rows = if thread_index / 2 == 0 {
+        1..mid_row
+    } else {
+        mid_row..maze.rows() - 1
+    };
+    let cols = if thread_index.is_multiple_of(2) {
+        1..mid_col
+    } else {
+        mid_col..maze.cols() - 1
+    };
+    (rows, cols)
+}
+
 pub fn set_corner_starts(maze: &maze::Maze) -> [maze::Point; 4] {
     let mut point1: maze::Point = maze::Point { row: 1, col: 1 };
     if maze.wall_at(point1.row, point1.col) {
@@ -133,8 +198,75 @@ pub fn set_corner_starts(maze: &maze::Maze) -> [maze::Point; 4] {
     [point1, point2, point3, point4]
 }
 
-pub fn pick_random_point(maze: &maze::Maze) -> maze::Point {
-    let mut gen = thread_rng();
+// The corner solvers race to the center so it and every square around it are carved open. A
+// tunnel carved here would leave half a crossing behind so its partner is carved open as well.
+pub fn set_corner_finish(maze: &mut maze::Maze) -> maze::Point {
+    let finish = maze::Point {
+        row: maze.rows() / 2,
+        col: maze.cols() / 2,
+    };
+    for next in corner_finish_ring(maze, finish) {
+        *maze.get_mut(next.row, next.col) = carved(maze.get(next.row, next.col));
+    }
+    *maze.get_mut(finish.row, finish.col) = carved(maze.get(finish.row, finish.col)) | FINISH_BIT;
+    finish
+}
+
+pub fn set_corner_finish_history(maze: &mut maze::Maze, burst: usize) -> maze::Point {
+    let finish = maze::Point {
+        row: maze.rows() / 2,
+        col: maze.cols() / 2,
+    };
+    for next in corner_finish_ring(maze, finish) {
+        let before = maze.get(next.row, next.col);
+        maze.solve_history.push(maze::Delta {
+            id: next,
+            before,
+            after: carved(before),
+            burst,
+        });
+        *maze.get_mut(next.row, next.col) = carved(before);
+    }
+    let finish_square = maze.get(finish.row, finish.col);
+    maze.solve_history.push(maze::Delta {
+        id: finish,
+        before: finish_square,
+        after: carved(finish_square) | FINISH_BIT,
+        burst,
+    });
+    *maze.get_mut(finish.row, finish.col) = carved(finish_square) | FINISH_BIT;
+    finish
+}
+
+// Every square around the finish followed by the partners of any tunnels among them or the finish.
+fn corner_finish_ring(maze: &maze::Maze, finish: maze::Point) -> Vec<maze::Point> {
+    let mut ring: Vec<maze::Point> = maze::ALL_DIRECTIONS
+        .iter()
+        .map(|d| maze::Point {
+            row: finish.row + d.row,
+            col: finish.col + d.col,
+        })
+        .collect();
+    let partners: Vec<maze::Point> = ring
+        .iter()
+        .chain(std::iter::once(&finish))
+        .filter(|p| maze.tunnel_at(p.row, p.col))
+        .filter_map(|&p| maze.tunnel_partner(p))
+        .filter(|q| *q != finish && !ring.contains(q))
+        .collect();
+    ring.extend(partners);
+    ring
+}
+
+#[inline]
+fn carved(square: maze::Square) -> maze::Square {
+    (square & !(maze::WALL_MASK | maze::TUNNEL_BIT)) | maze::PATH_BIT
+}
+
+pub fn pick_random_point(maze: &maze::Maze, gen: &mut StdRng) -> maze::Point {
+    if let Some(placed) = placed_point(maze) {
+        return placed;
+    }
     let choice = maze::Point {
         row: gen.gen_range(1..maze.rows() - 2),
         col: gen.gen_range(1..maze.cols() - 2),
@@ -145,6 +277,29 @@ pub fn pick_random_point(maze: &maze::Maze) -> maze::Point {
     find_nearest_square(maze, choice)
 }
 
+// Every solver picks its start before its finish, so a hand placed start is the first answer and a
+// hand placed finish is the first answer once some start is marked. Later picks are random again.
+fn placed_point(maze: &maze::Maze) -> Option<maze::Point> {
+    let unmarked = |p: maze::Point, bit: ThreadPaint| (maze.get(p.row, p.col) & bit) == 0;
+    if let Some(start) = maze.placed_start {
+        if unmarked(start, START_BIT) {
+            return Some(start);
+        }
+    }
+    let finish = maze.placed_finish?;
+    let started = match maze.placed_start {
+        Some(_) => true,
+        None => maze
+            .as_slice()
+            .iter()
+            .any(|&s| (s & maze::PATH_BIT) != 0 && is_start(s)),
+    };
+    match started && unmarked(finish, FINISH_BIT) {
+        true => Some(finish),
+        false => None,
+    }
+}
+
 pub fn find_nearest_square(maze: &maze::Maze, choice: maze::Point) -> maze::Point {
     for p in &maze::ALL_DIRECTIONS {
         let next = maze::Point {
@@ -166,13 +321,23 @@ pub fn find_nearest_square(maze: &maze::Maze, choice: maze::Point) -> maze::Poin
     print::maze_panic!("Could not place a point in this maze. Was it built correctly?");
 }
 
-///
-/// Playback and animation based logic for interacting with TUI buffer.
-///
+//
+// Playback and animation based logic for interacting with TUI buffer.
+//
 
 pub fn decode_square(wall_row: &[char], square: maze::Square) -> Cell {
+    // Tunnels are walls with their own dashed glyph so they are caught before any other wall.
+    if maze::is_tunnel(square) {
+        Cell {
+            symbol: maze::tunnel_char(square).to_string(),
+            fg: RatColor::Reset,
+            bg: RatColor::Reset,
+            underline_color: RatColor::Reset,
+            modifier: Modifier::empty(),
+            skip: false,
+        }
     // We have some special printing for the finish square. Not here.
-    if is_finish(square) {
+    } else if is_finish(square) {
         Cell {
             symbol: 'F'.to_string(),
             fg: RatColor::Indexed(ANSI_CYN),
@@ -190,6 +355,16 @@ pub fn decode_square(wall_row: &[char], square: maze::Square) -> Cell {
             modifier: Modifier::BOLD,
             skip: false,
         }
+    // A marked square shows how many times it has been walked instead of a block of paint.
+    } else if marks(square) != 0 {
+        Cell {
+            symbol: marks(square).to_string(),
+            fg: thread_rgb(square),
+            bg: RatColor::Reset,
+            underline_color: RatColor::Reset,
+            modifier: Modifier::BOLD,
+            skip: false,
+        }
     } else if is_color(square) {
         Cell {
             symbol: "█".to_string(),
@@ -226,7 +401,7 @@ pub fn decode_square(wall_row: &[char], square: maze::Square) -> Cell {
 pub fn decode_mini_path(maze: &maze::Blueprint, p: maze::Point) -> Cell {
     let square = maze.get(p.row, p.col);
     let this_color = thread_rgb(square);
-    if is_start_or_finish(square) {
+    if maze::is_path(square) && is_start_or_finish(square) {
         return Cell {
             symbol: '▀'.to_string(),
             fg: RatColor::Indexed(ANSI_CYN),
@@ -0,0 +1,71 @@
+use crate::solve;
+
+use rand::prelude::*;
+
+// Every square shows its mark count and the paint deepens with the second mark so a glance shows
+// which passages are spent.
+const ONE_MARK_PAINT: solve::ThreadPaint = 0x009531;
+const TWO_MARKS_PAINT: solve::ThreadPaint = 0x880044;
+
+//
+// Trémaux's algorithm is what a person with a piece of chalk does. Every square walked gets a
+// mark. Unmarked squares are always preferred, and when none are left the walker turns back along
+// its own marks, marking them a second time. A square with two marks is never entered again, so
+// when the finish is found the squares with exactly one mark are the way back to the start.
+//
+
+pub fn hunt_history(monitor: monitor::MazeMonitor) {
+    let mut lk = match monitor.lock() {
+        Ok(lk) => lk,
+        Err(p) => print::maze_panic!("Thread panicked: {}", p),
+    };
+    let (start, _) = solve::set_start_and_finish_history(&mut lk.maze);
+    let mut rng = solve::seeded_thread_rng(&lk.maze, 0);
+    let mut marks = vec![0u8; (lk.maze.rows() * lk.maze.cols()) as usize];
+    let cols = lk.maze.cols();
+    let at = |p: maze::Point| (p.row * cols + p.col) as usize;
+    // The walker only needs the marks to find its way back but the trail saves searching for them.
+    let mut trail = vec![start];
+    mark(&mut lk.maze, &mut marks[at(start)], start);
+    let mut directions = maze::CARDINAL_DIRECTIONS;
+    while let Some(&cur) = trail.last() {
+        if solve::is_finish(lk.maze.get(cur.row, cur.col)) {
+            return;
+        }
+        directions.shuffle(&mut rng);
+        match directions
+            .iter()
+            .map(|p| lk.maze.step(cur, *p))
+            .find(|&next| lk.maze.path_at(next.row, next.col) && marks[at(next)] == 0)
+        {
+            Some(next) => {
+                mark(&mut lk.maze, &mut marks[at(next)], next);
+                trail.push(next);
+            }
+            None => {
+                mark(&mut lk.maze, &mut marks[at(cur)], cur);
+                trail.pop();
+            }
+        }
+    }
+}
+
+fn mark(maze: &mut maze::Maze, count: &mut u8, p: maze::Point) {
+    *count += 1;
+    let square = maze.get(p.row, p.col);
+    let paint = if *count == 1 {
+        ONE_MARK_PAINT
+    } else {
+        TWO_MARKS_PAINT
+    };
+    let after = (square & !(solve::THREAD_MASK | solve::MARK_MASK))
+        | paint
+        | ((*count as maze::Square) << solve::MARK_SHIFT);
+    maze.solve_history.push(maze::Delta {
+        id: p,
+        before: square,
+        after,
+        burst: 1,
+    });
+    *maze.get_mut(p.row, p.col) = after;
+}
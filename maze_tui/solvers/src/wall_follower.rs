@@ -0,0 +1,167 @@
+use crate::solve;
+
+use std::collections::HashSet;
+
+const WALKER_PAINT: solve::ThreadPaint = 0x009531;
+// A walker that comes back to a square facing the same way it once did will repeat itself forever.
+// The loop from that first visit on is repainted in this color to report the finish is unreachable.
+const TRAPPED_PAINT: solve::ThreadPaint = 0xFF0000;
+
+//
+// The wall followers are the strategy most people try first: keep one hand on a wall and walk.
+// They always succeed in a perfect maze, but modifications such as cross add cycles and a walker
+// that starts next to a wall island will circle it forever. The walker remembers every square and
+// heading it has been in so it can notice the second lap and stop.
+//
+
+#[derive(Clone, Copy)]
+enum Hand {
+    Left,
+    Right,
+}
+
+pub fn left_hand_history(monitor: monitor::MazeMonitor) {
+    follower_history(monitor, Hand::Left);
+}
+
+pub fn right_hand_history(monitor: monitor::MazeMonitor) {
+    follower_history(monitor, Hand::Right);
+}
+
+fn follower_history(monitor: monitor::MazeMonitor, hand: Hand) {
+    let mut lk = match monitor.lock() {
+        Ok(lk) => lk,
+        Err(p) => print::maze_panic!("Thread panicked: {}", p),
+    };
+    let (start, _) = solve::set_start_and_finish_history(&mut lk.maze);
+    // Cardinal directions run clockwise so the hand side is one turn away from the heading.
+    let turns: [usize; solve::NUM_DIRECTIONS] = match hand {
+        Hand::Left => [3, 0, 1, 2],
+        Hand::Right => [1, 0, 3, 2],
+    };
+    let mut cur = start;
+    let mut heading = 0;
+    let mut seen = HashSet::from([(cur, heading)]);
+    let mut walk = vec![(cur, heading)];
+    paint(&mut lk.maze, cur, WALKER_PAINT);
+    loop {
+        let next_heading = match turns
+            .iter()
+            .map(|t| (heading + t) % solve::NUM_DIRECTIONS)
+            .find(|&d| {
+                let next = lk.maze.step(cur, maze::CARDINAL_DIRECTIONS[d]);
+                lk.maze.path_at(next.row, next.col)
+            }) {
+            Some(d) => d,
+            // A walled in start has nowhere to go but it is also not a loop.
+            None => return,
+        };
+        cur = lk.maze.step(cur, maze::CARDINAL_DIRECTIONS[next_heading]);
+        heading = next_heading;
+        paint(&mut lk.maze, cur, WALKER_PAINT);
+        if solve::is_finish(lk.maze.get(cur.row, cur.col)) {
+            return;
+        }
+        if !seen.insert((cur, heading)) {
+            let lap = walk
+                .iter()
+                .position(|&step| step == (cur, heading))
+                .unwrap_or(0);
+            for &(p, _) in walk[lap..].iter() {
+                let square = lk.maze.get(p.row, p.col);
+                let after = (square & !solve::THREAD_MASK) | TRAPPED_PAINT;
+                if after != square {
+                    lk.maze.solve_history.push(maze::Delta {
+                        id: p,
+                        before: square,
+                        after,
+                        burst: 1,
+                    });
+                    *lk.maze.get_mut(p.row, p.col) = after;
+                }
+            }
+            return;
+        }
+        walk.push((cur, heading));
+    }
+}
+
+// Retracing paint that is already there is not recorded so playback never stalls on no-ops.
+fn paint(maze: &mut maze::Maze, p: maze::Point, paint: solve::ThreadPaint) {
+    let square = maze.get(p.row, p.col);
+    let after = (square & !solve::THREAD_MASK) | paint;
+    if after == square {
+        return;
+    }
+    maze.solve_history.push(maze::Delta {
+        id: p,
+        before: square,
+        after,
+        burst: 1,
+    });
+    *maze.get_mut(p.row, p.col) = after;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // S sits on a loop around a single wall and F is walled off where no hand can reach it.
+    const MAZE: [&str; 7] = [
+        "#############",
+        "#S..#########",
+        "#.#.#########",
+        "#...####....#",
+        "#######.###.#",
+        "#######...F.#",
+        "#############",
+    ];
+
+    fn drawn(art: &[&str]) -> monitor::MazeMonitor {
+        let mut maze = maze::Maze::new(maze::MazeArgs {
+            odd_rows: art.len() as i32,
+            odd_cols: art[0].len() as i32,
+            ..Default::default()
+        });
+        assert_eq!((maze.rows(), maze.cols()), (7, 13));
+        for (row, line) in art.iter().enumerate() {
+            for (col, ch) in line.chars().enumerate() {
+                let p = maze::Point {
+                    row: row as i32,
+                    col: col as i32,
+                };
+                match ch {
+                    '#' => continue,
+                    'S' => maze.placed_start = Some(p),
+                    'F' => maze.placed_finish = Some(p),
+                    _ => {}
+                }
+                *maze.get_mut(p.row, p.col) = maze::PATH_BIT;
+            }
+        }
+        monitor::Monitor::new(maze)
+    }
+
+    #[test]
+    fn start_on_a_loop_away_from_the_finish_stops_trapped() {
+        for hand in [Hand::Left, Hand::Right] {
+            let monitor = drawn(&MAZE);
+            follower_history(monitor.clone(), hand);
+            let lk = monitor.lock().unwrap();
+            for (row, line) in MAZE.iter().enumerate() {
+                for (col, ch) in line.chars().enumerate() {
+                    let square = lk.maze.get(row as i32, col as i32);
+                    let painted = square & solve::THREAD_MASK;
+                    if ch != '#' && row <= 3 && col <= 3 {
+                        assert_eq!(painted, TRAPPED_PAINT, "({}, {})", row, col);
+                    } else {
+                        assert_eq!(painted, 0, "({}, {})", row, col);
+                    }
+                }
+            }
+            let last = lk.maze.solve_history.len() - 1;
+            let last = lk.maze.solve_history[last].after;
+            assert_eq!(last & solve::THREAD_MASK, TRAPPED_PAINT);
+        }
+    }
+}
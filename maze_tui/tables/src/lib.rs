@@ -1,26 +1,41 @@
+pub use builders::aldous_broder;
 pub use builders::arena;
+pub use builders::binary_tree;
+pub use builders::caves;
 pub use builders::eller;
 pub use builders::grid;
+pub use builders::growing_tree;
 pub use builders::hunt_kill;
 pub use builders::kruskal;
 pub use builders::modify;
 pub use builders::prim;
 pub use builders::recursive_backtracker;
 pub use builders::recursive_subdivision;
+pub use builders::sidewinder;
+pub use builders::text;
+pub use builders::weave;
 pub use builders::wilson_adder;
 pub use builders::wilson_carver;
 pub use monitor;
 pub use painters::distance;
 pub use painters::rgb;
 pub use painters::runs;
+pub use solvers::astar;
 pub use solvers::bfs;
+pub use solvers::bidirectional;
+pub use solvers::cul_de_sac;
+pub use solvers::dead_end;
 pub use solvers::dfs;
+pub use solvers::dijkstra;
 pub use solvers::floodfs;
 pub use solvers::rdfs;
 pub use solvers::solve;
+pub use solvers::tremaux;
+pub use solvers::wall_follower;
 
 pub type BuildHistoryFunction = fn(monitor::MazeMonitor);
 pub type SolveHistoryFunction = fn(monitor::MazeMonitor);
+pub type ArgCheck = fn(&str) -> Result<(), String>;
 
 pub struct FlagArg<'a, 'b> {
     pub flag: &'a str,
@@ -33,12 +48,30 @@ pub enum ViewingMode {
     AnimatedPlayback,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct HistoryRunner {
     pub args: maze::MazeArgs,
     pub build: BuildHistoryFunction,
     pub modify: Option<BuildHistoryFunction>,
+    // Some algorithms accept an argument after a colon such as `-b growing-tree:newest=0.5`.
+    pub build_arg: Option<String>,
+    pub modify_arg: Option<String>,
+    // Only builders listed in MASK_BUILDERS know how to stay inside a mask.
+    pub mask: Option<maze::mask::Mask>,
+    // A message pressed into the maze after building. Mask builders grow around it instead.
+    pub text: Option<String>,
     pub solve: SolveHistoryFunction,
+    // A loaded maze replaces the builder and modification entirely and goes straight to solving.
+    pub load: Option<maze::Blueprint>,
+    pub save: Option<std::path::PathBuf>,
+    // A replay already holds both recorded Tapes so no algorithm runs at all.
+    pub replay: Option<maze::Maze>,
+    pub record: Option<std::path::PathBuf>,
+    pub svg: Option<std::path::PathBuf>,
+    // Time between animation frames set with -ba and -sa. Without them each phase plays at the
+    // default tick.
+    pub build_speed: Option<std::time::Duration>,
+    pub solve_speed: Option<std::time::Duration>,
 }
 
 impl HistoryRunner {
@@ -49,10 +82,23 @@ impl HistoryRunner {
                 odd_cols: 111,
                 offset: maze::Offset::default(),
                 style: maze::MazeStyle::Sharp,
+                seed: 0,
+                topology: maze::topology::Topology::Square,
             },
             build: recursive_backtracker::generate_history,
             modify: None,
+            build_arg: None,
+            modify_arg: None,
+            mask: None,
+            text: None,
             solve: dfs::hunt_history,
+            load: None,
+            save: None,
+            replay: None,
+            record: None,
+            svg: None,
+            build_speed: None,
+            solve_speed: None,
         }
     }
 }
@@ -73,20 +119,54 @@ where
         .map(|(_, t)| t.clone())
 }
 
+pub fn check_arg(func: &BuildHistoryFunction, arg: &str) -> Result<(), String> {
+    match ALGORITHM_ARGS
+        .iter()
+        .find(|(f, _)| std::ptr::fn_addr_eq(*f, *func))
+    {
+        Some((_, check)) => check(arg),
+        None => Err(format!("algorithm takes no arg[{}]", arg)),
+    }
+}
+
+pub fn supports_mask(func: &BuildHistoryFunction) -> bool {
+    MASK_BUILDERS
+        .iter()
+        .any(|f| std::ptr::fn_addr_eq(*f, *func))
+}
+
+pub fn supports_topology(func: &BuildHistoryFunction) -> bool {
+    TOPOLOGY_BUILDERS
+        .iter()
+        .any(|f| std::ptr::fn_addr_eq(*f, *func))
+}
+
 pub fn load_info(cur_builder: &BuildHistoryFunction) -> &'static str {
-    match DESCRIPTIONS.iter().find(|(func, _)| func == cur_builder) {
+    match DESCRIPTIONS
+        .iter()
+        .find(|(func, _)| std::ptr::fn_addr_eq(*func, *cur_builder))
+    {
         Some(&(_, desc)) => desc,
         None => "Coming Soon!",
     }
 }
 
-pub const FLAGS: [(&str, &str); 6] = [
+pub const FLAGS: [(&str, &str); 15] = [
     ("-b", "-b"),
     ("-m", "-m"),
     ("-s", "-s"),
     ("-w", "-w"),
     ("-sa", "-sa"),
     ("-ba", "-ba"),
+    ("-seed", "-seed"),
+    ("-save", "-save"),
+    ("-load", "-load"),
+    ("-record", "-record"),
+    ("-replay", "-replay"),
+    ("-svg", "-svg"),
+    ("-mask", "-mask"),
+    ("-text", "-text"),
+    ("-topology", "-topology"),
 ];
 
 pub const WALL_STYLES: [(&str, maze::MazeStyle); 8] = [
@@ -100,11 +180,18 @@ pub const WALL_STYLES: [(&str, maze::MazeStyle); 8] = [
     ("spikes", maze::MazeStyle::Spikes),
 ];
 
-///
-/// History and playback specific tables
-///
+pub const TOPOLOGIES: [(&str, maze::topology::Topology); 4] = [
+    ("square", maze::topology::Topology::Square),
+    ("hex", maze::topology::Topology::Hex),
+    ("triangle", maze::topology::Topology::Triangle),
+    ("polar", maze::topology::Topology::Polar),
+];
+
+//
+// History and playback specific tables
+//
 
-pub const HISTORY_BUILDERS: [(&str, BuildHistoryFunction); 10] = [
+pub const HISTORY_BUILDERS: [(&str, BuildHistoryFunction); 16] = [
     ("arena", arena::generate_history),
     ("rdfs", recursive_backtracker::generate_history),
     ("hunt-kill", hunt_kill::generate_history),
@@ -115,14 +202,46 @@ pub const HISTORY_BUILDERS: [(&str, BuildHistoryFunction); 10] = [
     ("wilson", wilson_carver::generate_history),
     ("wilson-walls", wilson_adder::generate_history),
     ("grid", grid::generate_history),
+    ("aldous-broder", aldous_broder::generate_history),
+    ("binary-tree", binary_tree::generate_history),
+    ("sidewinder", sidewinder::generate_history),
+    ("growing-tree", growing_tree::generate_history),
+    ("caves", caves::generate_history),
+    ("weave", weave::generate_history),
+];
+
+// Only the algorithms listed here accept an argument. The check runs while parsing flags so a bad
+// argument is reported before any building starts.
+pub static ALGORITHM_ARGS: [(BuildHistoryFunction, ArgCheck); 5] = [
+    (growing_tree::generate_history, growing_tree::check_policy),
+    (caves::generate_history, caves::check_rule),
+    (weave::generate_history, weave::check_density),
+    (modify::braid_history, modify::check_braid),
+    (modify::add_rooms_history, modify::check_rooms),
 ];
 
-pub const HISTORY_MODIFICATIONS: [(&str, BuildHistoryFunction); 2] = [
+pub static MASK_BUILDERS: [BuildHistoryFunction; 5] = [
+    recursive_backtracker::generate_history,
+    kruskal::generate_history,
+    prim::generate_history,
+    wilson_carver::generate_history,
+    growing_tree::generate_history,
+];
+
+// Every builder works on square grids. Only these know how to build hex and triangle grids.
+pub static TOPOLOGY_BUILDERS: [BuildHistoryFunction; 2] = [
+    recursive_backtracker::generate_history,
+    kruskal::generate_history,
+];
+
+pub const HISTORY_MODIFICATIONS: [(&str, BuildHistoryFunction); 4] = [
     ("cross", modify::add_cross_history),
     ("x", modify::add_x_history),
+    ("braid", modify::braid_history),
+    ("rooms", modify::add_rooms_history),
 ];
 
-pub const HISTORY_SOLVERS: [(&str, SolveHistoryFunction); 14] = [
+pub const HISTORY_SOLVERS: [(&str, SolveHistoryFunction); 27] = [
     ("dfs-hunt", dfs::hunt_history),
     ("dfs-gather", dfs::gather_history),
     ("dfs-corner", dfs::corner_history),
@@ -132,18 +251,43 @@ pub const HISTORY_SOLVERS: [(&str, SolveHistoryFunction); 14] = [
     ("bfs-hunt", bfs::hunt_history),
     ("bfs-gather", bfs::gather_history),
     ("bfs-corner", bfs::corner_history),
+    ("bfs-bidirectional", bidirectional::bfs_history),
+    ("dfs-bidirectional", bidirectional::dfs_history),
     ("floodfs-hunt", floodfs::hunt_history),
     ("floodfs-gather", floodfs::gather_history),
     ("floodfs-corner", floodfs::corner_history),
+    ("dijkstra-hunt", dijkstra::hunt_history),
+    ("dijkstra-gather", dijkstra::gather_history),
+    ("dijkstra-corner", dijkstra::corner_history),
+    ("astar-hunt", astar::hunt_history),
+    ("astar-gather", astar::gather_history),
+    ("astar-corner", astar::corner_history),
+    ("left-hand", wall_follower::left_hand_history),
+    ("right-hand", wall_follower::right_hand_history),
+    ("tremaux", tremaux::hunt_history),
+    ("dead-end-fill", dead_end::fill_history),
+    ("cul-de-sac-fill", cul_de_sac::fill_history),
     ("distance", distance::paint_distance_from_center_history),
     ("runs", runs::paint_run_lengths_history),
 ];
 
-pub static DESCRIPTIONS: [(BuildHistoryFunction, &str); 10] = [
+pub static DESCRIPTIONS: [(BuildHistoryFunction, &str); 16] = [
+    (
+        builders::aldous_broder::generate_history,
+        include_str!("../../res/aldous_broder.txt"),
+    ),
     (
         builders::arena::generate_history,
         include_str!("../../res/arena.txt"),
     ),
+    (
+        builders::binary_tree::generate_history,
+        include_str!("../../res/binary_tree.txt"),
+    ),
+    (
+        builders::caves::generate_history,
+        include_str!("../../res/caves.txt"),
+    ),
     (
         builders::eller::generate_history,
         include_str!("../../res/eller.txt"),
@@ -152,6 +296,10 @@ pub static DESCRIPTIONS: [(BuildHistoryFunction, &str); 10] = [
         builders::grid::generate_history,
         include_str!("../../res/grid.txt"),
     ),
+    (
+        builders::growing_tree::generate_history,
+        include_str!("../../res/growing_tree.txt"),
+    ),
     (
         builders::hunt_kill::generate_history,
         include_str!("../../res/hunt_kill.txt"),
@@ -172,6 +320,10 @@ pub static DESCRIPTIONS: [(BuildHistoryFunction, &str); 10] = [
         builders::recursive_subdivision::generate_history,
         include_str!("../../res/recursive_subdivision.txt"),
     ),
+    (
+        builders::sidewinder::generate_history,
+        include_str!("../../res/sidewinder.txt"),
+    ),
     (
         builders::wilson_adder::generate_history,
         include_str!("../../res/wilson_adder.txt"),
@@ -180,4 +332,8 @@ pub static DESCRIPTIONS: [(BuildHistoryFunction, &str); 10] = [
         builders::wilson_carver::generate_history,
         include_str!("../../res/wilson_carver.txt"),
     ),
+    (
+        builders::weave::generate_history,
+        include_str!("../../res/weave.txt"),
+    ),
 ];